@@ -1,8 +1,8 @@
 use sp_core::{U256, Pair, Public, H160, sr25519};
 use sgc_runtime::{
     AccountId, AuraConfig, BalancesConfig, EVMConfig, EthereumConfig, GenesisConfig, GrandpaConfig,
-    ContractsConfig, SudoConfig, SystemConfig, TokensConfig, WASM_BINARY, Signature, Balance, DOLLARS,
-    TokenSymbol, CurrencyId,
+    ContractsConfig, SudoConfig, SystemConfig, TokensConfig, CurrencyTokenConfig, DexConfig, WASM_BINARY, Signature,
+    Balance, DOLLARS, TokenSymbol, CurrencyId,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -256,5 +256,21 @@ fn testnet_genesis(
               })
               .collect(),
           }),
+        pallet_currency_token: Some(CurrencyTokenConfig {
+            currency_tao: Some(b"SGC Currencies".to_vec()),
+            currencies: vec![
+                CurrencyId::Token(TokenSymbol::SGC),
+                CurrencyId::Token(TokenSymbol::DOT),
+                CurrencyId::Token(TokenSymbol::ACA),
+                CurrencyId::Token(TokenSymbol::AUSD),
+            ],
+            assets: vec![
+                (CurrencyId::Token(TokenSymbol::SGC), b"SGC".to_vec(), b"SGC".to_vec(), 18, 0),
+                (CurrencyId::Token(TokenSymbol::DOT), b"Polkadot".to_vec(), b"DOT".to_vec(), 10, 0),
+                (CurrencyId::Token(TokenSymbol::ACA), b"Acala".to_vec(), b"ACA".to_vec(), 13, 0),
+                (CurrencyId::Token(TokenSymbol::AUSD), b"Acala Dollar".to_vec(), b"AUSD".to_vec(), 12, 0),
+            ],
+        }),
+        pallet_dex: Some(DexConfig::default()),
     }
 }
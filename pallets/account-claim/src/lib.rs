@@ -0,0 +1,246 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Encode;
+use sp_core::{H160, ecdsa};
+use sp_io::{crypto::secp256k1_ecdsa_recover, hashing::keccak_256};
+use sp_runtime::traits::{Convert, Zero};
+use frame_support::{
+	ensure,
+	dispatch::DispatchResult,
+	traits::{Currency, ExistenceRequirement, ReservableCurrency},
+	transactional,
+};
+use orml_traits::MergeAccount;
+use pallet_evm::AddressMapping;
+use sp_std::{marker::PhantomData, prelude::*};
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub type EcdsaSignature = ecdsa::Signature;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The native currency, merged from the unbound account of an address when it is claimed.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The other assets of the unbound account of an address, such as `orml_tokens` currencies
+		/// and ERC-1155 tokens, merged when it is claimed.
+		type MergeAccount: MergeAccount<Self::AccountId>;
+
+		/// The mapping of addresses which have not been claimed.
+		type FallbackAddressMapping: AddressMapping<Self::AccountId>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	#[pallet::getter(fn accounts)]
+	pub(super) type Accounts<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		H160,
+		T::AccountId
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn evm_addresses)]
+	pub(super) type EvmAddresses<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		H160
+	>;
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		AccountClaimed(T::AccountId, H160),
+		AccountUnbound(T::AccountId, H160),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		AccountAlreadyBound,
+		EvmAddressAlreadyBound,
+		BadSignature,
+		InvalidSignature,
+		AccountNotBound,
+		/// The unbound account of the address has a reserved balance, which cannot be moved.
+		FallbackBalanceReserved,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+
+		/// Bind `evm_address` to the sender, proven by an EIP-191 `signature` of `claim_message`.
+		///
+		/// The assets of the address' fallback account are moved to the sender: its native balance
+		/// and whatever `MergeAccount` moves. Fails while any of the native balance is reserved.
+		#[pallet::weight(10_000)]
+		pub fn claim_account(
+			origin: OriginFor<T>,
+			evm_address: H160,
+			signature: EcdsaSignature,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_claim_account(&who, evm_address, signature)?;
+
+			Ok(().into())
+		}
+
+		/// Unbind the sender from its address, which maps to its fallback account again.
+		///
+		/// Assets stay with the sender; nothing is moved back to the fallback account.
+		#[pallet::weight(10_000)]
+		pub fn unbind(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_unbind(&who)?;
+
+			Ok(().into())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	#[transactional]
+	pub fn do_claim_account(who: &T::AccountId, evm_address: H160, signature: EcdsaSignature) -> DispatchResult {
+		ensure!(!EvmAddresses::<T>::contains_key(who), Error::<T>::AccountAlreadyBound);
+		ensure!(!Accounts::<T>::contains_key(evm_address), Error::<T>::EvmAddressAlreadyBound);
+
+		let signer = Self::eth_recover(&signature, &Self::claim_message(who)).ok_or(Error::<T>::BadSignature)?;
+		ensure!(signer == evm_address, Error::<T>::InvalidSignature);
+
+		let fallback = T::FallbackAddressMapping::into_account_id(evm_address);
+		if fallback != *who {
+			// Reserved for deposits of the fallback account, which stay its own
+			ensure!(T::Currency::reserved_balance(&fallback).is_zero(), Error::<T>::FallbackBalanceReserved);
+			T::MergeAccount::merge_account(&fallback, who)?;
+
+			let balance = T::Currency::free_balance(&fallback);
+			if !balance.is_zero() {
+				T::Currency::transfer(&fallback, who, balance, ExistenceRequirement::AllowDeath)?;
+			}
+		}
+
+		Accounts::<T>::insert(evm_address, who.clone());
+		EvmAddresses::<T>::insert(who, evm_address);
+
+		Self::deposit_event(Event::AccountClaimed(who.clone(), evm_address));
+
+		Ok(())
+	}
+
+	pub fn do_unbind(who: &T::AccountId) -> DispatchResult {
+		let evm_address = EvmAddresses::<T>::take(who).ok_or(Error::<T>::AccountNotBound)?;
+		Accounts::<T>::remove(evm_address);
+
+		Self::deposit_event(Event::AccountUnbound(who.clone(), evm_address));
+
+		Ok(())
+	}
+
+	/// The message an address signs to be bound to `who`.
+	///
+	/// It names the genesis hash so a signature cannot be replayed on another chain.
+	pub fn claim_message(who: &T::AccountId) -> Vec<u8> {
+		let genesis_hash = frame_system::Module::<T>::block_hash(T::BlockNumber::zero());
+
+		let mut message = b"Bind SGC account 0x".to_vec();
+		message.extend(to_hex(&who.encode()));
+		message.extend_from_slice(b" on chain 0x");
+		message.extend(to_hex(genesis_hash.as_ref()));
+		message
+	}
+
+	/// The EIP-191 `personal_sign` digest of `message`.
+	pub fn eth_signable_digest(message: &[u8]) -> [u8; 32] {
+		let mut data = b"\x19Ethereum Signed Message:\n".to_vec();
+		data.extend(to_decimal(message.len()));
+		data.extend_from_slice(message);
+		keccak_256(&data)
+	}
+
+	/// The address which signed `message`.
+	pub fn eth_recover(signature: &EcdsaSignature, message: &[u8]) -> Option<H160> {
+		let digest = Self::eth_signable_digest(message);
+		secp256k1_ecdsa_recover(&signature.0, &digest)
+			.ok()
+			.map(|public| H160::from_slice(&keccak_256(&public)[12..]))
+	}
+}
+
+/// Maps claimed addresses to their account and every other address through `FallbackAddressMapping`.
+pub struct EvmAddressMapping<T>(PhantomData<T>);
+
+impl<T: Config> AddressMapping<T::AccountId> for EvmAddressMapping<T> {
+	fn into_account_id(address: H160) -> T::AccountId {
+		Accounts::<T>::get(address).unwrap_or_else(|| T::FallbackAddressMapping::into_account_id(address))
+	}
+}
+
+/// The address an account acts as in the EVM: its claimed address, or else the leading 20 bytes of the account.
+///
+/// Accounts which cannot claim an address, such as contracts, act as their truncated account. The
+/// truncated address is bound to the account in `Accounts` the first time it is converted, so
+/// `EvmAddressMapping` maps it back: what EVM contracts send to `msg.sender` reaches the account and
+/// calls back into `pallet_contracts` come from it. The account can still claim an address later.
+pub struct EvmAddressOf<T>(PhantomData<T>);
+
+impl<T: Config> Convert<T::AccountId, H160> for EvmAddressOf<T> {
+	fn convert(who: T::AccountId) -> H160 {
+		EvmAddresses::<T>::get(&who).unwrap_or_else(|| {
+			let encoded = who.encode();
+			let len = encoded.len().min(20);
+			let mut address = H160::zero();
+			address.as_bytes_mut()[..len].copy_from_slice(&encoded[..len]);
+
+			// Only the key of the address, or an account of the same leading 20 bytes, could have bound it
+			if !Accounts::<T>::contains_key(address) {
+				Accounts::<T>::insert(address, who);
+			}
+			address
+		})
+	}
+}
+
+fn to_hex(data: &[u8]) -> Vec<u8> {
+	const HEX: &[u8; 16] = b"0123456789abcdef";
+	data.iter()
+		.flat_map(|b| vec![HEX[(b >> 4) as usize], HEX[(b & 0xf) as usize]])
+		.collect()
+}
+
+fn to_decimal(mut n: usize) -> Vec<u8> {
+	let mut digits = Vec::new();
+	loop {
+		digits.push(b'0' + (n % 10) as u8);
+		n /= 10;
+		if n == 0 {
+			break;
+		}
+	}
+	digits.reverse();
+	digits
+}
@@ -0,0 +1,132 @@
+use crate as pallet_account_claim;
+use sp_core::{H160, H256};
+use frame_support::{dispatch::DispatchResult, parameter_types};
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header,
+};
+use frame_system as system;
+use orml_traits::MergeAccount;
+use pallet_evm::AddressMapping;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Token: pallet_erc1155::{Module, Call, Storage, Event<T>},
+		AccountClaim: pallet_account_claim::{Module, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u128>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u128 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type Balance = u128;
+	type MaxLocks = ();
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+/// Maps an address to the account of its first 8 bytes.
+pub struct TruncatedAddressMapping;
+
+impl AddressMapping<u64> for TruncatedAddressMapping {
+	fn into_account_id(address: H160) -> u64 {
+		let mut bytes = [0u8; 8];
+		bytes.copy_from_slice(&address[0..8]);
+		u64::from_be_bytes(bytes)
+	}
+}
+
+parameter_types! {
+	pub const AttributeDepositBase: u128 = 10;
+	pub const DepositPerByte: u128 = 1;
+	pub const KeyLimit: u32 = 32;
+	pub const ValueLimit: u32 = 64;
+}
+
+impl pallet_erc1155::Config for Test {
+	type Event = Event;
+	type TokenId = u64;
+	type TaoId = u64;
+	type Currency = Balances;
+	type AttributeDepositBase = AttributeDepositBase;
+	type DepositPerByte = DepositPerByte;
+	type KeyLimit = KeyLimit;
+	type ValueLimit = ValueLimit;
+	type WeightInfo = ();
+}
+
+/// Merges the ERC-1155 balances of the fallback account.
+pub struct MergeTokens;
+
+impl MergeAccount<u64> for MergeTokens {
+	fn merge_account(source: &u64, dest: &u64) -> DispatchResult {
+		Token::do_merge_account(source, dest)
+	}
+}
+
+impl pallet_account_claim::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type MergeAccount = MergeTokens;
+	type FallbackAddressMapping = TruncatedAddressMapping;
+}
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, 1000), (BOB, 1000)],
+	}
+		.assimilate_storage(&mut t)
+		.unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,191 @@
+use crate::{Error, EcdsaSignature, EvmAddressMapping, EvmAddressOf, mock::*};
+use frame_support::{assert_ok, assert_noop, traits::{Currency, ReservableCurrency}};
+use pallet_evm::AddressMapping;
+use sp_runtime::traits::Convert;
+use sp_core::H160;
+use sp_io::hashing::keccak_256;
+
+fn secret_key(seed: &[u8]) -> secp256k1::SecretKey {
+	secp256k1::SecretKey::parse(&keccak_256(seed)).unwrap()
+}
+
+fn eth_address(secret: &secp256k1::SecretKey) -> H160 {
+	let public = secp256k1::PublicKey::from_secret_key(secret);
+	H160::from_slice(&keccak_256(&public.serialize()[1..65])[12..])
+}
+
+// Sign like `personal_sign`, with the recovery id as 27/28
+fn eth_sign(secret: &secp256k1::SecretKey, message: &[u8]) -> EcdsaSignature {
+	let digest = AccountClaim::eth_signable_digest(message);
+	let (signature, recovery_id) = secp256k1::sign(&secp256k1::Message::parse(&digest), secret);
+	let mut raw = [0u8; 65];
+	raw[0..64].copy_from_slice(&signature.serialize()[..]);
+	raw[64] = recovery_id.serialize() + 27;
+	EcdsaSignature::from_raw(raw)
+}
+
+#[test]
+fn claim_account_works() {
+	new_test_ext().execute_with(|| {
+		let alice_key = secret_key(b"Alice");
+		let address = eth_address(&alice_key);
+		let signature = eth_sign(&alice_key, &AccountClaim::claim_message(&ALICE));
+
+		assert_eq!(EvmAddressMapping::<Test>::into_account_id(address), TruncatedAddressMapping::into_account_id(address));
+
+		assert_ok!(AccountClaim::claim_account(Origin::signed(ALICE), address, signature));
+
+		assert_eq!(AccountClaim::accounts(address), Some(ALICE));
+		assert_eq!(AccountClaim::evm_addresses(ALICE), Some(address));
+		assert_eq!(EvmAddressMapping::<Test>::into_account_id(address), ALICE);
+	})
+}
+
+#[test]
+fn claim_account_merges_fallback_balance() {
+	new_test_ext().execute_with(|| {
+		let alice_key = secret_key(b"Alice");
+		let address = eth_address(&alice_key);
+		let fallback = TruncatedAddressMapping::into_account_id(address);
+		Balances::make_free_balance_be(&fallback, 500);
+
+		let signature = eth_sign(&alice_key, &AccountClaim::claim_message(&ALICE));
+		assert_ok!(AccountClaim::claim_account(Origin::signed(ALICE), address, signature));
+
+		assert_eq!(Balances::free_balance(fallback), 0);
+		assert_eq!(Balances::free_balance(ALICE), 1500);
+	})
+}
+
+#[test]
+fn claim_account_merges_fallback_tokens() {
+	new_test_ext().execute_with(|| {
+		let alice_key = secret_key(b"Alice");
+		let address = eth_address(&alice_key);
+		let fallback = TruncatedAddressMapping::into_account_id(address);
+		let tao_id = Token::do_create_tao(&BOB, vec![]).unwrap();
+		assert_ok!(Token::do_mint(&fallback, tao_id, 1, 10));
+		assert_ok!(Token::do_mint(&fallback, tao_id, 2, 1));
+		assert_ok!(Token::do_mint(&ALICE, tao_id, 1, 5));
+
+		let signature = eth_sign(&alice_key, &AccountClaim::claim_message(&ALICE));
+		assert_ok!(AccountClaim::claim_account(Origin::signed(ALICE), address, signature));
+
+		assert_eq!(Token::balance_of(&fallback, tao_id, 1), 0);
+		assert_eq!(Token::balance_of(&fallback, tao_id, 2), 0);
+		assert_eq!(Token::balance_of(&ALICE, tao_id, 1), 15);
+		assert_eq!(Token::balance_of(&ALICE, tao_id, 2), 1);
+	})
+}
+
+#[test]
+fn claim_account_fails_while_fallback_has_reserved_balance() {
+	new_test_ext().execute_with(|| {
+		let alice_key = secret_key(b"Alice");
+		let address = eth_address(&alice_key);
+		let fallback = TruncatedAddressMapping::into_account_id(address);
+		Balances::make_free_balance_be(&fallback, 500);
+		assert_ok!(Balances::reserve(&fallback, 100));
+
+		let signature = eth_sign(&alice_key, &AccountClaim::claim_message(&ALICE));
+		assert_noop!(
+			AccountClaim::claim_account(Origin::signed(ALICE), address, signature),
+			Error::<Test>::FallbackBalanceReserved,
+		);
+	})
+}
+
+#[test]
+fn claim_account_rejects_wrong_signer() {
+	new_test_ext().execute_with(|| {
+		let alice_key = secret_key(b"Alice");
+		let bob_key = secret_key(b"Bob");
+
+		// signed by another key
+		let signature = eth_sign(&bob_key, &AccountClaim::claim_message(&ALICE));
+		assert_noop!(
+			AccountClaim::claim_account(Origin::signed(ALICE), eth_address(&alice_key), signature),
+			Error::<Test>::InvalidSignature
+		);
+
+		// signed for another account
+		let signature = eth_sign(&alice_key, &AccountClaim::claim_message(&BOB));
+		assert_noop!(
+			AccountClaim::claim_account(Origin::signed(ALICE), eth_address(&alice_key), signature),
+			Error::<Test>::InvalidSignature
+		);
+
+		assert_noop!(
+			AccountClaim::claim_account(Origin::signed(ALICE), eth_address(&alice_key), EcdsaSignature::default()),
+			Error::<Test>::BadSignature
+		);
+	})
+}
+
+#[test]
+fn claim_account_binds_once() {
+	new_test_ext().execute_with(|| {
+		let alice_key = secret_key(b"Alice");
+		let bob_key = secret_key(b"Bob");
+
+		let signature = eth_sign(&alice_key, &AccountClaim::claim_message(&ALICE));
+		assert_ok!(AccountClaim::claim_account(Origin::signed(ALICE), eth_address(&alice_key), signature));
+
+		let signature = eth_sign(&bob_key, &AccountClaim::claim_message(&ALICE));
+		assert_noop!(
+			AccountClaim::claim_account(Origin::signed(ALICE), eth_address(&bob_key), signature),
+			Error::<Test>::AccountAlreadyBound
+		);
+
+		let signature = eth_sign(&alice_key, &AccountClaim::claim_message(&BOB));
+		assert_noop!(
+			AccountClaim::claim_account(Origin::signed(BOB), eth_address(&alice_key), signature),
+			Error::<Test>::EvmAddressAlreadyBound
+		);
+	})
+}
+
+#[test]
+fn unbind_works() {
+	new_test_ext().execute_with(|| {
+		let alice_key = secret_key(b"Alice");
+		let address = eth_address(&alice_key);
+
+		assert_noop!(AccountClaim::unbind(Origin::signed(ALICE)), Error::<Test>::AccountNotBound);
+
+		let signature = eth_sign(&alice_key, &AccountClaim::claim_message(&ALICE));
+		assert_ok!(AccountClaim::claim_account(Origin::signed(ALICE), address, signature));
+
+		// only the bound account can unbind
+		assert_noop!(AccountClaim::unbind(Origin::signed(BOB)), Error::<Test>::AccountNotBound);
+
+		assert_ok!(AccountClaim::unbind(Origin::signed(ALICE)));
+		assert_eq!(AccountClaim::accounts(address), None);
+		assert_eq!(AccountClaim::evm_addresses(ALICE), None);
+		assert_eq!(EvmAddressMapping::<Test>::into_account_id(address), TruncatedAddressMapping::into_account_id(address));
+
+		// the address is free to be claimed again, by another account
+		let signature = eth_sign(&alice_key, &AccountClaim::claim_message(&BOB));
+		assert_ok!(AccountClaim::claim_account(Origin::signed(BOB), address, signature));
+		assert_eq!(EvmAddressMapping::<Test>::into_account_id(address), BOB);
+	})
+}
+
+#[test]
+fn evm_address_of_prefers_claimed_address() {
+	new_test_ext().execute_with(|| {
+		let alice_key = secret_key(b"Alice");
+		let address = eth_address(&alice_key);
+
+		let mut truncated = H160::zero();
+		truncated.as_bytes_mut()[..8].copy_from_slice(&ALICE.to_le_bytes());
+		assert_eq!(EvmAddressOf::<Test>::convert(ALICE), truncated);
+		// bound on first use, so it maps back to the account
+		assert_eq!(EvmAddressMapping::<Test>::into_account_id(truncated), ALICE);
+
+		let signature = eth_sign(&alice_key, &AccountClaim::claim_message(&ALICE));
+		assert_ok!(AccountClaim::claim_account(Origin::signed(ALICE), address, signature));
+		assert_eq!(EvmAddressOf::<Test>::convert(ALICE), address);
+		assert_eq!(EvmAddressMapping::<Test>::into_account_id(truncated), ALICE);
+	})
+}
@@ -0,0 +1,295 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Encode, Decode};
+use sp_runtime::{
+	RuntimeDebug, ModuleId,
+	traits::{
+		AccountIdConversion, Hash, One, Zero,
+	},
+};
+use frame_support::{
+	ensure, transactional,
+	dispatch::{DispatchResult, DispatchError},
+	traits::{Currency, ExistenceRequirement, OnUnbalanced, WithdrawReasons},
+};
+use sp_std::{fmt::Debug, prelude::*};
+use primitives::Balance;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub type AirdropId = u32;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type NegativeImbalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + token::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		type ModuleId: Get<ModuleId>;
+
+		type Currency: Currency<Self::AccountId>;
+
+		/// The amount taken from the airdrop fund for every successful claim.
+		type ClaimFee: Get<BalanceOf<Self>>;
+
+		/// Where the claim fees go, such as the treasury.
+		type OnClaimFee: OnUnbalanced<NegativeImbalanceOf<Self>>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	#[pallet::getter(fn airdrops)]
+	pub(super) type Airdrops<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AirdropId,
+		Airdrop<T::TaoId, T::AccountId, T::Hash, BalanceOf<T>, T::BlockNumber>
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_airdrop_id)]
+	pub(super) type NextAirdropId<T: Config> = StorageValue<
+		_,
+		AirdropId,
+		ValueQuery
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn claimed_bitmap)]
+	pub(super) type ClaimedBitmap<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		AirdropId,
+		Twox64Concat,
+		u32,
+		u32,
+		ValueQuery
+	>;
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId", T::Hash = "Hash", BalanceOf<T> = "Balance")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		AirdropCreated(AirdropId, T::AccountId, T::TaoId, T::Hash, BalanceOf<T>),
+		Claimed(AirdropId, T::AccountId, T::TokenId, Balance),
+		Reclaimed(AirdropId, T::AccountId, BalanceOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		InvalidAirdropId,
+		NoAvailableAirdropId,
+		NotTaoOwner,
+		NotAirdropOwner,
+		InvalidExpiry,
+		AirdropExpired,
+		AirdropNotExpired,
+		AlreadyClaimed,
+		InvalidProof,
+		InsufficientFund,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+
+		#[pallet::weight(10_000)]
+		pub fn create_airdrop(
+			origin: OriginFor<T>,
+			tao_id: T::TaoId,
+			merkle_root: T::Hash,
+			deposit: BalanceOf<T>,
+			expires_at: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_create_airdrop(&who, tao_id, merkle_root, deposit, expires_at)?;
+
+			Ok(().into())
+		}
+
+		/// Claim the leaf `(index, who, token_id, amount)` of an airdrop.
+		///
+		/// A successful claim is paid for by the airdrop fund, not by the claimer, and its fee goes to
+		/// `Config::OnClaimFee`. A failed claim changes nothing.
+		#[pallet::weight(10_000)]
+		pub fn claim(
+			origin: OriginFor<T>,
+			airdrop_id: AirdropId,
+			index: u32,
+			token_id: T::TokenId,
+			amount: Balance,
+			proof: Vec<T::Hash>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_claim(&who, airdrop_id, index, token_id, amount, proof)?;
+
+			Ok(Pays::No.into())
+		}
+
+		#[pallet::weight(10_000)]
+		pub fn reclaim(origin: OriginFor<T>, airdrop_id: AirdropId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_reclaim(&who, airdrop_id)?;
+
+			Ok(().into())
+		}
+	}
+}
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct Airdrop<
+	TaoId: Encode + Decode + Clone + Debug + Eq + PartialEq,
+	AccountId: Encode + Decode + Clone + Debug + Eq + PartialEq,
+	Hash: Encode + Decode + Clone + Debug + Eq + PartialEq,
+	Balance: Encode + Decode + Clone + Debug + Eq + PartialEq,
+	BlockNumber: Encode + Decode + Clone + Debug + Eq + PartialEq,
+> {
+	/// The owner of Airdrop, who is also the owner of the tao
+	pub owner: AccountId,
+	/// The tao of the airdropped tokens
+	pub tao_id: TaoId,
+	/// The merkle root of `(index, account, token_id, amount)` leaves
+	pub merkle_root: Hash,
+	/// The funding deposit paid by the owner
+	pub deposit: Balance,
+	/// The block after which no more claims are accepted
+	pub expires_at: BlockNumber,
+	/// The fund account of Airdrop
+	pub vault: AccountId,
+}
+
+impl<T: Config> Pallet<T> {
+	pub fn do_create_airdrop(
+		who: &T::AccountId,
+		tao_id: T::TaoId,
+		merkle_root: T::Hash,
+		deposit: BalanceOf<T>,
+		expires_at: T::BlockNumber,
+	) -> Result<AirdropId, DispatchError> {
+		ensure!(token::Module::<T>::is_tao_owner(who, tao_id), Error::<T>::NotTaoOwner);
+		ensure!(expires_at > frame_system::Module::<T>::block_number(), Error::<T>::InvalidExpiry);
+
+		let airdrop_id =
+			NextAirdropId::<T>::try_mutate(|id| -> Result<AirdropId, DispatchError> {
+				let current_id = *id;
+				*id = id
+					.checked_add(One::one())
+					.ok_or(Error::<T>::NoAvailableAirdropId)?;
+				Ok(current_id)
+			})?;
+
+		let vault: T::AccountId = T::ModuleId::get().into_sub_account(airdrop_id);
+
+		T::Currency::transfer(who, &vault, deposit, ExistenceRequirement::KeepAlive)?;
+
+		Airdrops::<T>::insert(airdrop_id, Airdrop {
+			owner: who.clone(),
+			tao_id,
+			merkle_root,
+			deposit,
+			expires_at,
+			vault,
+		});
+
+		Self::deposit_event(Event::AirdropCreated(airdrop_id, who.clone(), tao_id, merkle_root, deposit));
+
+		Ok(airdrop_id)
+	}
+
+	#[transactional]
+	pub fn do_claim(
+		who: &T::AccountId,
+		airdrop_id: AirdropId,
+		index: u32,
+		token_id: T::TokenId,
+		amount: Balance,
+		proof: Vec<T::Hash>,
+	) -> DispatchResult {
+		let airdrop = Airdrops::<T>::get(airdrop_id).ok_or(Error::<T>::InvalidAirdropId)?;
+
+		ensure!(frame_system::Module::<T>::block_number() <= airdrop.expires_at, Error::<T>::AirdropExpired);
+		ensure!(!Self::is_claimed(airdrop_id, index), Error::<T>::AlreadyClaimed);
+
+		let leaf = Self::leaf_hash(index, who, token_id, amount);
+		ensure!(Self::verify_proof(airdrop.merkle_root, leaf, &proof), Error::<T>::InvalidProof);
+
+		// Pay for the claim out of the airdrop fund
+		let fee = T::ClaimFee::get();
+		if !fee.is_zero() {
+			let imbalance = T::Currency::withdraw(&airdrop.vault, fee, WithdrawReasons::FEE, ExistenceRequirement::AllowDeath)
+				.map_err(|_| Error::<T>::InsufficientFund)?;
+			T::OnClaimFee::on_unbalanced(imbalance);
+		}
+
+		ClaimedBitmap::<T>::mutate(airdrop_id, index / 32, |word| *word |= 1 << (index % 32));
+
+		token::Module::<T>::do_mint(who, airdrop.tao_id, token_id, amount)?;
+
+		Self::deposit_event(Event::Claimed(airdrop_id, who.clone(), token_id, amount));
+
+		Ok(())
+	}
+
+	pub fn do_reclaim(who: &T::AccountId, airdrop_id: AirdropId) -> DispatchResult {
+		let airdrop = Airdrops::<T>::get(airdrop_id).ok_or(Error::<T>::InvalidAirdropId)?;
+
+		ensure!(airdrop.owner == *who, Error::<T>::NotAirdropOwner);
+		ensure!(frame_system::Module::<T>::block_number() > airdrop.expires_at, Error::<T>::AirdropNotExpired);
+
+		let leftover = T::Currency::free_balance(&airdrop.vault);
+		T::Currency::transfer(&airdrop.vault, who, leftover, ExistenceRequirement::AllowDeath)?;
+
+		Airdrops::<T>::remove(airdrop_id);
+		ClaimedBitmap::<T>::remove_prefix(airdrop_id);
+
+		Self::deposit_event(Event::Reclaimed(airdrop_id, who.clone(), leftover));
+
+		Ok(())
+	}
+
+	pub fn is_claimed(airdrop_id: AirdropId, index: u32) -> bool {
+		let word = Self::claimed_bitmap(airdrop_id, index / 32);
+		word & (1 << (index % 32)) != 0
+	}
+
+	/// The leaf of an airdrop merkle tree, `hash(index, account, token_id, amount)`
+	pub fn leaf_hash(index: u32, who: &T::AccountId, token_id: T::TokenId, amount: Balance) -> T::Hash {
+		T::Hashing::hash_of(&(index, who, token_id, amount))
+	}
+
+	/// Fold the proof into the leaf, hashing each pair in sorted order, and compare with the root
+	pub fn verify_proof(root: T::Hash, leaf: T::Hash, proof: &[T::Hash]) -> bool {
+		let computed = proof.iter().fold(leaf, |node, sibling| {
+			if node <= *sibling {
+				T::Hashing::hash_of(&(node, *sibling))
+			} else {
+				T::Hashing::hash_of(&(*sibling, node))
+			}
+		});
+
+		computed == root
+	}
+}
@@ -0,0 +1,143 @@
+use crate::{Error, mock::*};
+use frame_support::{assert_ok, assert_noop};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+fn hash_pair(a: H256, b: H256) -> H256 {
+	if a <= b {
+		BlakeTwo256::hash_of(&(a, b))
+	} else {
+		BlakeTwo256::hash_of(&(b, a))
+	}
+}
+
+// A two leaf tree: (0, BOB, token 1, 100) and (1, CHARLIE, token 2, 5)
+fn setup_airdrop() -> (H256, H256) {
+	assert_ok!(Token::create_tao(Origin::signed(ALICE), [].to_vec()));
+
+	let bob_leaf = Airdrop::leaf_hash(0, &BOB, 1, 100);
+	let charlie_leaf = Airdrop::leaf_hash(1, &CHARLIE, 2, 5);
+	let root = hash_pair(bob_leaf, charlie_leaf);
+
+	assert_ok!(Airdrop::create_airdrop(Origin::signed(ALICE), 0, root, 100, 10));
+
+	(bob_leaf, charlie_leaf)
+}
+
+#[test]
+fn create_airdrop_works() {
+	new_test_ext().execute_with(|| {
+		setup_airdrop();
+
+		let airdrop = Airdrop::airdrops(0).unwrap();
+		assert_eq!(airdrop.owner, ALICE);
+		assert_eq!(Balances::free_balance(ALICE), 900);
+		assert_eq!(Balances::free_balance(airdrop.vault), 100);
+	})
+}
+
+#[test]
+fn create_airdrop_requires_tao_owner() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Token::create_tao(Origin::signed(ALICE), [].to_vec()));
+
+		assert_noop!(
+			Airdrop::create_airdrop(Origin::signed(BOB), 0, H256::zero(), 100, 10),
+			Error::<Test>::NotTaoOwner
+		);
+	})
+}
+
+#[test]
+fn claim_works() {
+	new_test_ext().execute_with(|| {
+		let (_, charlie_leaf) = setup_airdrop();
+
+		assert_ok!(Airdrop::claim(Origin::signed(BOB), 0, 0, 1, 100, vec![charlie_leaf]));
+
+		assert_eq!(Token::balance_of(&BOB, 0, 1), 100);
+		assert!(Airdrop::is_claimed(0, 0));
+		assert!(!Airdrop::is_claimed(0, 1));
+		assert_eq!(Balances::free_balance(Airdrop::airdrops(0).unwrap().vault), 90);
+		assert_eq!(Balances::free_balance(TREASURY), 10);
+	})
+}
+
+#[test]
+fn failed_claim_keeps_the_fee_and_the_leaf() {
+	new_test_ext().execute_with(|| {
+		let (_, charlie_leaf) = setup_airdrop();
+		assert_ok!(Token::do_mint(&BOB, 0, 1, u128::MAX));
+
+		// the mint overflows after the fee is taken and the leaf is marked claimed
+		assert_noop!(
+			Airdrop::claim(Origin::signed(BOB), 0, 0, 1, 100, vec![charlie_leaf]),
+			token::Error::<Test>::NumOverflow
+		);
+		assert!(!Airdrop::is_claimed(0, 0));
+		assert_eq!(Balances::free_balance(Airdrop::airdrops(0).unwrap().vault), 100);
+	})
+}
+
+#[test]
+fn claim_twice_fails() {
+	new_test_ext().execute_with(|| {
+		let (_, charlie_leaf) = setup_airdrop();
+
+		assert_ok!(Airdrop::claim(Origin::signed(BOB), 0, 0, 1, 100, vec![charlie_leaf]));
+		assert_noop!(
+			Airdrop::claim(Origin::signed(BOB), 0, 0, 1, 100, vec![charlie_leaf]),
+			Error::<Test>::AlreadyClaimed
+		);
+	})
+}
+
+#[test]
+fn claim_with_invalid_proof_fails() {
+	new_test_ext().execute_with(|| {
+		let (bob_leaf, charlie_leaf) = setup_airdrop();
+
+		// wrong amount
+		assert_noop!(
+			Airdrop::claim(Origin::signed(BOB), 0, 0, 1, 1000, vec![charlie_leaf]),
+			Error::<Test>::InvalidProof
+		);
+		// someone else's leaf
+		assert_noop!(
+			Airdrop::claim(Origin::signed(BOB), 0, 1, 2, 5, vec![bob_leaf]),
+			Error::<Test>::InvalidProof
+		);
+	})
+}
+
+#[test]
+fn claim_after_expiry_fails() {
+	new_test_ext().execute_with(|| {
+		let (_, charlie_leaf) = setup_airdrop();
+
+		System::set_block_number(11);
+		assert_noop!(
+			Airdrop::claim(Origin::signed(BOB), 0, 0, 1, 100, vec![charlie_leaf]),
+			Error::<Test>::AirdropExpired
+		);
+	})
+}
+
+#[test]
+fn reclaim_works() {
+	new_test_ext().execute_with(|| {
+		let (bob_leaf, _) = setup_airdrop();
+
+		assert_ok!(Airdrop::claim(Origin::signed(CHARLIE), 0, 1, 2, 5, vec![bob_leaf]));
+
+		assert_noop!(Airdrop::reclaim(Origin::signed(ALICE), 0), Error::<Test>::AirdropNotExpired);
+
+		System::set_block_number(11);
+		assert_noop!(Airdrop::reclaim(Origin::signed(BOB), 0), Error::<Test>::NotAirdropOwner);
+		assert_ok!(Airdrop::reclaim(Origin::signed(ALICE), 0));
+
+		assert_eq!(Balances::free_balance(ALICE), 990);
+		assert!(Airdrop::airdrops(0).is_none());
+		assert!(!Airdrop::is_claimed(0, 1));
+	})
+}
@@ -0,0 +1,48 @@
+;; Forwards the call input to the chain extension and returns its result.
+;;
+;; Input: func_id (u32, little endian) followed by the SCALE encoded function input.
+;; Output: the return code (u32, little endian) followed by the function output.
+(module
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) length of the input buffer
+	(data (i32.const 0) "\00\10")
+
+	;; [4, 4100) input buffer, func_id at [4, 8)
+
+	;; [8188, 8192) length of the output buffer
+	(data (i32.const 8188) "\00\20")
+
+	;; [8192, 8196) return code
+
+	;; [8196, 16388) output buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		(i32.store
+			(i32.const 8192)
+			(call $seal_call_chain_extension
+				(i32.load (i32.const 4))                         ;; func_id
+				(i32.const 8)                                    ;; input_ptr
+				(i32.sub (i32.load (i32.const 0)) (i32.const 4)) ;; input_len
+				(i32.const 8196)                                 ;; output_ptr
+				(i32.const 8188)                                 ;; output_len_ptr
+			)
+		)
+
+		;; the output length is left at the buffer size when nothing was written
+		(call $seal_return
+			(i32.const 0)
+			(i32.const 8192)
+			(i32.add (i32.load (i32.const 8188)) (i32.const 4))
+		)
+	)
+)
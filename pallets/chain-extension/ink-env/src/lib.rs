@@ -0,0 +1,177 @@
+//! ink! environment of the SGC chain.
+//!
+//! Contracts use [`SgcEnvironment`] as their `env` to call the runtime's `SgcChainExtension`
+//! through typed methods: `self.env().extension().mint(..)`. The inputs are the
+//! `*InputParam`s the runtime decodes, from `sgc-chain-extension-types`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_env::{DefaultEnvironment, Environment};
+use ink_lang as ink;
+use ink_prelude::vec::Vec;
+use scale::{Encode, Decode};
+
+pub use currency::{CurrencyId, ExchangeId, TaoId, TokenId, TokenSymbol};
+pub use types::*;
+
+#[cfg(test)]
+mod tests;
+
+pub type AccountId = <DefaultEnvironment as Environment>::AccountId;
+pub type Balance = <DefaultEnvironment as Environment>::Balance;
+pub type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// The functions of the runtime's `SgcChainExtension`, acting as the calling contract.
+///
+/// The extension ids are the `func_id`s of [`func`]. Errors of `pallet_erc1155`, the deadline and slippage
+/// errors of `pallet_dex` and failed EVM calls are returned as a [`StatusCode`]; any other error traps the contract.
+#[ink::chain_extension]
+pub trait SgcExtension {
+	type ErrorCode = StatusCode;
+
+	/// `Randomness::random_seed` of the runtime
+	#[ink(extension = 1001, returns_result = false)]
+	fn random_seed() -> [u8; 32];
+
+	/// Create a tao owned by the contract
+	#[ink(extension = 1002)]
+	fn create_tao(input: CreateTaoInputParam) -> Result<TaoId, StatusCode>;
+
+	/// Create a token in a tao owned by the contract
+	#[ink(extension = 1003)]
+	fn create_token(input: CreateTokenInputParam<TaoId, TokenId>) -> Result<(), StatusCode>;
+
+	/// Approve or revoke `operator` for all tokens of the contract
+	#[ink(extension = 1004)]
+	fn set_approval_for_all(input: SetApprovalForAllInputParam<AccountId>) -> Result<(), StatusCode>;
+
+	/// Mint a token of a tao owned by the contract
+	#[ink(extension = 1005)]
+	fn mint(input: MintInputParam<AccountId, TaoId, TokenId, Balance>) -> Result<(), StatusCode>;
+
+	/// Mint tokens of a tao owned by the contract
+	#[ink(extension = 1006)]
+	fn batch_mint(input: BatchMintInputParam<AccountId, TaoId, TokenId, Balance>) -> Result<(), StatusCode>;
+
+	/// Burn a token of a tao owned by the contract, from the contract or an account that approved it
+	#[ink(extension = 1007)]
+	fn burn(input: BurnInputParam<AccountId, TaoId, TokenId, Balance>) -> Result<(), StatusCode>;
+
+	/// Burn tokens of a tao owned by the contract, from the contract or an account that approved it
+	#[ink(extension = 1008)]
+	fn batch_burn(input: BatchBurnInputParam<AccountId, TaoId, TokenId, Balance>) -> Result<(), StatusCode>;
+
+	/// Transfer a token of `from`, the contract or an account that approved it
+	#[ink(extension = 1009)]
+	fn transfer_from(input: TransferFromInputParam<AccountId, TaoId, TokenId, Balance>) -> Result<(), StatusCode>;
+
+	/// Transfer tokens of `from`, the contract or an account that approved it
+	#[ink(extension = 1010)]
+	fn batch_transfer_from(input: BatchTransferFromInputParam<AccountId, TaoId, TokenId, Balance>) -> Result<(), StatusCode>;
+
+	/// Whether `account` is `who` or an operator approved by it
+	#[ink(extension = 1011)]
+	fn approved_or_owner(input: ApprovedOrOwnerInputParam<AccountId>) -> Result<bool, StatusCode>;
+
+	/// Whether `operator` is approved for all tokens of `owner`
+	#[ink(extension = 1012)]
+	fn is_approved_for_all(input: IsApprovedForAllInputParam<AccountId>) -> Result<bool, StatusCode>;
+
+	/// Balance of a token of `owner`
+	#[ink(extension = 1013)]
+	fn balance_of(input: BalanceOfInputParam<AccountId, TaoId, TokenId>) -> Result<Balance, StatusCode>;
+
+	/// Balances of tokens of `owners`
+	#[ink(extension = 1014)]
+	fn balance_of_batch(input: BalanceOfBatchInputParam<AccountId, TaoId, TokenId>) -> Result<Vec<Balance>, StatusCode>;
+
+	/// Set an attribute of a tao owned by the contract, or of which it is the game server
+	#[ink(extension = 1015)]
+	fn set_attribute(input: SetAttributeInputParam<TaoId, TokenId>) -> Result<(), StatusCode>;
+
+	/// Clear an attribute of a tao owned by the contract, or of which it is the game server
+	#[ink(extension = 1016)]
+	fn clear_attribute(input: ClearAttributeInputParam<TaoId, TokenId>) -> Result<(), StatusCode>;
+
+	/// An attribute of a tao or of one of its tokens
+	#[ink(extension = 1017)]
+	fn attribute(input: AttributeInputParam<TaoId, TokenId>) -> Result<Option<Vec<u8>>, StatusCode>;
+
+	/// A tao, `None` if it does not exist
+	#[ink(extension = 1018)]
+	fn tao(input: TaoInputParam<TaoId>) -> Result<Option<TaoView<AccountId>>, StatusCode>;
+
+	/// A token, `None` if it does not exist
+	#[ink(extension = 1019)]
+	fn token(input: TokenInputParam<TaoId, TokenId>) -> Result<Option<TokenView<TaoId, AccountId>>, StatusCode>;
+
+	/// The id the next created tao gets
+	#[ink(extension = 1020, returns_result = false)]
+	fn next_tao_id() -> TaoId;
+
+	/// Request randomness for the contract, unknown to anyone until its round is over
+	#[ink(extension = 1021, returns_result = false)]
+	fn request_randomness() -> u64;
+
+	/// The randomness of a request of the contract, `None` while its round is open
+	#[ink(extension = 1022, returns_result = false)]
+	fn take_randomness(request_id: u64) -> Option<[u8; 32]>;
+
+	/// Buy tokens with currency of the contract
+	#[ink(extension = 2001)]
+	fn buy_tokens(input: BuyTokensInputParam<AccountId, ExchangeId, TokenId, Balance, BlockNumber>) -> Result<(), StatusCode>;
+
+	/// Sell tokens of the contract for currency
+	#[ink(extension = 2002)]
+	fn sell_tokens(input: SellTokensInputParam<AccountId, ExchangeId, TokenId, Balance, BlockNumber>) -> Result<(), StatusCode>;
+
+	/// Add liquidity from tokens and currency of the contract
+	#[ink(extension = 2003)]
+	fn add_liquidity(input: AddLiquidityInputParam<AccountId, ExchangeId, TokenId, Balance, BlockNumber>) -> Result<(), StatusCode>;
+
+	/// Remove liquidity of the contract
+	#[ink(extension = 2004)]
+	fn remove_liquidity(input: RemoveLiquidityInputParam<AccountId, ExchangeId, TokenId, Balance, BlockNumber>) -> Result<(), StatusCode>;
+
+	/// Currency needed to buy the tokens
+	#[ink(extension = 2005)]
+	fn get_buy_prices(input: GetBuyPricesInputParam<ExchangeId, TokenId, Balance>) -> Result<Vec<Balance>, StatusCode>;
+
+	/// Currency received for selling the tokens
+	#[ink(extension = 2006)]
+	fn get_sell_prices(input: GetSellPricesInputParam<ExchangeId, TokenId, Balance>) -> Result<Vec<Balance>, StatusCode>;
+
+	/// The tao and token wrapping a currency
+	#[ink(extension = 3001)]
+	fn get_currency_token(input: GetCurrencyTokenInputParam<CurrencyId>) -> Result<(TaoId, TokenId), StatusCode>;
+
+	/// Wrap currency of the contract into its token
+	#[ink(extension = 3002)]
+	fn wrap(input: WrapInputParam<CurrencyId, Balance>) -> Result<(), StatusCode>;
+
+	/// Unwrap tokens of the contract back into currency
+	#[ink(extension = 3003)]
+	fn unwrap(input: UnwrapInputParam<CurrencyId, Balance>) -> Result<(), StatusCode>;
+
+	/// Call an EVM contract from the contract's EVM address, returning its return data
+	#[ink(extension = 4001)]
+	fn evm_call(input: EvmCallInputParam) -> Result<Vec<u8>, StatusCode>;
+}
+
+/// [`DefaultEnvironment`] with [`SgcExtension`] as its chain extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum SgcEnvironment {}
+
+impl Environment for SgcEnvironment {
+	const MAX_EVENT_TOPICS: usize = <DefaultEnvironment as Environment>::MAX_EVENT_TOPICS;
+
+	type AccountId = <DefaultEnvironment as Environment>::AccountId;
+	type Balance = <DefaultEnvironment as Environment>::Balance;
+	type Hash = <DefaultEnvironment as Environment>::Hash;
+	type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+	type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+	type RentFraction = <DefaultEnvironment as Environment>::RentFraction;
+
+	type ChainExtension = SgcExtension;
+}
@@ -0,0 +1,97 @@
+use super::*;
+use core::convert::TryFrom;
+use ink_env::chain_extension::FromStatusCode;
+use primitives::AccountId as RuntimeAccountId;
+
+const ALICE: [u8; 32] = [1u8; 32];
+const BOB: [u8; 32] = [2u8; 32];
+
+/// Decode the encoding of `value` as `T`, as the other side of the chain extension does.
+fn convert<T: Decode>(value: impl Encode) -> T {
+	let encoded = value.encode();
+	let mut input = &encoded[..];
+	let decoded = T::decode(&mut input).unwrap();
+	assert!(input.is_empty(), "trailing bytes");
+	decoded
+}
+
+#[test]
+fn erc1155_inputs_decode_on_runtime() {
+	let input: MintInputParam<AccountId, TaoId, TokenId, Balance> = MintInputParam {
+		to: ALICE.into(), tao_id: 1, token_id: 2, amount: 10,
+	};
+	let runtime: MintInputParam<RuntimeAccountId, primitives::TaoId, primitives::TokenId, primitives::Balance> = convert(input);
+	assert_eq!(runtime, MintInputParam { to: RuntimeAccountId::new(ALICE), tao_id: 1, token_id: 2, amount: 10 });
+
+	let input: BatchTransferFromInputParam<AccountId, TaoId, TokenId, Balance> = BatchTransferFromInputParam {
+		from: ALICE.into(), to: BOB.into(), tao_id: 1, token_ids: vec![1, 2], amounts: vec![10, 20],
+	};
+	let runtime: BatchTransferFromInputParam<RuntimeAccountId, primitives::TaoId, primitives::TokenId, primitives::Balance> = convert(input);
+	assert_eq!(runtime, BatchTransferFromInputParam {
+		from: RuntimeAccountId::new(ALICE), to: RuntimeAccountId::new(BOB), tao_id: 1, token_ids: vec![1, 2], amounts: vec![10, 20],
+	});
+
+	let input: SetAttributeInputParam<TaoId, TokenId> = SetAttributeInputParam {
+		tao_id: 1, token_id: Some(2), key: b"key".to_vec(), value: b"value".to_vec(),
+	};
+	let runtime: SetAttributeInputParam<primitives::TaoId, primitives::TokenId> = convert(input);
+	assert_eq!(runtime, SetAttributeInputParam { tao_id: 1, token_id: Some(2), key: b"key".to_vec(), value: b"value".to_vec() });
+}
+
+#[test]
+fn dex_and_currency_token_inputs_decode_on_runtime() {
+	let input: BuyTokensInputParam<AccountId, ExchangeId, TokenId, Balance, BlockNumber> = BuyTokensInputParam {
+		exchange_id: 3, token_ids: vec![1], token_amounts_out: vec![10], max_currencys: vec![100], to: BOB.into(), deadline: Some(9),
+	};
+	let runtime: BuyTokensInputParam<RuntimeAccountId, primitives::ExchangeId, primitives::TokenId, primitives::Balance, primitives::BlockNumber> =
+		convert(input);
+	assert_eq!(runtime, BuyTokensInputParam {
+		exchange_id: 3, token_ids: vec![1], token_amounts_out: vec![10], max_currencys: vec![100], to: RuntimeAccountId::new(BOB), deadline: Some(9),
+	});
+
+	let input: WrapInputParam<CurrencyId, Balance> = WrapInputParam { currency_id: CurrencyId::Token(TokenSymbol::DOT), amount: 10 };
+	let runtime: WrapInputParam<primitives::CurrencyId, primitives::Balance> = convert(input);
+	assert_eq!(runtime, WrapInputParam { currency_id: primitives::CurrencyId::Token(primitives::TokenSymbol::DOT), amount: 10 });
+}
+
+#[test]
+fn currency_ids_are_the_runtime_types() {
+	let id: primitives::CurrencyId = CurrencyId::Erc1155(1, 2);
+	assert_eq!(convert::<CurrencyId>(id), primitives::CurrencyId::Erc1155(1, 2));
+	assert_eq!(CurrencyId::try_from([0u8; 32]), primitives::CurrencyId::try_from([0u8; 32]));
+}
+
+#[test]
+fn runtime_outputs_decode_in_contract() {
+	let tao_id: primitives::TaoId = 7;
+	assert_eq!(convert::<TaoId>(tao_id), 7);
+
+	let balances: Vec<primitives::Balance> = vec![1, u128::max_value()];
+	assert_eq!(convert::<Vec<Balance>>(balances), vec![1, u128::max_value()]);
+
+	let attribute: Option<Vec<u8>> = Some(b"value".to_vec());
+	assert_eq!(convert::<Option<Vec<u8>>>(attribute), Some(b"value".to_vec()));
+
+	let currency_token: (primitives::TaoId, primitives::TokenId) = (1, 2);
+	assert_eq!(convert::<(TaoId, TokenId)>(currency_token), (1, 2));
+
+	assert_eq!(convert::<bool>(true), true);
+
+	let tao = Some(TaoView { owner: RuntimeAccountId::new(ALICE), data: b"data".to_vec() });
+	assert_eq!(convert::<Option<TaoView<AccountId>>>(tao), Some(TaoView { owner: ALICE.into(), data: b"data".to_vec() }));
+
+	let token = Some(TokenView { tao_id: 1u64, creator: RuntimeAccountId::new(BOB), is_nf: true, uri: b"uri".to_vec() });
+	assert_eq!(
+		convert::<Option<TokenView<TaoId, AccountId>>>(token),
+		Some(TokenView { tao_id: 1, creator: BOB.into(), is_nf: true, uri: b"uri".to_vec() }),
+	);
+}
+
+#[test]
+fn status_codes_map_to_results() {
+	assert_eq!(StatusCode::from_status_code(0), Ok(()));
+	assert_eq!(StatusCode::from_status_code(1), Err(StatusCode::UnknownFuncId));
+	assert_eq!(StatusCode::from_status_code(1010), Err(StatusCode::NoPermission));
+	assert_eq!(StatusCode::from_status_code(4001), Err(StatusCode::EvmReverted));
+	assert_eq!(StatusCode::from_status_code(2), Err(StatusCode::Other(2)));
+}
@@ -0,0 +1,75 @@
+//! `pallet_currency_token` functions of the chain extension, 3001–3003.
+
+use frame_support::weights::Weight;
+use pallet_contracts::chain_extension::{Ext, SysConfig, UncheckedFrom};
+use pallet_currency_token::WeightInfo as _;
+use primitives::{Balance, CurrencyId};
+use sp_std::prelude::*;
+use crate::*;
+
+type WeightOf<T> = <T as pallet_currency_token::Config>::WeightInfo;
+
+/// The `pallet_currency_token` functions, by `func_id`.
+pub fn funcs<C, E>() -> Vec<(u32, Handler<E>)>
+where
+	C: Config,
+	E: Ext<T = C>,
+	<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+	vec![
+		entry::<C, E, GetCurrencyToken>(),
+		entry::<C, E, Wrap>(),
+		entry::<C, E, Unwrap>(),
+	]
+}
+
+/// The tao and token wrapping a currency
+pub struct GetCurrencyToken;
+
+impl<T: Config> Func<T> for GetCurrencyToken {
+	const ID: u32 = func::GET_CURRENCY_TOKEN;
+	type Input = GetCurrencyTokenInputParam<CurrencyId>;
+	type Output = (T::TaoId, T::TokenId);
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::get_currency_token()
+	}
+
+	fn call(_: &T::AccountId, input: Self::Input) -> Result<(T::TaoId, T::TokenId)> {
+		pallet_currency_token::Module::<T>::get_currency_token(input.currency_id)
+	}
+}
+
+/// Wrap currency of the calling contract into its token
+pub struct Wrap;
+
+impl<T: Config> Func<T> for Wrap {
+	const ID: u32 = func::WRAP;
+	type Input = WrapInputParam<CurrencyId, Balance>;
+	type Output = ();
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::wrap()
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		pallet_currency_token::Module::<T>::do_wrap(who, input.currency_id, input.amount)
+	}
+}
+
+/// Unwrap tokens of the calling contract back into currency
+pub struct Unwrap;
+
+impl<T: Config> Func<T> for Unwrap {
+	const ID: u32 = func::UNWRAP;
+	type Input = UnwrapInputParam<CurrencyId, Balance>;
+	type Output = ();
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::unwrap()
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		pallet_currency_token::Module::<T>::do_unwrap(who, input.currency_id, input.amount)
+	}
+}
@@ -0,0 +1,140 @@
+//! `pallet_dex` functions of the chain extension, 2001–2006.
+
+use frame_support::weights::Weight;
+use pallet_contracts::chain_extension::{Ext, SysConfig, UncheckedFrom};
+use pallet_dex::WeightInfo as _;
+use primitives::{Balance, ExchangeId};
+use sp_std::prelude::*;
+use crate::*;
+
+type WeightOf<T> = <T as pallet_dex::Config>::WeightInfo;
+type AccountIdOf<T> = <T as SysConfig>::AccountId;
+type TokenIdOf<T> = <T as pallet_erc1155::Config>::TokenId;
+type BlockNumberOf<T> = <T as SysConfig>::BlockNumber;
+
+/// The `pallet_dex` functions, by `func_id`.
+pub fn funcs<C, E>() -> Vec<(u32, Handler<E>)>
+where
+	C: Config,
+	E: Ext<T = C>,
+	<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+	vec![
+		entry::<C, E, BuyTokens>(),
+		entry::<C, E, SellTokens>(),
+		entry::<C, E, AddLiquidity>(),
+		entry::<C, E, RemoveLiquidity>(),
+		entry::<C, E, GetBuyPrices>(),
+		entry::<C, E, GetSellPrices>(),
+	]
+}
+
+/// Buy tokens with currency of the calling contract
+pub struct BuyTokens;
+
+impl<T: Config> Func<T> for BuyTokens {
+	const ID: u32 = func::BUY_TOKENS;
+	type Input = BuyTokensInputParam<AccountIdOf<T>, ExchangeId, TokenIdOf<T>, Balance, BlockNumberOf<T>>;
+	type Output = ();
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::buy_tokens(input.token_ids.len() as u32)
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		pallet_dex::Module::<T>::do_buy_tokens(
+			who, input.exchange_id, input.token_ids, input.token_amounts_out, input.max_currencys, &input.to, input.deadline,
+		)
+	}
+}
+
+/// Sell tokens of the calling contract for currency
+pub struct SellTokens;
+
+impl<T: Config> Func<T> for SellTokens {
+	const ID: u32 = func::SELL_TOKENS;
+	type Input = SellTokensInputParam<AccountIdOf<T>, ExchangeId, TokenIdOf<T>, Balance, BlockNumberOf<T>>;
+	type Output = ();
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::sell_tokens(input.token_ids.len() as u32)
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		pallet_dex::Module::<T>::do_sell_tokens(
+			who, input.exchange_id, input.token_ids, input.token_amounts_in, input.min_currencys, &input.to, input.deadline,
+		)
+	}
+}
+
+/// Add liquidity from tokens and currency of the calling contract
+pub struct AddLiquidity;
+
+impl<T: Config> Func<T> for AddLiquidity {
+	const ID: u32 = func::ADD_LIQUIDITY;
+	type Input = AddLiquidityInputParam<AccountIdOf<T>, ExchangeId, TokenIdOf<T>, Balance, BlockNumberOf<T>>;
+	type Output = ();
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::add_liquidity(input.token_ids.len() as u32)
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		pallet_dex::Module::<T>::do_add_liquidity(
+			who, input.exchange_id, &input.to, input.token_ids, input.token_amounts, input.max_currencys, input.deadline,
+		)
+	}
+}
+
+/// Remove liquidity of the calling contract
+pub struct RemoveLiquidity;
+
+impl<T: Config> Func<T> for RemoveLiquidity {
+	const ID: u32 = func::REMOVE_LIQUIDITY;
+	type Input = RemoveLiquidityInputParam<AccountIdOf<T>, ExchangeId, TokenIdOf<T>, Balance, BlockNumberOf<T>>;
+	type Output = ();
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::remove_liquidity(input.token_ids.len() as u32)
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		pallet_dex::Module::<T>::do_remove_liquidity(
+			who, input.exchange_id, &input.to, input.token_ids, input.liquidities, input.min_currencys, input.min_tokens, input.deadline,
+		)
+	}
+}
+
+/// Currency needed to buy tokens
+pub struct GetBuyPrices;
+
+impl<T: Config> Func<T> for GetBuyPrices {
+	const ID: u32 = func::GET_BUY_PRICES;
+	type Input = GetBuyPricesInputParam<ExchangeId, TokenIdOf<T>, Balance>;
+	type Output = Vec<Balance>;
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::get_buy_prices(input.token_ids.len() as u32)
+	}
+
+	fn call(_: &T::AccountId, input: Self::Input) -> Result<Vec<Balance>> {
+		pallet_dex::Module::<T>::get_buy_prices(input.exchange_id, input.token_ids, input.token_amounts_out)
+	}
+}
+
+/// Currency received for selling tokens
+pub struct GetSellPrices;
+
+impl<T: Config> Func<T> for GetSellPrices {
+	const ID: u32 = func::GET_SELL_PRICES;
+	type Input = GetSellPricesInputParam<ExchangeId, TokenIdOf<T>, Balance>;
+	type Output = Vec<Balance>;
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::get_sell_prices(input.token_ids.len() as u32)
+	}
+
+	fn call(_: &T::AccountId, input: Self::Input) -> Result<Vec<Balance>> {
+		pallet_dex::Module::<T>::get_sell_prices(input.exchange_id, input.token_ids, input.token_amounts_in)
+	}
+}
@@ -0,0 +1,471 @@
+//! `pallet_erc1155` functions of the chain extension, 1001–1020, and the randomness of the runtime, 1021–1022.
+
+use frame_support::{traits::{Get, Randomness}, weights::Weight};
+use pallet_contracts::chain_extension::{Ext, SysConfig, UncheckedFrom};
+use pallet_erc1155::WeightInfo as _;
+use primitives::{Balance, randomness::{RandomnessRequests, RequestId}};
+use sp_std::prelude::*;
+use crate::*;
+
+type WeightOf<T> = <T as pallet_erc1155::Config>::WeightInfo;
+type AccountIdOf<T> = <T as SysConfig>::AccountId;
+type TaoIdOf<T> = <T as pallet_erc1155::Config>::TaoId;
+type TokenIdOf<T> = <T as pallet_erc1155::Config>::TokenId;
+
+/// The `pallet_erc1155` functions, by `func_id`.
+pub fn funcs<C, E>() -> Vec<(u32, Handler<E>)>
+where
+	C: Config,
+	E: Ext<T = C>,
+	<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+	vec![
+		entry::<C, E, RandomSeed>(),
+		entry::<C, E, CreateTao>(),
+		entry::<C, E, CreateToken>(),
+		entry::<C, E, SetApprovalForAll>(),
+		entry::<C, E, Mint>(),
+		entry::<C, E, BatchMint>(),
+		entry::<C, E, Burn>(),
+		entry::<C, E, BatchBurn>(),
+		entry::<C, E, TransferFrom>(),
+		entry::<C, E, BatchTransferFrom>(),
+		entry::<C, E, ApprovedOrOwner>(),
+		entry::<C, E, IsApprovedForAll>(),
+		entry::<C, E, BalanceOf>(),
+		entry::<C, E, BalanceOfBatch>(),
+		entry::<C, E, SetAttribute>(),
+		entry::<C, E, ClearAttribute>(),
+		entry::<C, E, Attribute>(),
+		entry::<C, E, Tao>(),
+		entry::<C, E, Token>(),
+		entry::<C, E, NextTaoId>(),
+		entry::<C, E, RequestRandomness>(),
+		entry::<C, E, TakeRandomness>(),
+	]
+}
+
+/// Weight of `ensure_tao_owner`, one read of the tao
+fn tao_owner_weight<T: Config>() -> Weight {
+	T::DbWeight::get().reads(1)
+}
+
+/// The calling contract must own the tao
+fn ensure_tao_owner<T: Config>(who: &T::AccountId, tao_id: T::TaoId) -> Result<()> {
+	if !pallet_erc1155::Module::<T>::is_tao_owner(who, tao_id) {
+		return Err(pallet_erc1155::Error::<T>::NoPermission.into());
+	}
+	Ok(())
+}
+
+/// The calling contract must be `owner` or an operator approved by it
+fn ensure_approved_or_owner<T: Config>(owner: &T::AccountId, who: &T::AccountId) -> Result<()> {
+	if !pallet_erc1155::Module::<T>::approved_or_owner(owner, who) {
+		return Err(pallet_erc1155::Error::<T>::NoPermission.into());
+	}
+	Ok(())
+}
+
+/// `Randomness::random_seed` of the runtime
+pub struct RandomSeed;
+
+impl<T: Config> Func<T> for RandomSeed {
+	const ID: u32 = func::RANDOM_SEED;
+	type Input = ();
+	type Output = T::Hash;
+
+	fn weight(_: &()) -> Weight {
+		// The revealed seed and the randomness of the block
+		T::DbWeight::get().reads(2)
+	}
+
+	fn call(_: &T::AccountId, _: ()) -> Result<T::Hash> {
+		Ok(<T as Config>::Randomness::random_seed())
+	}
+}
+
+/// Create a tao owned by the calling contract
+pub struct CreateTao;
+
+impl<T: Config> Func<T> for CreateTao {
+	const ID: u32 = func::CREATE_TAO;
+	type Input = CreateTaoInputParam;
+	type Output = T::TaoId;
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::create_tao(input.data.len() as u32)
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<T::TaoId> {
+		pallet_erc1155::Module::<T>::do_create_tao(who, input.data)
+	}
+}
+
+/// Create a token in a tao owned by the calling contract
+pub struct CreateToken;
+
+impl<T: Config> Func<T> for CreateToken {
+	const ID: u32 = func::CREATE_TOKEN;
+	type Input = CreateTokenInputParam<TaoIdOf<T>, TokenIdOf<T>>;
+	type Output = ();
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::create_token(input.uri.len() as u32).saturating_add(tao_owner_weight::<T>())
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		ensure_tao_owner::<T>(who, input.tao_id)?;
+		pallet_erc1155::Module::<T>::do_create_token(who, input.tao_id, input.token_id, input.is_nf, input.uri)
+	}
+}
+
+/// Approve or revoke an operator for all tokens of the calling contract
+pub struct SetApprovalForAll;
+
+impl<T: Config> Func<T> for SetApprovalForAll {
+	const ID: u32 = func::SET_APPROVAL_FOR_ALL;
+	type Input = SetApprovalForAllInputParam<AccountIdOf<T>>;
+	type Output = ();
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::set_approval_for_all()
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		pallet_erc1155::Module::<T>::do_set_approval_for_all(who, &input.operator, input.approved)
+	}
+}
+
+/// Mint a token of a tao owned by the calling contract
+pub struct Mint;
+
+impl<T: Config> Func<T> for Mint {
+	const ID: u32 = func::MINT;
+	type Input = MintInputParam<AccountIdOf<T>, TaoIdOf<T>, TokenIdOf<T>, Balance>;
+	type Output = ();
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::mint().saturating_add(tao_owner_weight::<T>())
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		ensure_tao_owner::<T>(who, input.tao_id)?;
+		pallet_erc1155::Module::<T>::do_mint(&input.to, input.tao_id, input.token_id, input.amount)
+	}
+}
+
+/// Mint tokens of a tao owned by the calling contract
+pub struct BatchMint;
+
+impl<T: Config> Func<T> for BatchMint {
+	const ID: u32 = func::BATCH_MINT;
+	type Input = BatchMintInputParam<AccountIdOf<T>, TaoIdOf<T>, TokenIdOf<T>, Balance>;
+	type Output = ();
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::batch_mint(input.token_ids.len() as u32).saturating_add(tao_owner_weight::<T>())
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		ensure_tao_owner::<T>(who, input.tao_id)?;
+		pallet_erc1155::Module::<T>::do_batch_mint(&input.to, input.tao_id, input.token_ids, input.amounts)
+	}
+}
+
+/// Burn a token of a tao owned by the calling contract, from the contract or an account that approved it
+pub struct Burn;
+
+impl<T: Config> Func<T> for Burn {
+	const ID: u32 = func::BURN;
+	type Input = BurnInputParam<AccountIdOf<T>, TaoIdOf<T>, TokenIdOf<T>, Balance>;
+	type Output = ();
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::burn()
+			.saturating_add(tao_owner_weight::<T>())
+			.saturating_add(WeightOf::<T>::approved_or_owner())
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		ensure_tao_owner::<T>(who, input.tao_id)?;
+		ensure_approved_or_owner::<T>(&input.from, who)?;
+		pallet_erc1155::Module::<T>::do_burn(&input.from, input.tao_id, input.token_id, input.amount)
+	}
+}
+
+/// Burn tokens of a tao owned by the calling contract, from the contract or an account that approved it
+pub struct BatchBurn;
+
+impl<T: Config> Func<T> for BatchBurn {
+	const ID: u32 = func::BATCH_BURN;
+	type Input = BatchBurnInputParam<AccountIdOf<T>, TaoIdOf<T>, TokenIdOf<T>, Balance>;
+	type Output = ();
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::batch_burn(input.token_ids.len() as u32)
+			.saturating_add(tao_owner_weight::<T>())
+			.saturating_add(WeightOf::<T>::approved_or_owner())
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		ensure_tao_owner::<T>(who, input.tao_id)?;
+		ensure_approved_or_owner::<T>(&input.from, who)?;
+		pallet_erc1155::Module::<T>::do_batch_burn(&input.from, input.tao_id, input.token_ids, input.amounts)
+	}
+}
+
+/// Transfer a token of `from`, the calling contract or an account that approved it
+pub struct TransferFrom;
+
+impl<T: Config> Func<T> for TransferFrom {
+	const ID: u32 = func::TRANSFER_FROM;
+	type Input = TransferFromInputParam<AccountIdOf<T>, TaoIdOf<T>, TokenIdOf<T>, Balance>;
+	type Output = ();
+
+	fn weight(input: &Self::Input) -> Weight {
+		// transferring to itself touches no balances
+		if input.from == input.to {
+			return WeightOf::<T>::approved_or_owner();
+		}
+		WeightOf::<T>::transfer_from().saturating_add(WeightOf::<T>::approved_or_owner())
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		ensure_approved_or_owner::<T>(&input.from, who)?;
+		pallet_erc1155::Module::<T>::do_transfer_from(&input.from, &input.to, input.tao_id, input.token_id, input.amount)
+	}
+}
+
+/// Transfer tokens of `from`, the calling contract or an account that approved it
+pub struct BatchTransferFrom;
+
+impl<T: Config> Func<T> for BatchTransferFrom {
+	const ID: u32 = func::BATCH_TRANSFER_FROM;
+	type Input = BatchTransferFromInputParam<AccountIdOf<T>, TaoIdOf<T>, TokenIdOf<T>, Balance>;
+	type Output = ();
+
+	fn weight(input: &Self::Input) -> Weight {
+		// transferring to itself touches no balances
+		if input.from == input.to {
+			return WeightOf::<T>::approved_or_owner();
+		}
+		WeightOf::<T>::batch_transfer_from(input.token_ids.len() as u32).saturating_add(WeightOf::<T>::approved_or_owner())
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		ensure_approved_or_owner::<T>(&input.from, who)?;
+		pallet_erc1155::Module::<T>::do_batch_transfer_from(&input.from, &input.to, input.tao_id, input.token_ids, input.amounts)
+	}
+}
+
+/// Whether `account` is `who` or an operator approved by it
+pub struct ApprovedOrOwner;
+
+impl<T: Config> Func<T> for ApprovedOrOwner {
+	const ID: u32 = func::APPROVED_OR_OWNER;
+	type Input = ApprovedOrOwnerInputParam<AccountIdOf<T>>;
+	type Output = bool;
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::approved_or_owner()
+	}
+
+	fn call(_: &T::AccountId, input: Self::Input) -> Result<bool> {
+		Ok(pallet_erc1155::Module::<T>::approved_or_owner(&input.who, &input.account))
+	}
+}
+
+/// Whether an operator is approved for all tokens of an owner
+pub struct IsApprovedForAll;
+
+impl<T: Config> Func<T> for IsApprovedForAll {
+	const ID: u32 = func::IS_APPROVED_FOR_ALL;
+	type Input = IsApprovedForAllInputParam<AccountIdOf<T>>;
+	type Output = bool;
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::is_approved_for_all()
+	}
+
+	fn call(_: &T::AccountId, input: Self::Input) -> Result<bool> {
+		Ok(pallet_erc1155::Module::<T>::is_approved_for_all(&input.owner, &input.operator))
+	}
+}
+
+/// Balance of a token of an owner
+pub struct BalanceOf;
+
+impl<T: Config> Func<T> for BalanceOf {
+	const ID: u32 = func::BALANCE_OF;
+	type Input = BalanceOfInputParam<AccountIdOf<T>, TaoIdOf<T>, TokenIdOf<T>>;
+	type Output = Balance;
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::balance_of()
+	}
+
+	fn call(_: &T::AccountId, input: Self::Input) -> Result<Balance> {
+		Ok(pallet_erc1155::Module::<T>::balance_of(&input.owner, input.tao_id, input.token_id))
+	}
+}
+
+/// Balances of tokens of owners
+pub struct BalanceOfBatch;
+
+impl<T: Config> Func<T> for BalanceOfBatch {
+	const ID: u32 = func::BALANCE_OF_BATCH;
+	type Input = BalanceOfBatchInputParam<AccountIdOf<T>, TaoIdOf<T>, TokenIdOf<T>>;
+	type Output = Vec<Balance>;
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::balance_of_batch(input.token_ids.len() as u32)
+	}
+
+	fn call(_: &T::AccountId, input: Self::Input) -> Result<Vec<Balance>> {
+		pallet_erc1155::Module::<T>::balance_of_batch(&input.owners, input.tao_id, input.token_ids)
+	}
+}
+
+/// Set an attribute of a tao owned by the calling contract, or of which it is the game server
+pub struct SetAttribute;
+
+impl<T: Config> Func<T> for SetAttribute {
+	const ID: u32 = func::SET_ATTRIBUTE;
+	type Input = SetAttributeInputParam<TaoIdOf<T>, TokenIdOf<T>>;
+	type Output = ();
+
+	fn weight(input: &Self::Input) -> Weight {
+		WeightOf::<T>::set_attribute((input.key.len() + input.value.len()) as u32)
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		pallet_erc1155::Module::<T>::do_set_attribute(who, input.tao_id, input.token_id, input.key, input.value)
+	}
+}
+
+/// Clear an attribute of a tao owned by the calling contract, or of which it is the game server
+pub struct ClearAttribute;
+
+impl<T: Config> Func<T> for ClearAttribute {
+	const ID: u32 = func::CLEAR_ATTRIBUTE;
+	type Input = ClearAttributeInputParam<TaoIdOf<T>, TokenIdOf<T>>;
+	type Output = ();
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::clear_attribute()
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<()> {
+		pallet_erc1155::Module::<T>::do_clear_attribute(who, input.tao_id, input.token_id, input.key)
+	}
+}
+
+/// An attribute of a tao or of one of its tokens
+pub struct Attribute;
+
+impl<T: Config> Func<T> for Attribute {
+	const ID: u32 = func::ATTRIBUTE;
+	type Input = AttributeInputParam<TaoIdOf<T>, TokenIdOf<T>>;
+	type Output = Option<Vec<u8>>;
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::attribute()
+	}
+
+	fn call(_: &T::AccountId, input: Self::Input) -> Result<Option<Vec<u8>>> {
+		Ok(pallet_erc1155::Module::<T>::attribute(input.tao_id, input.token_id, input.key))
+	}
+}
+
+/// A tao, `None` if it does not exist
+pub struct Tao;
+
+impl<T: Config> Func<T> for Tao {
+	const ID: u32 = func::TAO;
+	type Input = TaoInputParam<TaoIdOf<T>>;
+	type Output = Option<TaoView<AccountIdOf<T>>>;
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::tao()
+	}
+
+	fn call(_: &T::AccountId, input: Self::Input) -> Result<Self::Output> {
+		Ok(pallet_erc1155::Module::<T>::taos(input.tao_id).map(|tao| TaoView {
+			owner: tao.owner,
+			data: tao.data,
+		}))
+	}
+}
+
+/// A token, `None` if it does not exist
+pub struct Token;
+
+impl<T: Config> Func<T> for Token {
+	const ID: u32 = func::TOKEN;
+	type Input = TokenInputParam<TaoIdOf<T>, TokenIdOf<T>>;
+	type Output = Option<TokenView<TaoIdOf<T>, AccountIdOf<T>>>;
+
+	fn weight(_: &Self::Input) -> Weight {
+		WeightOf::<T>::token()
+	}
+
+	fn call(_: &T::AccountId, input: Self::Input) -> Result<Self::Output> {
+		Ok(pallet_erc1155::Module::<T>::tokens(input.tao_id, input.token_id).map(|token| TokenView {
+			tao_id: token.tao_id,
+			creator: token.creator,
+			is_nf: token.is_nf,
+			uri: token.uri,
+		}))
+	}
+}
+
+/// The id the next created tao gets
+pub struct NextTaoId;
+
+impl<T: Config> Func<T> for NextTaoId {
+	const ID: u32 = func::NEXT_TAO_ID;
+	type Input = ();
+	type Output = T::TaoId;
+
+	fn weight(_: &()) -> Weight {
+		WeightOf::<T>::next_tao_id()
+	}
+
+	fn call(_: &T::AccountId, _: ()) -> Result<T::TaoId> {
+		Ok(pallet_erc1155::Module::<T>::next_tao_id())
+	}
+}
+
+/// Request randomness for the calling contract, unknown until its round is over
+pub struct RequestRandomness;
+
+impl<T: Config> Func<T> for RequestRandomness {
+	const ID: u32 = func::REQUEST_RANDOMNESS;
+	type Input = ();
+	type Output = RequestId;
+
+	fn weight(_: &()) -> Weight {
+		T::DbWeight::get().reads_writes(3, 4)
+	}
+
+	fn call(who: &T::AccountId, _: ()) -> Result<RequestId> {
+		T::RandomnessRequests::request(who)
+	}
+}
+
+/// Take the randomness of a request of the calling contract, `None` while its round is open
+pub struct TakeRandomness;
+
+impl<T: Config> Func<T> for TakeRandomness {
+	const ID: u32 = func::TAKE_RANDOMNESS;
+	type Input = RequestId;
+	type Output = Option<T::Hash>;
+
+	fn weight(_: &RequestId) -> Weight {
+		T::DbWeight::get().reads_writes(2, 2)
+	}
+
+	fn call(who: &T::AccountId, request_id: RequestId) -> Result<Option<T::Hash>> {
+		T::RandomnessRequests::take(who, request_id)
+	}
+}
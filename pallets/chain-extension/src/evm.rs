@@ -0,0 +1,68 @@
+//! EVM functions of the chain extension, 4001.
+
+use frame_support::{traits::Get, weights::Weight};
+use pallet_contracts::chain_extension::{Ext, SysConfig, UncheckedFrom};
+use pallet_evm::{ExitReason, GasWeightMapping, Runner};
+use primitives::bridge;
+use sp_core::{H160, U256};
+use sp_runtime::traits::Convert;
+use sp_std::prelude::*;
+use crate::*;
+
+/// The EVM functions, by `func_id`.
+pub fn funcs<C, E>() -> Vec<(u32, Handler<E>)>
+where
+	C: Config,
+	E: Ext<T = C>,
+	<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+	vec![
+		entry::<C, E, EvmCall>(),
+	]
+}
+
+/// Call an EVM contract from the EVM address of the calling contract
+///
+/// No value is transferred and no gas fee is withdrawn: the contract is charged the weight of its whole
+/// `gas_limit`, converted by `pallet_evm::Config::GasWeightMapping`, and refunded the gas the call did not use.
+pub struct EvmCall;
+
+impl<T: Config> Func<T> for EvmCall {
+	const ID: u32 = func::EVM_CALL;
+	type Input = EvmCallInputParam;
+	type Output = Vec<u8>;
+
+	fn weight(input: &Self::Input) -> Weight {
+		T::GasWeightMapping::gas_to_weight(input.gas_limit)
+	}
+
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<Vec<u8>> {
+		Self::call_weighed(who, input).map(|(output, _)| output).map_err(|(e, _)| e)
+	}
+
+	fn call_weighed(who: &T::AccountId, input: Self::Input) -> WeighedResult<Vec<u8>> {
+		let source = T::EvmAddress::convert(who.clone());
+		let gas_limit = input.gas_limit;
+
+		let info = bridge::enter(T::MaxBridgeDepth::get(), || {
+			T::Runner::call(
+				source,
+				H160::from(input.target),
+				input.input,
+				U256::zero(),
+				gas_limit,
+				None,
+				None,
+				<T as pallet_evm::Config>::config(),
+			).map_err(Into::<DispatchError>::into)
+		}).ok_or((BRIDGE_DEPTH_EXCEEDED, None))?.map_err(|e| (e, None))?;
+
+		let used_gas = info.used_gas.min(U256::from(gas_limit)).low_u64();
+		let actual_weight = Some(T::GasWeightMapping::gas_to_weight(used_gas));
+		match info.exit_reason {
+			ExitReason::Succeed(_) => Ok((info.value, actual_weight)),
+			ExitReason::Revert(_) => Err((EVM_REVERTED, actual_weight)),
+			ExitReason::Error(_) | ExitReason::Fatal(_) => Err((EVM_FAILED, actual_weight)),
+		}
+	}
+}
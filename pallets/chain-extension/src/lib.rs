@@ -2,189 +2,232 @@
 
 use codec::{Encode, Decode};
 
-use frame_support::traits::Randomness;
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	traits::{Get, PalletInfo, Randomness},
+	weights::Weight,
+};
 use pallet_contracts::chain_extension::{
 	ChainExtension, Environment, Ext, InitState, RetVal, SysConfig, UncheckedFrom,
 };
-use sp_runtime::{DispatchError, RuntimeDebug};
-use sp_std::prelude::*;
-use primitives::Balance;
+use sp_core::H160;
+use sp_runtime::{DispatchError, traits::Convert};
+use sp_std::{fmt::Debug, prelude::*};
 
-pub trait Config: pallet_contracts::Config + pallet_erc1155::Config {
-	type Randomness: Randomness<Self::Hash>;
+pub use types::*;
+
+#[cfg(feature = "dex")]
+use pallet_dex::with_slippage_index;
+
+/// Without `pallet_dex` no function fails `SlippageExceeded`.
+#[cfg(not(feature = "dex"))]
+fn with_slippage_index<R>(f: impl FnOnce() -> R) -> (R, Option<u32>) {
+	(f(), None)
 }
 
-/// Result that returns a [`DispatchError`] on error.
-pub type Result<T> = sp_std::result::Result<T, DispatchError>;
+pub mod erc1155;
+#[cfg(feature = "dex")]
+pub mod dex;
+#[cfg(feature = "currency-token")]
+pub mod currency_token;
+#[cfg(feature = "evm")]
+pub mod evm;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// `pallet_dex::Config`, required by [`Config`] only with the `dex` feature.
+#[cfg(feature = "dex")]
+pub trait DexConfig: pallet_dex::Config {}
+#[cfg(feature = "dex")]
+impl<T: pallet_dex::Config> DexConfig for T {}
+#[cfg(not(feature = "dex"))]
+pub trait DexConfig {}
+#[cfg(not(feature = "dex"))]
+impl<T> DexConfig for T {}
+
+/// `pallet_currency_token::Config`, required by [`Config`] only with the `currency-token` feature.
+#[cfg(feature = "currency-token")]
+pub trait CurrencyTokenConfig: pallet_currency_token::Config {}
+#[cfg(feature = "currency-token")]
+impl<T: pallet_currency_token::Config> CurrencyTokenConfig for T {}
+#[cfg(not(feature = "currency-token"))]
+pub trait CurrencyTokenConfig {}
+#[cfg(not(feature = "currency-token"))]
+impl<T> CurrencyTokenConfig for T {}
+
+/// `pallet_evm::Config`, required by [`Config`] only with the `evm` feature.
+#[cfg(feature = "evm")]
+pub trait EvmConfig: pallet_evm::Config {}
+#[cfg(feature = "evm")]
+impl<T: pallet_evm::Config> EvmConfig for T {}
+#[cfg(not(feature = "evm"))]
+pub trait EvmConfig {}
+#[cfg(not(feature = "evm"))]
+impl<T> EvmConfig for T {}
+
+pub trait Config: pallet_contracts::Config + pallet_erc1155::Config + DexConfig + CurrencyTokenConfig + EvmConfig {
+	type Randomness: Randomness<Self::Hash>;
 
+	/// Randomness requested now and taken once nobody could have known it, see `primitives::randomness`
+	type RandomnessRequests: primitives::randomness::RandomnessRequests<Self::AccountId, Self::Hash>;
 
+	/// The EVM address a contract calls EVM contracts from
+	type EvmAddress: Convert<Self::AccountId, H160>;
 
-// func_id 1002
-// do_create_tao(who: &T::AccountId, data: Vec<u8>) -> Result<T::TaoId, DispatchError>
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct CreateTaoInputParam<AccountId> {
-	who: AccountId,
-	data: Vec<u8>,
+	/// The most calls between ink! and EVM contracts nested in one another, see `primitives::bridge`
+	type MaxBridgeDepth: Get<u32>;
 }
 
-// func_id 1003
-// do_create_token(
-// 		who: &T::AccountId,
-// 		tao_id: T::TaoId,
-// 		token_id: T::TokenId,
-// 		is_nf: bool,
-// 		uri: Vec<u8>,
-// 	)
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct CreateTokenInputParam<AccountId, TaoId, TokenId> {
-	who: AccountId,
-	tao_id: TaoId,
-	token_id: TokenId,
-	is_nf: bool,
-	uri: Vec<u8>,
-}
+/// The EVM contract reverted, returned as [`StatusCode::EvmReverted`].
+pub const EVM_REVERTED: DispatchError = DispatchError::Other("EvmReverted");
+/// The EVM call failed, returned as [`StatusCode::EvmFailed`].
+pub const EVM_FAILED: DispatchError = DispatchError::Other("EvmFailed");
+/// `Config::MaxBridgeDepth` bridged calls are already nested, returned as [`StatusCode::BridgeDepthExceeded`].
+pub const BRIDGE_DEPTH_EXCEEDED: DispatchError = DispatchError::Other(primitives::bridge::DEPTH_EXCEEDED);
 
-// func_id 1004
-// do_set_approval_for_all(
-// 		owner: &T::AccountId,
-// 		operator: &T::AccountId,
-// 		approved: bool,
-// 	)
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct SetApprovalForAllInputParam<AccountId> {
-	owner: AccountId,
-	operator: AccountId,
-	approved: bool,
-}
+/// Result that returns a [`DispatchError`] on error.
+pub type Result<T> = sp_std::result::Result<T, DispatchError>;
 
-// func_id 1005
-// do_mint(
-// 		to: &T::AccountId,
-// 		tao_id: T::TaoId,
-// 		token_id: T::TokenId,
-// 		amount: Balance
-// 	)
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct MintInputParam<AccountId, TaoId, TokenId, Balance> {
-	to: AccountId,
-	tao_id: TaoId,
-	token_id: TokenId,
-	amount: Balance,
-}
+/// Result of a [`Func`] with the weight it actually took, on success or on error, `None` for its charged weight.
+pub type WeighedResult<T> = sp_std::result::Result<(T, Option<Weight>), (DispatchError, Option<Weight>)>;
 
-// func_id 1006
-// do_batch_mint(
-// 		to: &T::AccountId,
-// 		tao_id: T::TaoId,
-// 		token_ids: Vec<T::TokenId>,
-// 		amounts: Vec<Balance>
-// 	)
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct BatchMintInputParam<AccountId, TaoId, TokenId, Balance> {
-	to: AccountId,
-	tao_id: TaoId,
-	token_ids: Vec<TokenId>,
-	amounts: Vec<Balance>,
-}
+/// A function of the chain extension, registered under its `func_id`.
+pub trait Func<T: Config> {
+	/// The `func_id` contracts call the function with
+	const ID: u32;
+	/// SCALE encoded input, read from the contract
+	type Input: Decode + Debug;
+	/// SCALE encoded output, written back to the contract
+	type Output: Encode + Debug;
 
-// func_id 1007
-// do_burn(
-// 		from: &T::AccountId,
-// 		tao_id: T::TaoId,
-// 		token_id: T::TokenId,
-// 		amount: Balance
-// 	)
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct BurnInputParam<AccountId, TaoId, TokenId, Balance> {
-	from: AccountId,
-	tao_id: TaoId,
-	token_id: TokenId,
-	amount: Balance,
-}
+	/// Weight charged before running the function, sized by its input
+	fn weight(input: &Self::Input) -> Weight;
 
-// func_id 1008
-// do_batch_burn(
-// 		from: &T::AccountId,
-// 		tao_id: T::TaoId,
-// 		token_ids: Vec<T::TokenId>,
-// 		amounts: Vec<Balance>
-// 	)
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct BatchBurnInputParam<AccountId, TaoId, TokenId, Balance> {
-	from: AccountId,
-	tao_id: TaoId,
-	token_ids: Vec<TokenId>,
-	amounts: Vec<Balance>,
-}
+	/// Run the function as `who`, the calling contract
+	fn call(who: &T::AccountId, input: Self::Input) -> Result<Self::Output>;
 
-// func_id 1009
-// do_transfer_from(
-// 		from: &T::AccountId,
-// 		to: &T::AccountId,
-// 		tao_id: T::TaoId,
-// 		token_id: T::TokenId,
-// 		amount: Balance
-// 	)
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct TransferFromInputParam<AccountId, TaoId, TokenId, Balance> {
-	from: AccountId,
-	to: AccountId,
-	tao_id: TaoId,
-	token_id: TokenId,
-	amount: Balance,
+	/// Run the function as `who` and return the weight it actually took, refunding the rest of
+	/// the charged weight. Implement it for functions that know their weight only once run.
+	fn call_weighed(who: &T::AccountId, input: Self::Input) -> WeighedResult<Self::Output> {
+		Self::call(who, input).map(|output| (output, None)).map_err(|e| (e, None))
+	}
 }
 
-// func_id 1010
-// do_batch_transfer_from(
-// 		from: &T::AccountId,
-// 		to: &T::AccountId,
-// 		tao_id: T::TaoId,
-// 		token_ids: Vec<T::TokenId>,
-// 		amounts: Vec<Balance>
-// 	)
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct BatchTransferFromInputParam<AccountId, TaoId, TokenId, Balance> {
-	from: AccountId,
-	to: AccountId,
-	tao_id: TaoId,
-	token_ids: Vec<TokenId>,
-	amounts: Vec<Balance>,
+/// Runs a [`Func`] with the environment of the call.
+pub type Handler<E> = fn(Environment<E, InitState>) -> Result<()>;
+
+/// Register `F` under its `func_id`.
+pub fn entry<C, E, F>() -> (u32, Handler<E>)
+where
+	C: Config,
+	E: Ext<T = C>,
+	F: Func<C>,
+	<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+	(F::ID, run::<C, E, F>)
 }
 
-// func_id 1011
-// approved_or_owner(who: &T::AccountId, account: &T::AccountId) -> bool
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct ApprovedOrOwnerInputParam<AccountId> {
-	who: AccountId,
-	account: AccountId,
-}
+/// Read the input of `F`, charge its weight, run it as the calling contract, refund the weight it did not take
+/// and write its output.
+fn run<C, E, F>(env: Environment<E, InitState>) -> Result<()>
+where
+	C: Config,
+	E: Ext<T = C>,
+	F: Func<C>,
+	<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+	let mut env = env.buf_in_buf_out();
+	let who = env.ext().address().clone();
+
+	let input: F::Input = env.read_as()?;
+	log::trace!(
+		target: "runtime",
+		"[ChainExtension]|call|func_id:{:}|input:{:?}",
+		F::ID,
+		input
+	);
+
+	let weight = F::weight(&input);
+	let charged = env.charge_weight(weight)?;
+
+	let (result, actual_weight) = match F::call_weighed(&who, input) {
+		Ok((output, actual_weight)) => (Ok(output), actual_weight),
+		Err((e, actual_weight)) => (Err(e), actual_weight),
+	};
+	if let Some(actual_weight) = actual_weight {
+		env.adjust_weight(charged, actual_weight.min(weight));
+	}
+	let output = result?;
+	log::trace!(target: "runtime", "[ChainExtension]|output:{:?}", output);
 
-// func_id 1012
-// is_approved_for_all(owner: &T::AccountId, operator: &T::AccountId) -> bool
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct IsApprovedForAllInputParam<AccountId> {
-	owner: AccountId,
-	operator: AccountId,
-}
-// func_id 1013
-// fn balance_of(owner: &T::AccountId, tao_id: T::TaoId, token_id: T::TokenId) -> Balance
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct BalanceOfInputParam<AccountId, TaoId, TokenId> {
-	owner: AccountId,
-	tao_id: TaoId,
-	token_id: TokenId,
+	env.write(&output.encode(), false, None)
+		.map_err(|_| DispatchError::Other("ChainExtension failed to write output"))?;
+	Ok(())
 }
 
-// func_id 1014
-// balance_of_batch(owners: &Vec<T::AccountId>, tao_id: T::TaoId, token_ids: Vec<T::TokenId>) -> Result<Vec<Balance>, DispatchError>
-#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct BalanceOfBatchInputParam<AccountId, TaoId, TokenId> {
-	owners: Vec<AccountId>,
-	tao_id: TaoId,
-	token_ids: Vec<TokenId>,
+/// The status of a `pallet_erc1155` error, a `pallet_dex` deadline or slippage error or a failed EVM call,
+/// or `None` for errors of anything else. `slippage_index` is the index of the token id of a slippage error.
+#[cfg_attr(not(feature = "dex"), allow(unused_variables))]
+fn status_code_of<T: Config>(error: DispatchError, slippage_index: Option<u32>) -> Option<StatusCode> {
+	if let DispatchError::Other(_) = error {
+		let statuses = [
+			(EVM_REVERTED, StatusCode::EvmReverted),
+			(EVM_FAILED, StatusCode::EvmFailed),
+			(BRIDGE_DEPTH_EXCEEDED, StatusCode::BridgeDepthExceeded),
+		];
+		return statuses.iter().find(|(other, _)| *other == error).map(|(_, status)| *status);
+	}
+
+	#[cfg(feature = "dex")]
+	if let DispatchError::Module { index, message: Some(message), .. } = error {
+		if Some(index as usize) == <T as SysConfig>::PalletInfo::index::<pallet_dex::Module<T>>() {
+			return match message {
+				"DeadlineExpired" => Some(StatusCode::DeadlineExpired),
+				"SlippageExceeded" => slippage_index.map(StatusCode::SlippageExceeded),
+				_ => None,
+			};
+		}
+	}
+
+	let erc1155_index = <T as SysConfig>::PalletInfo::index::<pallet_erc1155::Module<T>>()?;
+
+	match error {
+		DispatchError::Module { index, message: Some(message), .. } if index as usize == erc1155_index => {
+			match message {
+				"Unknown" => Some(StatusCode::Unknown),
+				"InUse" => Some(StatusCode::InUse),
+				"InvalidTokenId" => Some(StatusCode::InvalidTokenId),
+				"InsufficientBalance" => Some(StatusCode::InsufficientBalance),
+				"NumOverflow" => Some(StatusCode::NumOverflow),
+				"InvalidArrayLength" => Some(StatusCode::InvalidArrayLength),
+				"Overflow" => Some(StatusCode::Overflow),
+				"NoAvailableTaoId" => Some(StatusCode::NoAvailableTaoId),
+				"InvalidTaoId" => Some(StatusCode::InvalidTaoId),
+				"NoPermission" => Some(StatusCode::NoPermission),
+				"KeyTooLong" => Some(StatusCode::KeyTooLong),
+				"ValueTooLong" => Some(StatusCode::ValueTooLong),
+				"AttributeNotFound" => Some(StatusCode::AttributeNotFound),
+				_ => None,
+			}
+		}
+		_ => None,
+	}
 }
 
 /// chain extension of contract
+///
+/// Functions act as the calling contract, `env.ext().address()`, never as an account named in the input.
+/// ERC-1155 functions are 1001–1020, randomness requests 1021–1022, `pallet_dex` functions 2001–2006, `pallet_currency_token` functions 3001–3003
+/// and EVM calls 4001, the latter three behind the `dex`, `currency-token` and `evm` features. Each is a [`Func`],
+/// which charges the weight of its pallet function, sized by the input, before running it.
+/// The return value is a [`StatusCode`], so contracts can handle `pallet_erc1155` errors, `pallet_dex` deadline and
+/// slippage errors and failed EVM calls themselves.
+/// Inputs and outputs are SCALE encoded, see `sgc-chain-extension-types` for their layouts and
+/// `sgc-ink-env` for the ink! side.
 pub struct SgcChainExtension;
 
 impl<C: Config> ChainExtension<C> for SgcChainExtension {
@@ -193,447 +236,59 @@ impl<C: Config> ChainExtension<C> for SgcChainExtension {
 		E: Ext<T = C>,
 		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
 	{
-		match func_id {
-			1001 => {
-				log::info!("run 1001");
-				let mut env = env.buf_in_buf_out();
-				let random_slice = <E::T as Config>::Randomness::random_seed().encode();
-				// let random_slice = random_seed.encode();
-				log::trace!(
-					target: "runtime",
-					"[ChainExtension]|call|func_id:{:}",
-					func_id
-				);
-				env.write(&random_slice, false, None)
-					.map_err(|_| DispatchError::Other("ChainExtension failed to call random"))?;
-			}
-			1002 => { // do_create_tao(who: &T::AccountId, data: Vec<u8>) -> Result<T::TaoId, DispatchError>
-				log::info!("run 1002");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let input: CreateTaoInputParam<
-					<E::T as SysConfig>::AccountId
-				 > = env.read_as()?;
-
-				let tao_id: u64 = pallet_erc1155::Module::<E::T>::do_create_tao(&input.who, input.data)?.into();
-				log::info!("balance: {:?}", tao_id);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				let tao_slice = tao_id.to_be_bytes();
-				log::info!("balance_slice: {:?}", tao_slice);
-
-				log::trace!(
-					target: "runtime",
-					"[ChainExtension]|call|func_id:{:}",
-					func_id
-				);
-
-				env.write(&tao_slice, false, None)
-					.map_err(|_| DispatchError::Other("ChainExtension failed to call create collection"))?;
-			}
-			1003 => { // // do_create_token(
-						// 		who: &T::AccountId,
-						// 		tao_id: T::TaoId,
-						// 		token_id: T::TokenId,
-						// 		is_nf: bool,
-						// 		uri: Vec<u8>,
-						// 	)
-				log::info!("run 1003");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let in_len = env.in_len();
-				log::info!("in_len: {}", in_len);
-
-				let mut buffer = vec![0u8; in_len as usize];
-
-				env.read_into(&mut &mut buffer[..])?;
-				log::info!("buffer: {:?}", buffer);
-
-				let input: CreateTokenInputParam<
-					<E::T as SysConfig>::AccountId,
-					<E::T as pallet_erc1155::Config>::TaoId,
-					<E::T as pallet_erc1155::Config>::TokenId,
-				> = env.read_as()?;
-				log::info!("input: {:?}", input);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				pallet_erc1155::Module::<E::T>::do_create_token(&input.who, input.tao_id, input.token_id, input.is_nf, input.uri)?;
-			}
-			1004 => {
-				// do_set_approval_for_all(
-				// 		owner: &T::AccountId,
-				// 		operator: &T::AccountId,
-				// 		approved: bool,
-				// 	)
-				log::info!("run 1004");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let in_len = env.in_len();
-				log::info!("in_len: {}", in_len);
-
-				let mut buffer = vec![0u8; in_len as usize];
-
-				env.read_into(&mut &mut buffer[..])?;
-				log::info!("buffer: {:?}", buffer);
-
-				let input: SetApprovalForAllInputParam<
-					<E::T as SysConfig>::AccountId,
-				> = env.read_as()?;
-				log::info!("input: {:?}", input);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				pallet_erc1155::Module::<E::T>::do_set_approval_for_all(&input.owner, &input.operator, input.approved)?;
-
-			}
-			1005 => {
-				// do_mint(
-				// 		to: &T::AccountId,
-				// 		tao_id: T::TaoId,
-				// 		token_id: T::TokenId,
-				// 		amount: Balance
-				// 	)
-
-				log::info!("run 1005");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let in_len = env.in_len();
-				log::info!("in_len: {}", in_len);
-
-				let mut buffer = vec![0u8; in_len as usize];
-
-				env.read_into(&mut &mut buffer[..])?;
-				log::info!("buffer: {:?}", buffer);
-
-				let input: MintInputParam<
-					<E::T as SysConfig>::AccountId,
-					<E::T as pallet_erc1155::Config>::TaoId,
-					<E::T as pallet_erc1155::Config>::TokenId,
-					Balance,
-				> = env.read_as()?;
-				log::info!("input: {:?}", input);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				pallet_erc1155::Module::<E::T>::do_mint(&input.to, input.tao_id, input.token_id, input.amount)?;
-
-			}
-			1006 => {
-				// do_batch_mint(
-				// 		to: &T::AccountId,
-				// 		tao_id: T::TaoId,
- 				// 		token_ids: Vec<T::TokenId>,
- 				// 		amounts: Vec<Balance>
- 				// 	)
-				log::info!("run 1006");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let in_len = env.in_len();
-				log::info!("in_len: {}", in_len);
-
-				let mut buffer = vec![0u8; in_len as usize];
-
-				env.read_into(&mut &mut buffer[..])?;
-				log::info!("buffer: {:?}", buffer);
-
-				let input: BatchMintInputParam<
-					<E::T as SysConfig>::AccountId,
-					<E::T as pallet_erc1155::Config>::TaoId,
-					<E::T as pallet_erc1155::Config>::TokenId,
-					Balance,
-				> = env.read_as()?;
-				log::info!("input: {:?}", input);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				pallet_erc1155::Module::<E::T>::do_batch_mint(&input.to, input.tao_id, input.token_ids, input.amounts)?;
-
-			}
-			1007 => {
-				// do_burn(
-				// 		from: &T::AccountId,
-				// 		tao_id: T::TaoId,
-				// 		token_id: T::TokenId,
-				// 		amount: Balance
-				// 	)
-				log::info!("run 1007");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let in_len = env.in_len();
-				log::info!("in_len: {}", in_len);
-
-				let mut buffer = vec![0u8; in_len as usize];
-
-				env.read_into(&mut &mut buffer[..])?;
-				log::info!("buffer: {:?}", buffer);
-
-				let input: BurnInputParam<
-					<E::T as SysConfig>::AccountId,
-					<E::T as pallet_erc1155::Config>::TaoId,
-					<E::T as pallet_erc1155::Config>::TokenId,
-					Balance,
-				> = env.read_as()?;
-				log::info!("input: {:?}", input);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				pallet_erc1155::Module::<E::T>::do_burn(&input.from, input.tao_id, input.token_id, input.amount)?;
-			}
-			1008 => {
-				// do_batch_burn(
-				// 		from: &T::AccountId,
-				// 		tao_id: T::TaoId,
-				// 		token_ids: Vec<T::TokenId>,
-				// 		amounts: Vec<Balance>
-				// 	)
-				log::info!("run 1008");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let in_len = env.in_len();
-				log::info!("in_len: {}", in_len);
-
-				let mut buffer = vec![0u8; in_len as usize];
-
-				env.read_into(&mut &mut buffer[..])?;
-				log::info!("buffer: {:?}", buffer);
-
-				let input: BatchBurnInputParam<
-					<E::T as SysConfig>::AccountId,
-					<E::T as pallet_erc1155::Config>::TaoId,
-					<E::T as pallet_erc1155::Config>::TokenId,
-					Balance,
-				> = env.read_as()?;
-				log::info!("input: {:?}", input);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				pallet_erc1155::Module::<E::T>::do_batch_burn(&input.from, input.tao_id, input.token_ids, input.amounts)?;
-			}
-			1009 => {
-				// do_transfer_from(
-				// 		from: &T::AccountId,
-				// 		to: &T::AccountId,
-				// 		tao_id: T::TaoId,
-				// 		token_id: T::TokenId,
-				// 		amount: Balance
-				// 	)
-				log::info!("run 1009");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let in_len = env.in_len();
-				log::info!("in_len: {}", in_len);
-
-				let mut buffer = vec![0u8; in_len as usize];
-
-				env.read_into(&mut &mut buffer[..])?;
-				log::info!("buffer: {:?}", buffer);
-
-				let input: TransferFromInputParam<
-					<E::T as SysConfig>::AccountId,
-					<E::T as pallet_erc1155::Config>::TaoId,
-					<E::T as pallet_erc1155::Config>::TokenId,
-					Balance,
-				> = env.read_as()?;
-				log::info!("input: {:?}", input);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				pallet_erc1155::Module::<E::T>::do_transfer_from(&input.from, &input.to, input.tao_id, input.token_id, input.amount)?;
-			}
-			1010 => {
-				// do_batch_transfer_from(
-				// 		from: &T::AccountId,
-				// 		to: &T::AccountId,
-				// 		tao_id: T::TaoId,
-				// 		token_ids: Vec<T::TokenId>,
-				// 		amounts: Vec<Balance>
-				// 	)
-				log::info!("run 1010");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let in_len = env.in_len();
-				log::info!("in_len: {}", in_len);
-
-				let mut buffer = vec![0u8; in_len as usize];
-
-				env.read_into(&mut &mut buffer[..])?;
-				log::info!("buffer: {:?}", buffer);
-
-				let input: BatchTransferFromInputParam<
-					<E::T as SysConfig>::AccountId,
-					<E::T as pallet_erc1155::Config>::TaoId,
-					<E::T as pallet_erc1155::Config>::TokenId,
-					Balance,
-				> = env.read_as()?;
-				log::info!("input: {:?}", input);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				pallet_erc1155::Module::<E::T>::do_batch_transfer_from(&input.from, &input.to, input.tao_id, input.token_ids, input.amounts)?;
-			}
-			1011 => {
-				// approved_or_owner(who: &T::AccountId, account: &T::AccountId) -> bool
-				log::info!("run 1011");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let input: ApprovedOrOwnerInputParam<
-					<E::T as SysConfig>::AccountId
-				> = env.read_as()?;
-
-				let ret: bool = pallet_erc1155::Module::<E::T>::approved_or_owner(&input.who, &input.account);
-				let ret = ret as u8;
-				log::info!("balance: {:?}", ret);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				let ret_slice = ret.to_be_bytes();
-				log::info!("balance_slice: {:?}", ret_slice);
+		// The contract goes on running after a returned error, so discard the writes of the failed function
+		let (result, slippage_index) = with_slippage_index(|| with_transaction(|| match Self::dispatch(func_id, env) {
+			Ok(status) => TransactionOutcome::Commit(Ok(status)),
+			Err(e) => TransactionOutcome::Rollback(Err(e)),
+		}));
+
+		match result {
+			Ok(status) => Ok(RetVal::Converging(status.code())),
+			Err(e) => status_code_of::<C>(e, slippage_index)
+				.map(|status| RetVal::Converging(status.code()))
+				.ok_or(e),
+		}
+	}
 
-				log::trace!(
-					target: "runtime",
-					"[ChainExtension]|call|func_id:{:}",
-					func_id
-				);
+	fn enabled() -> bool {
+		true
+	}
+}
 
-				env.write(&ret_slice, false, None)
-					.map_err(|_| DispatchError::Other("ChainExtension failed to call create collection"))?;
+impl SgcChainExtension {
+	/// The functions of the extension, by `func_id`.
+	fn funcs<C, E>() -> Vec<(u32, Handler<E>)>
+	where
+		C: Config,
+		E: Ext<T = C>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		#[allow(unused_mut)]
+		let mut funcs = erc1155::funcs::<C, E>();
+		#[cfg(feature = "dex")]
+		funcs.extend(dex::funcs::<C, E>());
+		#[cfg(feature = "currency-token")]
+		funcs.extend(currency_token::funcs::<C, E>());
+		#[cfg(feature = "evm")]
+		funcs.extend(evm::funcs::<C, E>());
+		funcs
+	}
 
+	fn dispatch<C, E>(func_id: u32, env: Environment<E, InitState>) -> Result<StatusCode>
+	where
+		C: Config,
+		E: Ext<T = C>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		match Self::funcs::<C, E>().into_iter().find(|(id, _)| *id == func_id) {
+			Some((_, handler)) => {
+				handler(env)?;
+				Ok(StatusCode::Success)
 			}
-			1012 => {
-				// is_approved_for_all(owner: &T::AccountId, operator: &T::AccountId) -> bool
-				log::info!("run 1012");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let input: ApprovedOrOwnerInputParam<
-					<E::T as SysConfig>::AccountId
-				> = env.read_as()?;
-
-				let ret: bool = pallet_erc1155::Module::<E::T>::is_approved_for_all(&input.who, &input.account);
-				let ret = ret as u8;
-				log::info!("ret: {:?}", ret);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				let ret_slice = ret.to_be_bytes();
-				log::info!("ret_slice: {:?}", ret_slice);
-
-				log::trace!(
-					target: "runtime",
-					"[ChainExtension]|call|func_id:{:}",
-					func_id
-				);
-
-				env.write(&ret_slice, false, None)
-					.map_err(|_| DispatchError::Other("ChainExtension failed to call create collection"))?;
-			}
-			1013 => {
-				// fn balance_of(owner: &T::AccountId, tao_id: T::TaoId, token_id: T::TokenId) -> Balance
-				log::info!("run 1013");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let input: BalanceOfInputParam<
-					<E::T as SysConfig>::AccountId,
-					<E::T as pallet_erc1155::Config>::TaoId,
-					<E::T as pallet_erc1155::Config>::TokenId,
-				> = env.read_as()?;
-
-				let balance: u128 = pallet_erc1155::Module::<E::T>::balance_of(&input.owner, input.tao_id, input.token_id);
-				log::info!("balance: {:?}", balance);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				let balance_slice = balance.to_be_bytes();
-				log::info!("balance_slice: {:?}", balance_slice);
-
-				log::trace!(
-					target: "runtime",
-					"[ChainExtension]|call|func_id:{:}",
-					func_id
-				);
-
-				env.write(&balance_slice, false, None)
-					.map_err(|_| DispatchError::Other("ChainExtension failed to call create collection"))?;
-			}
-			1014 => {
-				// balance_of_batch(owners: &Vec<T::AccountId>, tao_id: T::TaoId, token_ids: Vec<T::TokenId>) -> Result<Vec<Balance>, DispatchError>
-				log::info!("run 1014");
-				let mut env = env.buf_in_buf_out();
-				let caller = env.ext().caller().clone();
-				log::info!("caller: {:?}", caller);
-
-				let input: BalanceOfBatchInputParam<
-					<E::T as SysConfig>::AccountId,
-					<E::T as pallet_erc1155::Config>::TaoId,
-					<E::T as pallet_erc1155::Config>::TokenId,
-				> = env.read_as()?;
-
-				let ret: Vec<Balance> = pallet_erc1155::Module::<E::T>::balance_of_batch(&input.owners, input.tao_id, input.token_ids)?;
-				let ret_slice = ret.encode();
-				log::info!("ret: {:?}", ret);
-
-				let weight = 100_000;
-				env.charge_weight(weight)?;
-
-				// let ret_slice = ret.to_be_bytes();
-				log::info!("ret_slice: {:?}", ret_slice);
-
-				log::trace!(
-					target: "runtime",
-					"[ChainExtension]|call|func_id:{:}",
-					func_id
-				);
-
-				env.write(&ret_slice, false, None)
-					.map_err(|_| DispatchError::Other("ChainExtension failed to call create collection"))?;
-			}
-
-			_ => {
+			None => {
 				log::error!("call an unregistered `func_id`, func_id:{:}", func_id);
-				return Err(DispatchError::Other("Unimplemented func_id"));
+				Ok(StatusCode::UnknownFuncId)
 			}
 		}
-		Ok(RetVal::Converging(0))
-	}
-
-	fn enabled() -> bool {
-		true
 	}
 }
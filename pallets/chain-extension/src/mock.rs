@@ -0,0 +1,336 @@
+use crate as chain_extension;
+use codec::Encode;
+use sp_core::{H160, H256, U256};
+use frame_support::{dispatch::DispatchResult, parameter_types, traits::GenesisBuild, weights::Weight};
+use sp_runtime::{
+	AccountId32, ModuleId, Perbill, Permill,
+	traits::{BlakeTwo256, Hash, IdentityLookup, Zero}, testing::Header,
+};
+use pallet_evm::{EnsureAddressNever, EnsureAddressRoot, GenesisAccount, HashedAddressMapping};
+use std::collections::BTreeMap;
+use frame_system::{self as system, EnsureRoot};
+use orml_traits::{parameter_type_with_key, MergeAccount};
+use pallet_contracts_primitives::ContractExecResult;
+use primitives::{Amount, Balance, CurrencyId, TokenSymbol};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the chain extension.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
+		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Module, Call, Storage},
+		Contracts: pallet_contracts::{Module, Call, Config<T>, Storage, Event<T>},
+		Token: pallet_erc1155::{Module, Call, Storage, Event<T>},
+		Tokens: orml_tokens::{Module, Storage, Event<T>, Config<T>},
+		CurrencyToken: pallet_currency_token::{Module, Call, Storage, Event<T>, Config},
+		Dex: pallet_dex::{Module, Call, Storage, Event<T>},
+		EVM: pallet_evm::{Module, Config, Call, Storage, Event<T>},
+		CommitReveal: pallet_commit_reveal::{Module, Call, Storage, Event<T>},
+		AccountClaim: pallet_account_claim::{Module, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+	pub BlockWeights: frame_system::limits::BlockWeights =
+		frame_system::limits::BlockWeights::simple_max(2 * 1_000_000_000_000);
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = BlockWeights;
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId32;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u128>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u128 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type Balance = u128;
+	type MaxLocks = ();
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const SignedClaimHandicap: u64 = 2;
+	pub const TombstoneDeposit: u128 = 16;
+	pub const DepositPerContract: u128 = 8 * DepositPerStorageByte::get();
+	pub const DepositPerStorageByte: u128 = 10_000;
+	pub const DepositPerStorageItem: u128 = 10_000;
+	pub RentFraction: Perbill = Perbill::from_rational_approximation(4u32, 10_000u32);
+	pub const SurchargeReward: u128 = 500_000;
+	pub const MaxDepth: u32 = 32;
+	pub const MaxValueSize: u32 = 16 * 1024;
+	pub const DeletionQueueDepth: u32 = 1024;
+	pub const DeletionWeightLimit: Weight = 500_000_000_000;
+	pub const MaxCodeSize: u32 = 256 * 1024;
+}
+
+impl pallet_contracts::Config for Test {
+	type Time = Timestamp;
+	type Randomness = RandomnessCollectiveFlip;
+	type Currency = Balances;
+	type Event = Event;
+	type RentPayment = ();
+	type SignedClaimHandicap = SignedClaimHandicap;
+	type TombstoneDeposit = TombstoneDeposit;
+	type DepositPerContract = DepositPerContract;
+	type DepositPerStorageByte = DepositPerStorageByte;
+	type DepositPerStorageItem = DepositPerStorageItem;
+	type RentFraction = RentFraction;
+	type SurchargeReward = SurchargeReward;
+	type MaxDepth = MaxDepth;
+	type MaxValueSize = MaxValueSize;
+	type WeightPrice = ();
+	type WeightInfo = ();
+	type ChainExtension = chain_extension::SgcChainExtension;
+	type DeletionQueueDepth = DeletionQueueDepth;
+	type DeletionWeightLimit = DeletionWeightLimit;
+	type MaxCodeSize = MaxCodeSize;
+}
+
+parameter_types! {
+	pub const AttributeDepositBase: u128 = 10;
+	pub const DepositPerByte: u128 = 1;
+	pub const KeyLimit: u32 = 32;
+	pub const ValueLimit: u32 = 256;
+}
+
+impl pallet_erc1155::Config for Test {
+	type Event = Event;
+	type TokenId = u64;
+	type TaoId = u64;
+	type Currency = Balances;
+	type AttributeDepositBase = AttributeDepositBase;
+	type DepositPerByte = DepositPerByte;
+	type KeyLimit = KeyLimit;
+	type ValueLimit = ValueLimit;
+	type WeightInfo = ();
+}
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+		Zero::zero()
+	};
+}
+
+impl orml_tokens::Config for Test {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+}
+
+parameter_types! {
+	pub const CurrencyTokenModuleId: ModuleId = ModuleId(*b"sgc/curr");
+	pub const StringLimit: u32 = 16;
+	pub const DexModuleId: ModuleId = ModuleId(*b"sgc/dexm");
+	pub const DexMaxFee: Permill = Permill::from_percent(3);
+	pub const TreasuryAccount: AccountId32 = AccountId32::new([100u8; 32]);
+}
+
+impl pallet_currency_token::Config for Test {
+	type Event = Event;
+	type ModuleId = CurrencyTokenModuleId;
+	type Currency = Tokens;
+	type RegistryOrigin = EnsureRoot<AccountId32>;
+	type StringLimit = StringLimit;
+	type WeightInfo = ();
+}
+
+impl pallet_dex::Config for Test {
+	type Event = Event;
+	type ModuleId = DexModuleId;
+	type GovernanceOrigin = EnsureRoot<AccountId32>;
+	type MaxFee = DexMaxFee;
+	type TreasuryAccount = TreasuryAccount;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const ChainId: u64 = 42;
+}
+
+impl pallet_evm::Config for Test {
+	type FeeCalculator = ();
+	type GasWeightMapping = ();
+	type CallOrigin = EnsureAddressRoot<AccountId32>;
+	type WithdrawOrigin = EnsureAddressNever<AccountId32>;
+	type AddressMapping = pallet_account_claim::EvmAddressMapping<Test>;
+	type Currency = Balances;
+	type Event = Event;
+	type Runner = pallet_evm::runner::stack::Runner<Self>;
+	type Precompiles = ();
+	type ChainId = ChainId;
+}
+
+/// Merges the ERC-1155 balances of the fallback account.
+pub struct MergeTokens;
+
+impl MergeAccount<AccountId32> for MergeTokens {
+	fn merge_account(source: &AccountId32, dest: &AccountId32) -> DispatchResult {
+		Token::do_merge_account(source, dest)
+	}
+}
+
+impl pallet_account_claim::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type MergeAccount = MergeTokens;
+	type FallbackAddressMapping = HashedAddressMapping<BlakeTwo256>;
+}
+
+parameter_types! {
+	pub const CommitDeposit: u128 = 100;
+	pub const RevealPeriod: u64 = 10;
+}
+
+impl pallet_commit_reveal::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type BlockRandomness = RandomnessCollectiveFlip;
+	type CommitDeposit = CommitDeposit;
+	type RevealPeriod = RevealPeriod;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MaxBridgeDepth: u32 = 2;
+}
+
+impl chain_extension::Config for Test {
+	type Randomness = RandomnessCollectiveFlip;
+	type RandomnessRequests = CommitReveal;
+	type EvmAddress = pallet_account_claim::EvmAddressOf<Test>;
+	type MaxBridgeDepth = MaxBridgeDepth;
+}
+
+pub const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
+pub const BOB: AccountId32 = AccountId32::new([2u8; 32]);
+pub const DOT: CurrencyId = CurrencyId::Token(TokenSymbol::DOT);
+
+pub const GAS_LIMIT: Weight = 100_000_000_000;
+
+/// An EVM contract returning the word `42`.
+pub const EVM_ANSWER: H160 = H160([0x42; 20]);
+/// An EVM contract that always reverts.
+pub const EVM_REVERT: H160 = H160([0x43; 20]);
+/// An EVM contract sending 100 to `msg.sender`.
+pub const EVM_PAYER: H160 = H160([0x44; 20]);
+
+fn evm_contract(code: Vec<u8>) -> GenesisAccount {
+	GenesisAccount { nonce: U256::zero(), balance: U256::zero(), storage: BTreeMap::new(), code }
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, 1_000_000_000_000), (BOB, 1_000_000_000_000)],
+	}
+		.assimilate_storage(&mut t)
+		.unwrap();
+	orml_tokens::GenesisConfig::<Test> {
+		endowed_accounts: vec![(ALICE, DOT, 1_000_000_000_000), (BOB, DOT, 1_000_000_000_000)],
+	}
+		.assimilate_storage(&mut t)
+		.unwrap();
+	GenesisBuild::<Test>::assimilate_storage(
+		&pallet_currency_token::GenesisConfig {
+			// tao 0, so taos created in tests start at 1
+			currency_tao: Some(vec![]),
+			currencies: vec![DOT],
+			assets: vec![],
+		},
+		&mut t,
+	)
+		.unwrap();
+	pallet_evm::GenesisConfig {
+		accounts: vec![
+			// PUSH1 0x2a PUSH1 0 MSTORE PUSH1 0x20 PUSH1 0 RETURN
+			(EVM_ANSWER, evm_contract(vec![0x60, 0x2a, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3])),
+			// PUSH1 0 PUSH1 0 REVERT
+			(EVM_REVERT, evm_contract(vec![0x60, 0x00, 0x60, 0x00, 0xfd])),
+			// CALL(GAS, CALLER, 100, 0, 0, 0, 0)
+			(EVM_PAYER, evm_contract(vec![
+				0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x64, 0x33, 0x5a, 0xf1, 0x50, 0x00,
+			])),
+		].into_iter().collect(),
+	}
+		.assimilate_storage::<Test>(&mut t)
+		.unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Deploy the proxy fixture from `ALICE` and return its address.
+pub fn deploy_proxy() -> AccountId32 {
+	let wasm = wat::parse_str(include_str!("../fixtures/chain_extension_proxy.wat")).unwrap();
+	let code_hash = <Test as frame_system::Config>::Hashing::hash(&wasm);
+
+	Contracts::instantiate_with_code(
+		Origin::signed(ALICE),
+		100_000_000,
+		GAS_LIMIT,
+		wasm,
+		vec![],
+		vec![],
+	).unwrap();
+
+	Contracts::contract_address(&ALICE, &code_hash, &[])
+}
+
+/// Call `func_id` with `input` through the proxy contract at `proxy`, signed by `origin`.
+pub fn call_extension<I: Encode>(origin: AccountId32, proxy: &AccountId32, func_id: u32, input: I) -> ContractExecResult {
+	let mut data = func_id.to_le_bytes().to_vec();
+	data.extend(input.encode());
+	Contracts::bare_call(origin, proxy.clone(), 0, GAS_LIMIT, data)
+}
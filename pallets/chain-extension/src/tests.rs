@@ -0,0 +1,642 @@
+use crate::{
+	mock::*,
+	CreateTaoInputParam, CreateTokenInputParam, SetApprovalForAllInputParam, MintInputParam,
+	TransferFromInputParam, BurnInputParam, BatchMintInputParam,
+	BuyTokensInputParam, AddLiquidityInputParam, GetBuyPricesInputParam,
+	GetCurrencyTokenInputParam, WrapInputParam, UnwrapInputParam, BatchTransferFromInputParam,
+	BalanceOfInputParam, ApprovedOrOwnerInputParam, TaoInputParam, TokenInputParam, TaoView, TokenView,
+	IsApprovedForAllInputParam, BalanceOfBatchInputParam, BatchBurnInputParam, SetAttributeInputParam,
+	ClearAttributeInputParam, AttributeInputParam, SellTokensInputParam, RemoveLiquidityInputParam,
+	GetSellPricesInputParam, EvmCallInputParam, StatusCode,
+};
+use codec::{Decode, Encode};
+use frame_support::{assert_noop, assert_ok, traits::{Currency, Get, Randomness}, weights::Weight};
+use pallet_evm::AddressMapping;
+use pallet_erc1155::WeightInfo;
+use sp_core::H256;
+use orml_traits::MultiCurrency;
+use primitives::{Balance, bridge};
+use sp_runtime::Permill;
+use pallet_contracts_primitives::ContractExecResult;
+
+/// The status code the chain extension returned to the proxy.
+fn status(result: &ContractExecResult) -> u32 {
+	let data = &result.exec_result.as_ref().unwrap().data;
+	u32::from_le_bytes([data[0], data[1], data[2], data[3]])
+}
+
+/// The SCALE encoded output the chain extension wrote, after the status code.
+fn output<T: Decode>(result: ContractExecResult) -> T {
+	let data = result.exec_result.unwrap().data;
+	T::decode(&mut &data[4..]).unwrap()
+}
+
+fn create_tao_through(proxy: &sp_runtime::AccountId32) -> u64 {
+	let result = call_extension(ALICE, proxy, 1002, CreateTaoInputParam { data: vec![] });
+	output(result)
+}
+
+#[test]
+fn create_tao_is_owned_by_contract() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+
+		let tao_id = create_tao_through(&proxy);
+
+		assert_eq!(tao_id, 1);
+		assert_eq!(Token::taos(1).unwrap().owner, proxy);
+	})
+}
+
+#[test]
+fn create_token_and_mint_require_tao_owner() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		create_tao_through(&proxy);
+		assert_ok!(Token::create_tao(Origin::signed(ALICE), vec![]));
+
+		let result = call_extension(ALICE, &proxy, 1003, CreateTokenInputParam {
+			tao_id: 2u64, token_id: 1u64, is_nf: false, uri: vec![],
+		});
+		assert_eq!(status(&result), StatusCode::NoPermission.code());
+
+		let result = call_extension(ALICE, &proxy, 1005, MintInputParam {
+			to: BOB, tao_id: 2u64, token_id: 1u64, amount: 10u128,
+		});
+		assert_eq!(status(&result), StatusCode::NoPermission.code());
+
+		let result = call_extension(ALICE, &proxy, 1003, CreateTokenInputParam {
+			tao_id: 1u64, token_id: 1u64, is_nf: false, uri: vec![],
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+		assert_eq!(Token::tokens(1, 1).unwrap().creator, proxy);
+
+		let result = call_extension(ALICE, &proxy, 1005, MintInputParam {
+			to: BOB, tao_id: 1u64, token_id: 1u64, amount: 10u128,
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+		assert_eq!(Token::balance_of(&BOB, 1, 1), 10);
+	})
+}
+
+#[test]
+fn set_approval_for_all_acts_as_contract() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+
+		let result = call_extension(ALICE, &proxy, 1004, SetApprovalForAllInputParam {
+			operator: BOB, approved: true,
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		assert!(Token::is_approved_for_all(&proxy, &BOB));
+		assert!(!Token::is_approved_for_all(&ALICE, &BOB));
+	})
+}
+
+#[test]
+fn transfer_and_burn_require_approval() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let tao_id = create_tao_through(&proxy);
+		let result = call_extension(ALICE, &proxy, 1005, MintInputParam { to: ALICE, tao_id, token_id: 1u64, amount: 100u128 });
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		// the contract is called by ALICE, but is not her operator
+		let result = call_extension(ALICE, &proxy, 1009, TransferFromInputParam {
+			from: ALICE, to: BOB, tao_id, token_id: 1u64, amount: 10u128,
+		});
+		assert_eq!(status(&result), StatusCode::NoPermission.code());
+
+		let result = call_extension(BOB, &proxy, 1007, BurnInputParam {
+			from: ALICE, tao_id, token_id: 1u64, amount: 10u128,
+		});
+		assert_eq!(status(&result), StatusCode::NoPermission.code());
+		assert_eq!(Token::balance_of(&ALICE, tao_id, 1), 100);
+
+		assert_ok!(Token::set_approval_for_all(Origin::signed(ALICE), proxy.clone(), true));
+
+		let result = call_extension(BOB, &proxy, 1009, TransferFromInputParam {
+			from: ALICE, to: BOB, tao_id, token_id: 1u64, amount: 10u128,
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		let result = call_extension(BOB, &proxy, 1007, BurnInputParam {
+			from: ALICE, tao_id, token_id: 1u64, amount: 10u128,
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		assert_eq!(Token::balance_of(&ALICE, tao_id, 1), 80);
+		assert_eq!(Token::balance_of(&BOB, tao_id, 1), 10);
+
+		// approval is not enough to burn tokens of a tao the contract does not own
+		assert_ok!(Token::create_tao(Origin::signed(ALICE), vec![]));
+		let other_tao = Token::next_tao_id() - 1;
+		assert_ok!(Token::mint(Origin::signed(ALICE), ALICE, other_tao, 1, 100));
+		let result = call_extension(BOB, &proxy, 1007, BurnInputParam {
+			from: ALICE, tao_id: other_tao, token_id: 1u64, amount: 10u128,
+		});
+		assert_eq!(status(&result), StatusCode::NoPermission.code());
+		assert_eq!(Token::balance_of(&ALICE, other_tao, 1), 100);
+	})
+}
+
+#[test]
+fn weight_scales_with_batch_size() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		create_tao_through(&proxy);
+
+		let single = call_extension(ALICE, &proxy, 1005, MintInputParam {
+			to: BOB, tao_id: 1u64, token_id: 1u64, amount: 10u128,
+		});
+		assert_eq!(status(&single), StatusCode::Success.code());
+
+		let batch = call_extension(ALICE, &proxy, 1006, BatchMintInputParam {
+			to: BOB, tao_id: 1u64, token_ids: (1..=10u64).collect(), amounts: vec![10u128; 10],
+		});
+		assert_eq!(status(&batch), StatusCode::Success.code());
+
+		assert!(batch.gas_consumed > single.gas_consumed);
+	})
+}
+
+#[test]
+fn transfer_to_self_refunds_unused_weight() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		assert_ok!(Token::create_tao(Origin::signed(ALICE), vec![]));
+		assert_ok!(Token::mint(Origin::signed(ALICE), proxy.clone(), 1, 1, 100));
+
+		let to_self = call_extension(ALICE, &proxy, 1009, TransferFromInputParam {
+			from: proxy.clone(), to: proxy.clone(), tao_id: 1u64, token_id: 1u64, amount: 10u128,
+		});
+		assert_eq!(status(&to_self), StatusCode::Success.code());
+
+		let to_bob = call_extension(ALICE, &proxy, 1009, TransferFromInputParam {
+			from: proxy.clone(), to: BOB, tao_id: 1u64, token_id: 1u64, amount: 10u128,
+		});
+		assert_eq!(status(&to_bob), StatusCode::Success.code());
+
+		assert!(to_self.gas_consumed < to_bob.gas_consumed);
+		assert_eq!(Token::balance_of(&proxy, 1, 1), 90);
+	})
+}
+
+#[test]
+fn get_currency_token_returns_tao_and_token() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+
+		let result = call_extension(ALICE, &proxy, 3001, GetCurrencyTokenInputParam { currency_id: DOT });
+
+		assert_eq!(output::<(u64, u64)>(result), CurrencyToken::get_currency_token(DOT).unwrap());
+	})
+}
+
+#[test]
+fn wrap_and_unwrap_act_as_contract() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		assert_ok!(<Tokens as MultiCurrency<_>>::transfer(DOT, &ALICE, &proxy, 1_000));
+		let (tao_id, token_id) = CurrencyToken::get_currency_token(DOT).unwrap();
+
+		let result = call_extension(ALICE, &proxy, 3002, WrapInputParam { currency_id: DOT, amount: 600u128 });
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		let result = call_extension(ALICE, &proxy, 3003, UnwrapInputParam { currency_id: DOT, amount: 100u128 });
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		assert_eq!(Token::balance_of(&proxy, tao_id, token_id), 500);
+		assert_eq!(<Tokens as MultiCurrency<_>>::free_balance(DOT, &proxy), 500);
+		assert_eq!(Token::balance_of(&ALICE, tao_id, token_id), 0);
+	})
+}
+
+#[test]
+fn add_liquidity_and_buy_tokens_through_contract() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		assert_ok!(Token::create_tao(Origin::signed(ALICE), vec![]));
+		let token_tao = Token::next_tao_id() - 1;
+		assert_ok!(Token::mint(Origin::signed(ALICE), proxy.clone(), token_tao, 1, 1_000));
+		assert_ok!(Dex::create_exchange(Origin::signed(ALICE), DOT, token_tao, Permill::from_parts(5_000)));
+
+		assert_ok!(<Tokens as MultiCurrency<_>>::transfer(DOT, &ALICE, &proxy, 100_000_000_000));
+		let result = call_extension(ALICE, &proxy, 3002, WrapInputParam { currency_id: DOT, amount: 100_000_000_000u128 });
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		let result = call_extension(ALICE, &proxy, 2003, AddLiquidityInputParam {
+			exchange_id: 0u32, to: proxy.clone(), token_ids: vec![1u64], token_amounts: vec![500u128], max_currencys: vec![10_000_000_000u128],
+			deadline: None::<u64>,
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		let result = call_extension(ALICE, &proxy, 2005, GetBuyPricesInputParam {
+			exchange_id: 0u32, token_ids: vec![1u64], token_amounts_out: vec![10u128],
+		});
+		let prices = output::<Vec<Balance>>(result);
+		assert_eq!(prices, Dex::get_buy_prices(0, vec![1], vec![10]).unwrap());
+
+		let result = call_extension(ALICE, &proxy, 2001, BuyTokensInputParam {
+			exchange_id: 0u32, token_ids: vec![1u64], token_amounts_out: vec![10u128], max_currencys: vec![prices[0] - 1], to: BOB,
+			deadline: None::<u64>,
+		});
+		assert_eq!(status(&result), StatusCode::SlippageExceeded(0).code());
+		let result = call_extension(ALICE, &proxy, 2001, BuyTokensInputParam {
+			exchange_id: 0u32, token_ids: vec![1u64], token_amounts_out: vec![10u128], max_currencys: vec![prices[0]], to: BOB,
+			deadline: Some(0u64),
+		});
+		assert_eq!(status(&result), StatusCode::DeadlineExpired.code());
+
+		let result = call_extension(ALICE, &proxy, 2001, BuyTokensInputParam {
+			exchange_id: 0u32, token_ids: vec![1u64], token_amounts_out: vec![10u128], max_currencys: vec![prices[0]], to: BOB,
+			deadline: Some(1u64),
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		assert_eq!(Token::balance_of(&BOB, token_tao, 1), 10);
+		assert_eq!(Token::balance_of(&proxy, token_tao, 1), 500);
+	})
+}
+
+#[test]
+fn query_outputs_are_scale_encoded() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let tao_id = create_tao_through(&proxy);
+		assert_ok!(Token::mint(Origin::signed(ALICE), BOB, tao_id, 1, 300));
+
+		let result = call_extension(ALICE, &proxy, 1013, BalanceOfInputParam { owner: BOB, tao_id, token_id: 1u64 });
+		assert_eq!(output::<Balance>(result), 300);
+
+		let result = call_extension(ALICE, &proxy, 1011, ApprovedOrOwnerInputParam { who: BOB, account: BOB });
+		assert!(output::<bool>(result));
+	})
+}
+
+#[test]
+fn tao_and_token_views() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let tao_id = create_tao_through(&proxy);
+		assert_ok!(Token::create_token(Origin::signed(ALICE), tao_id, 1, true, b"uri".to_vec()));
+
+		let result = call_extension(ALICE, &proxy, 1018, TaoInputParam { tao_id });
+		assert_eq!(output::<Option<TaoView<_>>>(result), Some(TaoView { owner: proxy.clone(), data: vec![] }));
+
+		let result = call_extension(ALICE, &proxy, 1019, TokenInputParam { tao_id, token_id: 1u64 });
+		assert_eq!(output::<Option<TokenView<_, _>>>(result), Some(TokenView {
+			tao_id, creator: ALICE, is_nf: true, uri: b"uri".to_vec(),
+		}));
+	})
+}
+
+#[test]
+fn missing_tao_and_token_are_none() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let tao_id = create_tao_through(&proxy);
+
+		let result = call_extension(ALICE, &proxy, 1018, TaoInputParam { tao_id: tao_id + 1 });
+		assert_eq!(output::<Option<TaoView<sp_runtime::AccountId32>>>(result), None);
+
+		let result = call_extension(ALICE, &proxy, 1019, TokenInputParam { tao_id, token_id: 1u64 });
+		assert_eq!(output::<Option<TokenView<u64, sp_runtime::AccountId32>>>(result), None);
+	})
+}
+
+#[test]
+fn next_tao_id_through_contract() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let tao_id = create_tao_through(&proxy);
+
+		let result = call_extension(ALICE, &proxy, 1020, ());
+		assert_eq!(output::<u64>(result), tao_id + 1);
+	})
+}
+
+#[test]
+fn every_func_id_is_registered() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+
+		for func_id in (1001..=1022).chain(2001..=2006).chain(3001..=3003).chain(4001..=4001) {
+			let result = call_extension(ALICE, &proxy, func_id, ());
+			let unknown = result.exec_result.is_ok() && status(&result) == StatusCode::UnknownFuncId.code();
+			assert!(!unknown, "func_id {} is not registered", func_id);
+		}
+	})
+}
+
+#[test]
+fn unknown_func_id_returns_status() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+
+		let result = call_extension(ALICE, &proxy, 9999, ());
+
+		assert_eq!(status(&result), StatusCode::UnknownFuncId.code());
+	})
+}
+
+#[test]
+fn failed_batch_transfer_is_rolled_back() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		assert_ok!(Token::create_tao(Origin::signed(ALICE), vec![]));
+		assert_ok!(Token::batch_mint(Origin::signed(ALICE), proxy.clone(), 1, vec![1, 2], vec![100, 5]));
+
+		let result = call_extension(ALICE, &proxy, 1010, BatchTransferFromInputParam {
+			from: proxy.clone(), to: BOB, tao_id: 1u64, token_ids: vec![1u64, 2u64], amounts: vec![10u128, 10u128],
+		});
+		assert_eq!(status(&result), StatusCode::NumOverflow.code());
+
+		assert_eq!(Token::balance_of(&proxy, 1, 1), 100);
+		assert_eq!(Token::balance_of(&BOB, 1, 1), 0);
+	})
+}
+
+#[test]
+fn random_seed_through_contract() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+
+		let result = call_extension(ALICE, &proxy, 1001, ());
+
+		assert_eq!(output::<H256>(result), RandomnessCollectiveFlip::random_seed());
+	})
+}
+
+#[test]
+fn randomness_requests_through_contract() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let secret = H256::repeat_byte(1);
+		assert_ok!(CommitReveal::commit(Origin::signed(ALICE), CommitReveal::commitment_of(&ALICE, &secret)));
+
+		System::set_block_number(2);
+		let result = call_extension(ALICE, &proxy, 1021, ());
+		let request_id = output::<u64>(result);
+		let result = call_extension(ALICE, &proxy, 1022, request_id);
+		assert_eq!(output::<Option<H256>>(result), None);
+
+		System::set_block_number(3);
+		assert_ok!(CommitReveal::reveal(Origin::signed(ALICE), secret));
+
+		System::set_block_number(12);
+		let result = call_extension(ALICE, &proxy, 1022, request_id);
+		assert!(output::<Option<H256>>(result).is_some());
+		// the request is the contract's, not the account calling it
+		assert_noop!(CommitReveal::take_randomness(Origin::signed(ALICE), request_id), pallet_commit_reveal::Error::<Test>::UnknownRequest);
+	})
+}
+
+#[test]
+fn is_approved_for_all_reads_owner_then_operator() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let result = call_extension(ALICE, &proxy, 1004, SetApprovalForAllInputParam { operator: BOB, approved: true });
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		let result = call_extension(ALICE, &proxy, 1012, IsApprovedForAllInputParam { owner: proxy.clone(), operator: BOB });
+		assert!(output::<bool>(result));
+
+		let result = call_extension(ALICE, &proxy, 1012, IsApprovedForAllInputParam { owner: BOB, operator: proxy.clone() });
+		assert!(!output::<bool>(result));
+	})
+}
+
+#[test]
+fn balance_of_batch_and_batch_burn_through_contract() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let tao_id = create_tao_through(&proxy);
+		let result = call_extension(ALICE, &proxy, 1006, BatchMintInputParam {
+			to: proxy.clone(), tao_id, token_ids: vec![1u64, 2], amounts: vec![10u128, 20],
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		let result = call_extension(ALICE, &proxy, 1008, BatchBurnInputParam {
+			from: proxy.clone(), tao_id, token_ids: vec![1u64, 2], amounts: vec![1u128, 2],
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		let result = call_extension(ALICE, &proxy, 1014, BalanceOfBatchInputParam {
+			owners: vec![proxy.clone(), proxy.clone()], tao_id, token_ids: vec![1u64, 2],
+		});
+		assert_eq!(output::<Vec<Balance>>(result), vec![9, 18]);
+
+		let result = call_extension(ALICE, &proxy, 1014, BalanceOfBatchInputParam {
+			owners: vec![proxy.clone()], tao_id, token_ids: vec![1u64, 2],
+		});
+		assert_eq!(status(&result), StatusCode::InvalidArrayLength.code());
+	})
+}
+
+#[test]
+fn attributes_through_contract() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let tao_id = create_tao_through(&proxy);
+
+		let result = call_extension(ALICE, &proxy, 1015, SetAttributeInputParam {
+			tao_id, token_id: None::<u64>, key: b"level".to_vec(), value: b"1".to_vec(),
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		let result = call_extension(ALICE, &proxy, 1017, AttributeInputParam { tao_id, token_id: None::<u64>, key: b"level".to_vec() });
+		assert_eq!(output::<Option<Vec<u8>>>(result), Some(b"1".to_vec()));
+
+		let result = call_extension(ALICE, &proxy, 1015, SetAttributeInputParam {
+			tao_id, token_id: None::<u64>, key: vec![0u8; KeyLimit::get() as usize + 1], value: vec![],
+		});
+		assert_eq!(status(&result), StatusCode::KeyTooLong.code());
+
+		let result = call_extension(ALICE, &proxy, 1016, ClearAttributeInputParam { tao_id, token_id: None::<u64>, key: b"level".to_vec() });
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		let result = call_extension(ALICE, &proxy, 1017, AttributeInputParam { tao_id, token_id: None::<u64>, key: b"level".to_vec() });
+		assert_eq!(output::<Option<Vec<u8>>>(result), None);
+
+		let result = call_extension(ALICE, &proxy, 1016, ClearAttributeInputParam { tao_id, token_id: None::<u64>, key: b"level".to_vec() });
+		assert_eq!(status(&result), StatusCode::AttributeNotFound.code());
+	})
+}
+
+#[test]
+fn sell_tokens_and_remove_liquidity_through_contract() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		assert_ok!(Token::create_tao(Origin::signed(ALICE), vec![]));
+		let token_tao = Token::next_tao_id() - 1;
+		assert_ok!(Token::mint(Origin::signed(ALICE), proxy.clone(), token_tao, 1, 1_000));
+		assert_ok!(Dex::create_exchange(Origin::signed(ALICE), DOT, token_tao, Permill::from_parts(5_000)));
+		let liquidity_tao = Token::next_tao_id() - 1;
+		let (currency_tao, currency_token) = CurrencyToken::get_currency_token(DOT).unwrap();
+
+		assert_ok!(<Tokens as MultiCurrency<_>>::transfer(DOT, &ALICE, &proxy, 100_000_000_000));
+		let result = call_extension(ALICE, &proxy, 3002, WrapInputParam { currency_id: DOT, amount: 100_000_000_000u128 });
+		assert_eq!(status(&result), StatusCode::Success.code());
+		let result = call_extension(ALICE, &proxy, 2003, AddLiquidityInputParam {
+			exchange_id: 0u32, to: proxy.clone(), token_ids: vec![1u64], token_amounts: vec![500u128], max_currencys: vec![10_000_000_000u128],
+			deadline: None::<u64>,
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		let result = call_extension(ALICE, &proxy, 2006, GetSellPricesInputParam {
+			exchange_id: 0u32, token_ids: vec![1u64], token_amounts_in: vec![10u128],
+		});
+		let prices = output::<Vec<Balance>>(result);
+		assert_eq!(prices, Dex::get_sell_prices(0, vec![1], vec![10]).unwrap());
+
+		let result = call_extension(ALICE, &proxy, 2002, SellTokensInputParam {
+			exchange_id: 0u32, token_ids: vec![1u64], token_amounts_in: vec![10u128], min_currencys: vec![prices[0]], to: BOB,
+			deadline: Some(1u64),
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+		assert_eq!(Token::balance_of(&BOB, currency_tao, currency_token), prices[0]);
+		assert_eq!(Token::balance_of(&proxy, token_tao, 1), 490);
+
+		let result = call_extension(ALICE, &proxy, 2004, RemoveLiquidityInputParam {
+			exchange_id: 0u32, to: proxy.clone(), token_ids: vec![1u64], liquidities: vec![1_000_000_000u128],
+			min_currencys: vec![0u128], min_tokens: vec![0u128], deadline: None::<u64>,
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+		assert_eq!(Token::balance_of(&proxy, liquidity_tao, 1), 9_000_000_000);
+		assert!(Token::balance_of(&proxy, token_tao, 1) > 490);
+	})
+}
+
+#[test]
+fn malformed_input_traps() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+
+		let result = call_extension(ALICE, &proxy, 1005, ());
+
+		assert!(result.exec_result.is_err());
+	})
+}
+
+/// Gas the chain extension charged for `func_id`, measured against `next_tao_id`, which ignores its
+/// input and returns a few bytes, called with the same input.
+fn charged<I: Encode + Clone>(proxy: &sp_runtime::AccountId32, func_id: u32, input: I) -> Weight {
+	let baseline = call_extension(ALICE, proxy, 1020, input.clone()).gas_consumed;
+	let consumed = call_extension(ALICE, proxy, func_id, input).gas_consumed;
+	(consumed + <() as WeightInfo>::next_tao_id()).saturating_sub(baseline)
+}
+
+/// The charged weight is the declared weight, give or take returning a few more bytes of output.
+fn assert_charged(charged: Weight, declared: Weight) {
+	let difference = if charged > declared { charged - declared } else { declared - charged };
+	assert!(difference < 1_000_000, "charged {}, declared {}", charged, declared);
+}
+
+#[test]
+fn charged_weight_is_declared_weight() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let tao_id = create_tao_through(&proxy);
+
+		// the mock has no `DbWeight`, so the tao owner check is free
+		assert_charged(
+			charged(&proxy, 1005, MintInputParam { to: BOB, tao_id, token_id: 1u64, amount: 10u128 }),
+			<() as WeightInfo>::mint(),
+		);
+		assert_charged(
+			charged(&proxy, 1006, BatchMintInputParam { to: BOB, tao_id, token_ids: vec![1u64, 2, 3], amounts: vec![1u128, 1, 1] }),
+			<() as WeightInfo>::batch_mint(3),
+		);
+		assert_charged(
+			charged(&proxy, 1013, BalanceOfInputParam { owner: BOB, tao_id, token_id: 1u64 }),
+			<() as WeightInfo>::balance_of(),
+		);
+		assert_charged(
+			charged(&proxy, 1015, SetAttributeInputParam { tao_id, token_id: None::<u64>, key: vec![1u8; 4], value: vec![2u8; 6] }),
+			<() as WeightInfo>::set_attribute(10),
+		);
+		assert_charged(
+			charged(&proxy, 1018, TaoInputParam { tao_id }),
+			<() as WeightInfo>::tao(),
+		);
+		// the mock maps one gas to one weight, and the gas the call did not use is refunded
+		let used = charged(&proxy, 4001, EvmCallInputParam { target: EVM_ANSWER.0, input: vec![], gas_limit: 100_000 });
+		assert!(used < 100_000);
+		assert_charged(
+			charged(&proxy, 4001, EvmCallInputParam { target: EVM_ANSWER.0, input: vec![], gas_limit: 10_000_000 }),
+			used,
+		);
+	})
+}
+
+#[test]
+fn evm_call_returns_output() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+
+		let result = call_extension(ALICE, &proxy, 4001, EvmCallInputParam {
+			target: EVM_ANSWER.0, input: vec![], gas_limit: 100_000,
+		});
+
+		assert_eq!(status(&result), StatusCode::Success.code());
+		let mut word = vec![0u8; 32];
+		word[31] = 42;
+		assert_eq!(output::<Vec<u8>>(result), word);
+	})
+}
+
+#[test]
+fn evm_contracts_pay_back_to_the_calling_contract() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let payer = pallet_account_claim::EvmAddressMapping::<Test>::into_account_id(EVM_PAYER);
+		let _ = Balances::deposit_creating(&payer, 1_000);
+		let before = Balances::free_balance(&proxy);
+
+		let result = call_extension(ALICE, &proxy, 4001, EvmCallInputParam {
+			target: EVM_PAYER.0, input: vec![], gas_limit: 100_000,
+		});
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		// `msg.sender` maps back to the contract
+		assert_eq!(Balances::free_balance(&proxy), before + 100);
+		assert_eq!(Balances::free_balance(&payer), 900);
+	})
+}
+
+#[test]
+fn failed_evm_calls_return_status() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+
+		let result = call_extension(ALICE, &proxy, 4001, EvmCallInputParam {
+			target: EVM_REVERT.0, input: vec![], gas_limit: 100_000,
+		});
+		assert_eq!(status(&result), StatusCode::EvmReverted.code());
+
+		// less than the intrinsic gas of a call
+		let result = call_extension(ALICE, &proxy, 4001, EvmCallInputParam {
+			target: EVM_ANSWER.0, input: vec![], gas_limit: 1,
+		});
+		assert_eq!(status(&result), StatusCode::EvmFailed.code());
+	})
+}
+
+#[test]
+fn evm_calls_are_limited_by_bridge_depth() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy_proxy();
+		let input = EvmCallInputParam { target: EVM_ANSWER.0, input: vec![], gas_limit: 100_000 };
+
+		// one bridged call is nested, so one more is allowed
+		let result = bridge::enter(MaxBridgeDepth::get(), || call_extension(ALICE, &proxy, 4001, input.clone())).unwrap();
+		assert_eq!(status(&result), StatusCode::Success.code());
+
+		let result = bridge::enter(MaxBridgeDepth::get(), || {
+			bridge::enter(MaxBridgeDepth::get(), || call_extension(ALICE, &proxy, 4001, input.clone()))
+		}).unwrap().unwrap();
+		assert_eq!(status(&result), StatusCode::BridgeDepthExceeded.code());
+	})
+}
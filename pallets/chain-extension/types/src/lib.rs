@@ -0,0 +1,572 @@
+//! Types shared by the `SgcChainExtension` of the runtime and the ink! contracts calling it.
+//!
+//! Inputs are SCALE encoded `*InputParam`s, generic over the runtime types so the same
+//! definitions serve both sides. Outputs are SCALE encoded too.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::{Encode, Decode};
+
+#[cfg(test)]
+mod tests;
+
+/// The `func_id`s of the chain extension.
+pub mod func {
+	pub const RANDOM_SEED: u32 = 1001;
+	pub const CREATE_TAO: u32 = 1002;
+	pub const CREATE_TOKEN: u32 = 1003;
+	pub const SET_APPROVAL_FOR_ALL: u32 = 1004;
+	pub const MINT: u32 = 1005;
+	pub const BATCH_MINT: u32 = 1006;
+	pub const BURN: u32 = 1007;
+	pub const BATCH_BURN: u32 = 1008;
+	pub const TRANSFER_FROM: u32 = 1009;
+	pub const BATCH_TRANSFER_FROM: u32 = 1010;
+	pub const APPROVED_OR_OWNER: u32 = 1011;
+	pub const IS_APPROVED_FOR_ALL: u32 = 1012;
+	pub const BALANCE_OF: u32 = 1013;
+	pub const BALANCE_OF_BATCH: u32 = 1014;
+	pub const SET_ATTRIBUTE: u32 = 1015;
+	pub const CLEAR_ATTRIBUTE: u32 = 1016;
+	pub const ATTRIBUTE: u32 = 1017;
+	pub const TAO: u32 = 1018;
+	pub const TOKEN: u32 = 1019;
+	pub const NEXT_TAO_ID: u32 = 1020;
+	pub const REQUEST_RANDOMNESS: u32 = 1021;
+	pub const TAKE_RANDOMNESS: u32 = 1022;
+
+	pub const BUY_TOKENS: u32 = 2001;
+	pub const SELL_TOKENS: u32 = 2002;
+	pub const ADD_LIQUIDITY: u32 = 2003;
+	pub const REMOVE_LIQUIDITY: u32 = 2004;
+	pub const GET_BUY_PRICES: u32 = 2005;
+	pub const GET_SELL_PRICES: u32 = 2006;
+
+	pub const GET_CURRENCY_TOKEN: u32 = 3001;
+	pub const WRAP: u32 = 3002;
+	pub const UNWRAP: u32 = 3003;
+
+	pub const EVM_CALL: u32 = 4001;
+}
+
+/// The status of a call, returned to the contract as the return value of `seal_call_chain_extension`.
+///
+/// Codes are stable: new ones are only ever added. `pallet_erc1155` errors are 1001 onwards,
+/// in the order of `pallet_erc1155::Error`, the deadline error of `pallet_dex` 2001 onwards
+/// and failed EVM calls 4001 onwards. The slippage error of `pallet_dex` carries the index of its token id,
+/// as [`StatusCode::SLIPPAGE_EXCEEDED`] plus the index. Codes added after a contract was built decode as [`StatusCode::Other`].
+/// Any other error traps the contract.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StatusCode {
+	/// The function succeeded.
+	Success,
+	/// No function is registered for the `func_id`.
+	UnknownFuncId,
+	/// `pallet_erc1155::Error::Unknown`
+	Unknown,
+	/// `pallet_erc1155::Error::InUse`, the token already exists.
+	InUse,
+	/// `pallet_erc1155::Error::InvalidTokenId`
+	InvalidTokenId,
+	/// `pallet_erc1155::Error::InsufficientBalance`
+	InsufficientBalance,
+	/// `pallet_erc1155::Error::NumOverflow`, also returned when burning or transferring more than the balance.
+	NumOverflow,
+	/// `pallet_erc1155::Error::InvalidArrayLength`
+	InvalidArrayLength,
+	/// `pallet_erc1155::Error::Overflow`
+	Overflow,
+	/// `pallet_erc1155::Error::NoAvailableTaoId`
+	NoAvailableTaoId,
+	/// `pallet_erc1155::Error::InvalidTaoId`
+	InvalidTaoId,
+	/// `pallet_erc1155::Error::NoPermission`, the contract does not own the tao or is not approved.
+	NoPermission,
+	/// `pallet_erc1155::Error::KeyTooLong`
+	KeyTooLong,
+	/// `pallet_erc1155::Error::ValueTooLong`
+	ValueTooLong,
+	/// `pallet_erc1155::Error::AttributeNotFound`
+	AttributeNotFound,
+	/// `pallet_dex::Error::DeadlineExpired`
+	DeadlineExpired,
+	/// `pallet_dex::Error::SlippageExceeded` at the index of the token id.
+	SlippageExceeded(u32),
+	/// The EVM contract reverted.
+	EvmReverted,
+	/// The EVM call failed, e.g. ran out of gas.
+	EvmFailed,
+	/// Too many calls between ink! and EVM contracts are nested.
+	BridgeDepthExceeded,
+	/// A code this version does not know, returned by a newer runtime.
+	Other(u32),
+}
+
+impl StatusCode {
+	/// The code of [`StatusCode::SlippageExceeded`] at the first token id, the codes from it on are the later ones.
+	pub const SLIPPAGE_EXCEEDED: u32 = 2_000_000_000;
+
+	/// The code returned by the chain extension.
+	pub fn code(self) -> u32 {
+		match self {
+			Self::Success => 0,
+			Self::UnknownFuncId => 1,
+			Self::Unknown => 1001,
+			Self::InUse => 1002,
+			Self::InvalidTokenId => 1003,
+			Self::InsufficientBalance => 1004,
+			Self::NumOverflow => 1005,
+			Self::InvalidArrayLength => 1006,
+			Self::Overflow => 1007,
+			Self::NoAvailableTaoId => 1008,
+			Self::InvalidTaoId => 1009,
+			Self::NoPermission => 1010,
+			Self::KeyTooLong => 1011,
+			Self::ValueTooLong => 1012,
+			Self::AttributeNotFound => 1013,
+			Self::DeadlineExpired => 2001,
+			Self::SlippageExceeded(index) => Self::SLIPPAGE_EXCEEDED.saturating_add(index),
+			Self::EvmReverted => 4001,
+			Self::EvmFailed => 4002,
+			Self::BridgeDepthExceeded => 4003,
+			Self::Other(code) => code,
+		}
+	}
+
+	/// The status of a code returned by the chain extension, [`StatusCode::Other`] if this version does not know it.
+	pub fn from_u32(code: u32) -> Self {
+		match code {
+			0 => Self::Success,
+			1 => Self::UnknownFuncId,
+			1001 => Self::Unknown,
+			1002 => Self::InUse,
+			1003 => Self::InvalidTokenId,
+			1004 => Self::InsufficientBalance,
+			1005 => Self::NumOverflow,
+			1006 => Self::InvalidArrayLength,
+			1007 => Self::Overflow,
+			1008 => Self::NoAvailableTaoId,
+			1009 => Self::InvalidTaoId,
+			1010 => Self::NoPermission,
+			1011 => Self::KeyTooLong,
+			1012 => Self::ValueTooLong,
+			1013 => Self::AttributeNotFound,
+			2001 => Self::DeadlineExpired,
+			4001 => Self::EvmReverted,
+			4002 => Self::EvmFailed,
+			4003 => Self::BridgeDepthExceeded,
+			code if code >= Self::SLIPPAGE_EXCEEDED => Self::SlippageExceeded(code - Self::SLIPPAGE_EXCEEDED),
+			code => Self::Other(code),
+		}
+	}
+}
+
+#[cfg(feature = "ink")]
+impl ink_env::chain_extension::FromStatusCode for StatusCode {
+	fn from_status_code(status_code: u32) -> Result<(), Self> {
+		match Self::from_u32(status_code) {
+			Self::Success => Ok(()),
+			status => Err(status),
+		}
+	}
+}
+
+// func_id 1002
+// do_create_tao(who: &T::AccountId, data: Vec<u8>) -> Result<T::TaoId, DispatchError>
+// `who` is the calling contract, which becomes the tao owner
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct CreateTaoInputParam {
+	pub data: Vec<u8>,
+}
+
+// func_id 1003
+// do_create_token(
+// 		who: &T::AccountId,
+// 		tao_id: T::TaoId,
+// 		token_id: T::TokenId,
+// 		is_nf: bool,
+// 		uri: Vec<u8>,
+// 	)
+// `who` is the calling contract, which must be the tao owner
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct CreateTokenInputParam<TaoId, TokenId> {
+	pub tao_id: TaoId,
+	pub token_id: TokenId,
+	pub is_nf: bool,
+	pub uri: Vec<u8>,
+}
+
+// func_id 1004
+// do_set_approval_for_all(
+// 		owner: &T::AccountId,
+// 		operator: &T::AccountId,
+// 		approved: bool,
+// 	)
+// `owner` is the calling contract
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct SetApprovalForAllInputParam<AccountId> {
+	pub operator: AccountId,
+	pub approved: bool,
+}
+
+// func_id 1005
+// do_mint(
+// 		to: &T::AccountId,
+// 		tao_id: T::TaoId,
+// 		token_id: T::TokenId,
+// 		amount: Balance
+// 	)
+// the calling contract must be the tao owner
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct MintInputParam<AccountId, TaoId, TokenId, Balance> {
+	pub to: AccountId,
+	pub tao_id: TaoId,
+	pub token_id: TokenId,
+	pub amount: Balance,
+}
+
+// func_id 1006
+// do_batch_mint(
+// 		to: &T::AccountId,
+// 		tao_id: T::TaoId,
+// 		token_ids: Vec<T::TokenId>,
+// 		amounts: Vec<Balance>
+// 	)
+// the calling contract must be the tao owner
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct BatchMintInputParam<AccountId, TaoId, TokenId, Balance> {
+	pub to: AccountId,
+	pub tao_id: TaoId,
+	pub token_ids: Vec<TokenId>,
+	pub amounts: Vec<Balance>,
+}
+
+// func_id 1007
+// do_burn(
+// 		from: &T::AccountId,
+// 		tao_id: T::TaoId,
+// 		token_id: T::TokenId,
+// 		amount: Balance
+// 	)
+// the calling contract must be `from` or approved by it
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct BurnInputParam<AccountId, TaoId, TokenId, Balance> {
+	pub from: AccountId,
+	pub tao_id: TaoId,
+	pub token_id: TokenId,
+	pub amount: Balance,
+}
+
+// func_id 1008
+// do_batch_burn(
+// 		from: &T::AccountId,
+// 		tao_id: T::TaoId,
+// 		token_ids: Vec<T::TokenId>,
+// 		amounts: Vec<Balance>
+// 	)
+// the calling contract must be `from` or approved by it
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct BatchBurnInputParam<AccountId, TaoId, TokenId, Balance> {
+	pub from: AccountId,
+	pub tao_id: TaoId,
+	pub token_ids: Vec<TokenId>,
+	pub amounts: Vec<Balance>,
+}
+
+// func_id 1009
+// do_transfer_from(
+// 		from: &T::AccountId,
+// 		to: &T::AccountId,
+// 		tao_id: T::TaoId,
+// 		token_id: T::TokenId,
+// 		amount: Balance
+// 	)
+// the calling contract must be `from` or approved by it
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct TransferFromInputParam<AccountId, TaoId, TokenId, Balance> {
+	pub from: AccountId,
+	pub to: AccountId,
+	pub tao_id: TaoId,
+	pub token_id: TokenId,
+	pub amount: Balance,
+}
+
+// func_id 1010
+// do_batch_transfer_from(
+// 		from: &T::AccountId,
+// 		to: &T::AccountId,
+// 		tao_id: T::TaoId,
+// 		token_ids: Vec<T::TokenId>,
+// 		amounts: Vec<Balance>
+// 	)
+// the calling contract must be `from` or approved by it
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct BatchTransferFromInputParam<AccountId, TaoId, TokenId, Balance> {
+	pub from: AccountId,
+	pub to: AccountId,
+	pub tao_id: TaoId,
+	pub token_ids: Vec<TokenId>,
+	pub amounts: Vec<Balance>,
+}
+
+// func_id 1011
+// approved_or_owner(who: &T::AccountId, account: &T::AccountId) -> bool
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct ApprovedOrOwnerInputParam<AccountId> {
+	pub who: AccountId,
+	pub account: AccountId,
+}
+
+// func_id 1012
+// is_approved_for_all(owner: &T::AccountId, operator: &T::AccountId) -> bool
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct IsApprovedForAllInputParam<AccountId> {
+	pub owner: AccountId,
+	pub operator: AccountId,
+}
+
+// func_id 1013
+// fn balance_of(owner: &T::AccountId, tao_id: T::TaoId, token_id: T::TokenId) -> Balance
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct BalanceOfInputParam<AccountId, TaoId, TokenId> {
+	pub owner: AccountId,
+	pub tao_id: TaoId,
+	pub token_id: TokenId,
+}
+
+// func_id 1014
+// balance_of_batch(owners: &Vec<T::AccountId>, tao_id: T::TaoId, token_ids: Vec<T::TokenId>) -> Result<Vec<Balance>, DispatchError>
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct BalanceOfBatchInputParam<AccountId, TaoId, TokenId> {
+	pub owners: Vec<AccountId>,
+	pub tao_id: TaoId,
+	pub token_ids: Vec<TokenId>,
+}
+
+// func_id 1015
+// do_set_attribute(
+// 		who: &T::AccountId,
+// 		tao_id: T::TaoId,
+// 		token_id: Option<T::TokenId>,
+// 		key: Vec<u8>,
+// 		value: Vec<u8>,
+// 	)
+// `who` is the calling contract, which must be the tao owner or its game server
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct SetAttributeInputParam<TaoId, TokenId> {
+	pub tao_id: TaoId,
+	pub token_id: Option<TokenId>,
+	pub key: Vec<u8>,
+	pub value: Vec<u8>,
+}
+
+// func_id 1016
+// do_clear_attribute(
+// 		who: &T::AccountId,
+// 		tao_id: T::TaoId,
+// 		token_id: Option<T::TokenId>,
+// 		key: Vec<u8>,
+// 	)
+// `who` is the calling contract, which must be the tao owner or its game server
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct ClearAttributeInputParam<TaoId, TokenId> {
+	pub tao_id: TaoId,
+	pub token_id: Option<TokenId>,
+	pub key: Vec<u8>,
+}
+
+// func_id 1017
+// attribute(tao_id: T::TaoId, token_id: Option<T::TokenId>, key: Vec<u8>) -> Option<Vec<u8>>
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct AttributeInputParam<TaoId, TokenId> {
+	pub tao_id: TaoId,
+	pub token_id: Option<TokenId>,
+	pub key: Vec<u8>,
+}
+
+// func_id 1018
+// taos(tao_id: T::TaoId) -> Option<Tao<T::AccountId>>
+// returns `Option<TaoView>`, `None` if the tao does not exist
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct TaoInputParam<TaoId> {
+	pub tao_id: TaoId,
+}
+
+// func_id 1019
+// tokens(tao_id: T::TaoId, token_id: T::TokenId) -> Option<Token<T::TaoId, T::AccountId>>
+// returns `Option<TokenView>`, `None` if the token does not exist
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct TokenInputParam<TaoId, TokenId> {
+	pub tao_id: TaoId,
+	pub token_id: TokenId,
+}
+
+// func_id 1020
+// next_tao_id() -> T::TaoId
+// takes no input
+
+// func_id 1021
+// request_randomness() -> RequestId
+// takes no input, returns the `u64` id of the request
+
+// func_id 1022
+// take_randomness(request_id: RequestId) -> Option<T::Hash>
+// takes the `u64` id of the request, returns `None` while its round is open
+
+/// A tao, as returned by func_id 1018
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct TaoView<AccountId> {
+	pub owner: AccountId,
+	pub data: Vec<u8>,
+}
+
+/// A token, as returned by func_id 1019
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct TokenView<TaoId, AccountId> {
+	pub tao_id: TaoId,
+	pub creator: AccountId,
+	pub is_nf: bool,
+	pub uri: Vec<u8>,
+}
+
+// func_id 2001
+// do_buy_tokens(
+// 		who: &T::AccountId,
+// 		exchange_id: ExchangeId,
+// 		token_ids: Vec<T::TokenId>,
+// 		token_amounts_out: Vec<Balance>,
+// 		max_currencys: Vec<Balance>,
+// 		to: &T::AccountId,
+// 		deadline: Option<T::BlockNumber>,
+// 	)
+// `who` is the calling contract, which pays the currency
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct BuyTokensInputParam<AccountId, ExchangeId, TokenId, Balance, BlockNumber> {
+	pub exchange_id: ExchangeId,
+	pub token_ids: Vec<TokenId>,
+	pub token_amounts_out: Vec<Balance>,
+	pub max_currencys: Vec<Balance>,
+	pub to: AccountId,
+	pub deadline: Option<BlockNumber>,
+}
+
+// func_id 2002
+// do_sell_tokens(
+// 		who: &T::AccountId,
+// 		exchange_id: ExchangeId,
+// 		token_ids: Vec<T::TokenId>,
+// 		token_amounts_in: Vec<Balance>,
+// 		min_currencys: Vec<Balance>,
+// 		to: &T::AccountId,
+// 		deadline: Option<T::BlockNumber>,
+// 	)
+// `who` is the calling contract, which pays the tokens
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct SellTokensInputParam<AccountId, ExchangeId, TokenId, Balance, BlockNumber> {
+	pub exchange_id: ExchangeId,
+	pub token_ids: Vec<TokenId>,
+	pub token_amounts_in: Vec<Balance>,
+	pub min_currencys: Vec<Balance>,
+	pub to: AccountId,
+	pub deadline: Option<BlockNumber>,
+}
+
+// func_id 2003
+// do_add_liquidity(
+// 		who: &T::AccountId,
+// 		exchange_id: ExchangeId,
+// 		to: &T::AccountId,
+// 		token_ids: Vec<T::TokenId>,
+// 		token_amounts: Vec<Balance>,
+// 		max_currencys: Vec<Balance>,
+// 		deadline: Option<T::BlockNumber>,
+// 	)
+// `who` is the calling contract, which pays the tokens and currency
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct AddLiquidityInputParam<AccountId, ExchangeId, TokenId, Balance, BlockNumber> {
+	pub exchange_id: ExchangeId,
+	pub to: AccountId,
+	pub token_ids: Vec<TokenId>,
+	pub token_amounts: Vec<Balance>,
+	pub max_currencys: Vec<Balance>,
+	pub deadline: Option<BlockNumber>,
+}
+
+// func_id 2004
+// do_remove_liquidity(
+// 		who: &T::AccountId,
+// 		exchange_id: ExchangeId,
+// 		to: &T::AccountId,
+// 		token_ids: Vec<T::TokenId>,
+// 		liquidities: Vec<Balance>,
+// 		min_currencys: Vec<Balance>,
+// 		min_tokens: Vec<Balance>,
+// 		deadline: Option<T::BlockNumber>,
+// 	)
+// `who` is the calling contract, which pays the liquidity tokens
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct RemoveLiquidityInputParam<AccountId, ExchangeId, TokenId, Balance, BlockNumber> {
+	pub exchange_id: ExchangeId,
+	pub to: AccountId,
+	pub token_ids: Vec<TokenId>,
+	pub liquidities: Vec<Balance>,
+	pub min_currencys: Vec<Balance>,
+	pub min_tokens: Vec<Balance>,
+	pub deadline: Option<BlockNumber>,
+}
+
+// func_id 2005
+// get_buy_prices(exchange_id: ExchangeId, token_ids: Vec<T::TokenId>, token_amounts_out: Vec<Balance>) -> Result<Vec<Balance>, DispatchError>
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct GetBuyPricesInputParam<ExchangeId, TokenId, Balance> {
+	pub exchange_id: ExchangeId,
+	pub token_ids: Vec<TokenId>,
+	pub token_amounts_out: Vec<Balance>,
+}
+
+// func_id 2006
+// get_sell_prices(exchange_id: ExchangeId, token_ids: Vec<T::TokenId>, token_amounts_in: Vec<Balance>) -> Result<Vec<Balance>, DispatchError>
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct GetSellPricesInputParam<ExchangeId, TokenId, Balance> {
+	pub exchange_id: ExchangeId,
+	pub token_ids: Vec<TokenId>,
+	pub token_amounts_in: Vec<Balance>,
+}
+
+// func_id 3001
+// get_currency_token(currency_id: CurrencyId) -> Result<(T::TaoId, T::TokenId), DispatchError>
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct GetCurrencyTokenInputParam<CurrencyId> {
+	pub currency_id: CurrencyId,
+}
+
+// func_id 3002
+// do_wrap(who: &T::AccountId, currency_id: CurrencyId, amount: Balance)
+// `who` is the calling contract, whose currency is wrapped
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct WrapInputParam<CurrencyId, Balance> {
+	pub currency_id: CurrencyId,
+	pub amount: Balance,
+}
+
+// func_id 3003
+// do_unwrap(who: &T::AccountId, currency_id: CurrencyId, amount: Balance)
+// `who` is the calling contract, whose token is unwrapped
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct UnwrapInputParam<CurrencyId, Balance> {
+	pub currency_id: CurrencyId,
+	pub amount: Balance,
+}
+
+// func_id 4001
+// Runner::call(source, target, input, value: 0, gas_limit, gas_price: None, nonce: None, config)
+// `source` is the EVM address of the calling contract, the output is the return data of the EVM contract
+#[derive(Clone, Encode, Decode, PartialEq, Eq, Debug)]
+pub struct EvmCallInputParam {
+	pub target: [u8; 20],
+	pub input: Vec<u8>,
+	pub gas_limit: u64,
+}
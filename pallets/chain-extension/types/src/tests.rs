@@ -0,0 +1,108 @@
+use super::*;
+use alloc::vec;
+
+type AccountId = [u8; 32];
+
+const ALICE: AccountId = [1u8; 32];
+const BOB: AccountId = [2u8; 32];
+
+fn assert_round_trip<T: Encode + Decode + PartialEq + core::fmt::Debug>(value: T) {
+	let encoded = value.encode();
+	assert_eq!(T::decode(&mut &encoded[..]), Ok(value));
+}
+
+#[test]
+fn erc1155_inputs_round_trip() {
+	assert_round_trip(CreateTaoInputParam { data: vec![1, 2, 3] });
+	assert_round_trip(CreateTokenInputParam { tao_id: 1u64, token_id: 2u64, is_nf: true, uri: vec![4] });
+	assert_round_trip(SetApprovalForAllInputParam { operator: BOB, approved: true });
+	assert_round_trip(MintInputParam { to: BOB, tao_id: 1u64, token_id: 2u64, amount: 10u128 });
+	assert_round_trip(BatchMintInputParam { to: BOB, tao_id: 1u64, token_ids: vec![1u64, 2], amounts: vec![10u128, 20] });
+	assert_round_trip(BurnInputParam { from: ALICE, tao_id: 1u64, token_id: 2u64, amount: 10u128 });
+	assert_round_trip(BatchBurnInputParam { from: ALICE, tao_id: 1u64, token_ids: vec![1u64], amounts: vec![10u128] });
+	assert_round_trip(TransferFromInputParam { from: ALICE, to: BOB, tao_id: 1u64, token_id: 2u64, amount: 10u128 });
+	assert_round_trip(BatchTransferFromInputParam { from: ALICE, to: BOB, tao_id: 1u64, token_ids: vec![2u64], amounts: vec![10u128] });
+	assert_round_trip(ApprovedOrOwnerInputParam { who: ALICE, account: BOB });
+	assert_round_trip(IsApprovedForAllInputParam { owner: ALICE, operator: BOB });
+	assert_round_trip(BalanceOfInputParam { owner: ALICE, tao_id: 1u64, token_id: 2u64 });
+	assert_round_trip(BalanceOfBatchInputParam { owners: vec![ALICE, BOB], tao_id: 1u64, token_ids: vec![1u64, 2] });
+	assert_round_trip(SetAttributeInputParam { tao_id: 1u64, token_id: Some(2u64), key: vec![1], value: vec![2] });
+	assert_round_trip(ClearAttributeInputParam { tao_id: 1u64, token_id: None::<u64>, key: vec![1] });
+	assert_round_trip(AttributeInputParam { tao_id: 1u64, token_id: Some(2u64), key: vec![1] });
+	assert_round_trip(TaoInputParam { tao_id: 1u64 });
+	assert_round_trip(TokenInputParam { tao_id: 1u64, token_id: 2u64 });
+}
+
+#[test]
+fn views_round_trip() {
+	assert_round_trip(Some(TaoView { owner: ALICE, data: vec![1, 2] }));
+	assert_round_trip(Some(TokenView { tao_id: 1u64, creator: ALICE, is_nf: true, uri: vec![3] }));
+	assert_round_trip(None::<TaoView<AccountId>>);
+}
+
+#[test]
+fn dex_and_currency_token_inputs_round_trip() {
+	assert_round_trip(BuyTokensInputParam {
+		exchange_id: 1u32, token_ids: vec![1u64], token_amounts_out: vec![10u128], max_currencys: vec![100u128], to: BOB, deadline: Some(9u32),
+	});
+	assert_round_trip(SellTokensInputParam {
+		exchange_id: 1u32, token_ids: vec![1u64], token_amounts_in: vec![10u128], min_currencys: vec![1u128], to: BOB, deadline: None::<u32>,
+	});
+	assert_round_trip(AddLiquidityInputParam {
+		exchange_id: 1u32, to: BOB, token_ids: vec![1u64], token_amounts: vec![10u128], max_currencys: vec![100u128], deadline: Some(9u32),
+	});
+	assert_round_trip(RemoveLiquidityInputParam {
+		exchange_id: 1u32, to: BOB, token_ids: vec![1u64], liquidities: vec![10u128], min_currencys: vec![1u128], min_tokens: vec![1u128],
+		deadline: None::<u32>,
+	});
+	assert_round_trip(GetBuyPricesInputParam { exchange_id: 1u32, token_ids: vec![1u64], token_amounts_out: vec![10u128] });
+	assert_round_trip(GetSellPricesInputParam { exchange_id: 1u32, token_ids: vec![1u64], token_amounts_in: vec![10u128] });
+	assert_round_trip(GetCurrencyTokenInputParam { currency_id: 7u32 });
+	assert_round_trip(WrapInputParam { currency_id: 7u32, amount: 10u128 });
+	assert_round_trip(UnwrapInputParam { currency_id: 7u32, amount: 10u128 });
+}
+
+#[test]
+fn evm_inputs_round_trip() {
+	assert_round_trip(EvmCallInputParam { target: [4u8; 20], input: vec![1, 2, 3], gas_limit: 100_000 });
+}
+
+#[test]
+fn inputs_are_fields_in_order() {
+	let input = MintInputParam { to: BOB, tao_id: 1u64, token_id: 2u64, amount: 10u128 };
+
+	assert_eq!(input.encode(), (BOB, 1u64, 2u64, 10u128).encode());
+}
+
+#[test]
+fn status_codes_round_trip() {
+	let codes = [
+		StatusCode::Success,
+		StatusCode::UnknownFuncId,
+		StatusCode::Unknown,
+		StatusCode::InUse,
+		StatusCode::InvalidTokenId,
+		StatusCode::InsufficientBalance,
+		StatusCode::NumOverflow,
+		StatusCode::InvalidArrayLength,
+		StatusCode::Overflow,
+		StatusCode::NoAvailableTaoId,
+		StatusCode::InvalidTaoId,
+		StatusCode::NoPermission,
+		StatusCode::KeyTooLong,
+		StatusCode::ValueTooLong,
+		StatusCode::AttributeNotFound,
+		StatusCode::DeadlineExpired,
+		StatusCode::SlippageExceeded(0),
+		StatusCode::SlippageExceeded(3),
+		StatusCode::EvmReverted,
+		StatusCode::EvmFailed,
+		StatusCode::BridgeDepthExceeded,
+		StatusCode::Other(2),
+	];
+	for code in codes.iter() {
+		assert_eq!(StatusCode::from_u32(code.code()), *code);
+	}
+	assert_eq!(StatusCode::SlippageExceeded(3).code(), 2_000_000_003);
+	assert_eq!(StatusCode::from_u32(2002), StatusCode::Other(2002));
+}
@@ -0,0 +1,392 @@
+//! Commit-reveal randomness.
+//!
+//! Participants, players or a game server, commit to the hash of a secret and reveal the
+//! secret in a later block, within `RevealPeriod`. A participant who withholds its reveal
+//! loses its deposit.
+//!
+//! Randomness requested in block `N` resolves from the reveals of commitments made before `N`
+//! and revealed after it, so no one knows it when it is requested, the block author included.
+//! It can be taken from block `N + RevealPeriod`, once every such commitment is revealed or
+//! past its period. A committer can still withhold its reveal to choose between two outcomes,
+//! at the cost of its deposit, and leaving a reveal out takes the authors of the whole period.
+//!
+//! [`Randomness`] is implemented too, from every reveal so far and the randomness of the block.
+//! Both are known when it is used, so it is no better than the block randomness against the
+//! block author: games should request randomness instead.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Encode, Decode};
+use sp_runtime::{
+	RuntimeDebug,
+	traits::{Hash, Saturating, UniqueSaturatedInto},
+};
+use frame_support::{
+	ensure,
+	dispatch::{DispatchResult, DispatchError},
+	traits::{Currency, ReservableCurrency, Randomness},
+};
+use sp_std::prelude::*;
+use primitives::randomness::{RandomnessRequests, RequestId};
+
+pub use pallet::*;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The randomness of the block, combined with the revealed seed.
+		type BlockRandomness: Randomness<Self::Hash>;
+
+		/// The amount reserved by a commitment, returned on reveal and slashed on expiry.
+		type CommitDeposit: Get<BalanceOf<Self>>;
+
+		/// The number of blocks after the commitment in which the secret must be revealed.
+		type RevealPeriod: Get<Self::BlockNumber>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	#[pallet::getter(fn commitments)]
+	pub(super) type Commitments<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Commitment<T::Hash, BalanceOf<T>, T::BlockNumber>
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn seed)]
+	pub(super) type Seed<T: Config> = StorageValue<
+		_,
+		T::Hash,
+		ValueQuery
+	>;
+
+	/// The seed of each block randomness was requested in, see [`Round`].
+	#[pallet::storage]
+	#[pallet::getter(fn rounds)]
+	pub(super) type Rounds<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Round<T::Hash>
+	>;
+
+	/// The rounds still taking reveals, oldest first.
+	#[pallet::storage]
+	#[pallet::getter(fn open_rounds)]
+	pub(super) type OpenRounds<T: Config> = StorageValue<
+		_,
+		Vec<T::BlockNumber>,
+		ValueQuery
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn requests)]
+	pub(super) type Requests<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		RequestId,
+		RandomnessRequest<T::AccountId, T::BlockNumber>
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn next_request_id)]
+	pub(super) type NextRequestId<T: Config> = StorageValue<
+		_,
+		RequestId,
+		ValueQuery
+	>;
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId", T::Hash = "Hash", BalanceOf<T> = "Balance", T::BlockNumber = "BlockNumber")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		Committed(T::AccountId, T::Hash),
+		Revealed(T::AccountId, T::Hash),
+		Expired(T::AccountId, BalanceOf<T>),
+		/// Randomness was requested, resolving from the round of the block.
+		RandomnessRequested(RequestId, T::AccountId, T::BlockNumber),
+		RandomnessTaken(RequestId, T::Hash),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		AlreadyCommitted,
+		NoCommitment,
+		RevealTooEarly,
+		RevealPeriodOver,
+		RevealPeriodNotOver,
+		InvalidSecret,
+		UnknownRequest,
+		NotRequester,
+		/// The round of the request is still taking reveals.
+		RoundOpen,
+		/// No commitment of the round was revealed, so it has no randomness. Request again.
+		NoReveals,
+		NoAvailableRequestId,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			// Commitments made before a round are revealed or past their period by its end
+			let period = T::RevealPeriod::get();
+			OpenRounds::<T>::mutate(|rounds| rounds.retain(|round| round.saturating_add(period) > now));
+
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+
+		/// Commit to `Pallet::commitment_of(who, secret)`, reserving `CommitDeposit`.
+		#[pallet::weight(T::WeightInfo::commit())]
+		pub fn commit(origin: OriginFor<T>, hash: T::Hash) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_commit(&who, hash)?;
+
+			Ok(().into())
+		}
+
+		/// Reveal the secret of a commitment made in an earlier block, mixing it into the seed
+		/// and into the open rounds after the commitment.
+		#[pallet::weight(T::WeightInfo::reveal(Pallet::<T>::max_open_rounds()))]
+		pub fn reveal(origin: OriginFor<T>, secret: T::Hash) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_reveal(&who, secret)?;
+
+			Ok(().into())
+		}
+
+		/// Remove a commitment whose reveal period is over, slashing its deposit.
+		#[pallet::weight(T::WeightInfo::expire())]
+		pub fn expire(origin: OriginFor<T>, committer: T::AccountId) -> DispatchResultWithPostInfo {
+			let _who = ensure_signed(origin)?;
+
+			Self::do_expire(&committer)?;
+
+			Ok(().into())
+		}
+
+		/// Request randomness, taken with `take_randomness` once its round is over.
+		#[pallet::weight(T::WeightInfo::request_randomness())]
+		pub fn request_randomness(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_request_randomness(&who)?;
+
+			Ok(().into())
+		}
+
+		/// Take the randomness of a request, reported in `RandomnessTaken`.
+		#[pallet::weight(T::WeightInfo::take_randomness())]
+		pub fn take_randomness(origin: OriginFor<T>, request_id: RequestId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_take_randomness(&who, request_id)?.ok_or(Error::<T>::RoundOpen)?;
+
+			Ok(().into())
+		}
+	}
+}
+
+/// The randomness of the requests made in one block `N`.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct Round<Hash> {
+	/// The reveals of commitments made before `N`, revealed after it
+	pub seed: Hash,
+	/// The number of reveals mixed into the seed
+	pub reveals: u32,
+	/// The requests of the round not taken yet
+	pub requests: u32,
+}
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct RandomnessRequest<AccountId, BlockNumber> {
+	/// The account the randomness is for
+	pub requester: AccountId,
+	/// The block of the request, the round it resolves from
+	pub round: BlockNumber,
+}
+
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct Commitment<Hash, Balance, BlockNumber> {
+	/// The hash of the committer and its secret
+	pub hash: Hash,
+	/// The reserved deposit
+	pub deposit: Balance,
+	/// The block of the commitment
+	pub committed_at: BlockNumber,
+}
+
+impl<T: Config> Pallet<T> {
+	/// The hash `who` commits to for `secret`. Binding the committer stops others from
+	/// replaying a revealed secret as their own.
+	pub fn commitment_of(who: &T::AccountId, secret: &T::Hash) -> T::Hash {
+		T::Hashing::hash_of(&(who, secret))
+	}
+
+	pub fn do_commit(who: &T::AccountId, hash: T::Hash) -> DispatchResult {
+		ensure!(!Commitments::<T>::contains_key(who), Error::<T>::AlreadyCommitted);
+
+		let deposit = T::CommitDeposit::get();
+		T::Currency::reserve(who, deposit)?;
+
+		Commitments::<T>::insert(who, Commitment {
+			hash,
+			deposit,
+			committed_at: frame_system::Module::<T>::block_number(),
+		});
+
+		Self::deposit_event(Event::Committed(who.clone(), hash));
+
+		Ok(())
+	}
+
+	pub fn do_reveal(who: &T::AccountId, secret: T::Hash) -> DispatchResult {
+		let commitment = Commitments::<T>::get(who).ok_or(Error::<T>::NoCommitment)?;
+		let now = frame_system::Module::<T>::block_number();
+
+		// revealing in the block of the commitment would let the author see both
+		ensure!(now > commitment.committed_at, Error::<T>::RevealTooEarly);
+		ensure!(now <= commitment.committed_at.saturating_add(T::RevealPeriod::get()), Error::<T>::RevealPeriodOver);
+		ensure!(Self::commitment_of(who, &secret) == commitment.hash, Error::<T>::InvalidSecret);
+
+		Seed::<T>::mutate(|seed| *seed = T::Hashing::hash_of(&(*seed, secret)));
+
+		// Rounds requested in the block of the reveal are left out, their author sees it first
+		for block in OpenRounds::<T>::get().into_iter().filter(|block| commitment.committed_at < *block && *block < now) {
+			Rounds::<T>::mutate(block, |round| if let Some(round) = round {
+				round.seed = T::Hashing::hash_of(&(round.seed, secret));
+				round.reveals = round.reveals.saturating_add(1);
+			});
+		}
+
+		Commitments::<T>::remove(who);
+		T::Currency::unreserve(who, commitment.deposit);
+
+		Self::deposit_event(Event::Revealed(who.clone(), secret));
+
+		Ok(())
+	}
+
+	pub fn do_expire(who: &T::AccountId) -> DispatchResult {
+		let commitment = Commitments::<T>::get(who).ok_or(Error::<T>::NoCommitment)?;
+		let now = frame_system::Module::<T>::block_number();
+
+		ensure!(now > commitment.committed_at.saturating_add(T::RevealPeriod::get()), Error::<T>::RevealPeriodNotOver);
+
+		Commitments::<T>::remove(who);
+		let (_, unslashed) = T::Currency::slash_reserved(who, commitment.deposit);
+
+		Self::deposit_event(Event::Expired(who.clone(), commitment.deposit.saturating_sub(unslashed)));
+
+		Ok(())
+	}
+
+	pub fn do_request_randomness(who: &T::AccountId) -> Result<RequestId, DispatchError> {
+		let request_id = NextRequestId::<T>::try_mutate(|id| -> Result<RequestId, DispatchError> {
+			let current_id = *id;
+			*id = id.checked_add(1).ok_or(Error::<T>::NoAvailableRequestId)?;
+			Ok(current_id)
+		})?;
+		let now = frame_system::Module::<T>::block_number();
+
+		Rounds::<T>::mutate(now, |round| {
+			let round = round.get_or_insert_with(|| {
+				OpenRounds::<T>::append(now);
+				Round::default()
+			});
+			round.requests = round.requests.saturating_add(1);
+		});
+		Requests::<T>::insert(request_id, RandomnessRequest { requester: who.clone(), round: now });
+
+		Self::deposit_event(Event::RandomnessRequested(request_id, who.clone(), now));
+
+		Ok(request_id)
+	}
+
+	/// The randomness of a request of `who`, or `None` while its round is open. The request is
+	/// removed once taken.
+	pub fn do_take_randomness(who: &T::AccountId, request_id: RequestId) -> Result<Option<T::Hash>, DispatchError> {
+		let request = Requests::<T>::get(request_id).ok_or(Error::<T>::UnknownRequest)?;
+		ensure!(request.requester == *who, Error::<T>::NotRequester);
+
+		let now = frame_system::Module::<T>::block_number();
+		if now < request.round.saturating_add(T::RevealPeriod::get()) {
+			return Ok(None);
+		}
+
+		let mut round = Rounds::<T>::get(request.round).ok_or(Error::<T>::UnknownRequest)?;
+		ensure!(round.reveals > 0, Error::<T>::NoReveals);
+		let randomness = T::Hashing::hash_of(&(round.seed, request_id));
+
+		Requests::<T>::remove(request_id);
+		round.requests = round.requests.saturating_sub(1);
+		if round.requests == 0 {
+			Rounds::<T>::remove(request.round);
+		} else {
+			Rounds::<T>::insert(request.round, round);
+		}
+
+		Self::deposit_event(Event::RandomnessTaken(request_id, randomness));
+
+		Ok(Some(randomness))
+	}
+
+	/// The most rounds open at once, one for each block of the reveal period.
+	pub fn max_open_rounds() -> u32 {
+		T::RevealPeriod::get().unique_saturated_into()
+	}
+}
+
+impl<T: Config> RandomnessRequests<T::AccountId, T::Hash> for Pallet<T> {
+	fn request(who: &T::AccountId) -> Result<RequestId, DispatchError> {
+		Self::do_request_randomness(who)
+	}
+
+	fn take(who: &T::AccountId, request_id: RequestId) -> Result<Option<T::Hash>, DispatchError> {
+		Self::do_take_randomness(who, request_id)
+	}
+}
+
+impl<T: Config> Randomness<T::Hash> for Pallet<T> {
+	/// The revealed seed combined with the randomness of the block, both for `subject`.
+	///
+	/// Known to the block author when used, see the module docs.
+	fn random(subject: &[u8]) -> T::Hash {
+		let block = T::BlockRandomness::random(subject);
+		T::Hashing::hash_of(&(Seed::<T>::get(), block, subject))
+	}
+}
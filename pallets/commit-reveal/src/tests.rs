@@ -0,0 +1,154 @@
+use crate::{Error, Round, mock::*};
+use frame_support::{assert_ok, assert_noop, traits::{OnInitialize, Randomness}};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+fn secret(n: u8) -> H256 {
+	H256::repeat_byte(n)
+}
+
+fn commit(who: u64, n: u8) {
+	assert_ok!(CommitReveal::commit(Origin::signed(who), CommitReveal::commitment_of(&who, &secret(n))));
+}
+
+#[test]
+fn commit_reserves_deposit() {
+	new_test_ext().execute_with(|| {
+		commit(ALICE, 1);
+
+		assert_eq!(Balances::reserved_balance(ALICE), 100);
+		assert_eq!(CommitReveal::commitments(ALICE).unwrap().committed_at, 1);
+		assert_noop!(
+			CommitReveal::commit(Origin::signed(ALICE), H256::zero()),
+			Error::<Test>::AlreadyCommitted,
+		);
+	})
+}
+
+#[test]
+fn reveal_mixes_secret_into_seed() {
+	new_test_ext().execute_with(|| {
+		commit(ALICE, 1);
+		commit(BOB, 2);
+		System::set_block_number(2);
+
+		assert_ok!(CommitReveal::reveal(Origin::signed(ALICE), secret(1)));
+		let seed = CommitReveal::seed();
+		assert_ne!(seed, H256::zero());
+
+		assert_ok!(CommitReveal::reveal(Origin::signed(BOB), secret(2)));
+		assert_ne!(CommitReveal::seed(), seed);
+
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert!(CommitReveal::commitments(ALICE).is_none());
+	})
+}
+
+#[test]
+fn reveal_must_match_commitment() {
+	new_test_ext().execute_with(|| {
+		commit(ALICE, 1);
+		System::set_block_number(2);
+
+		assert_noop!(CommitReveal::reveal(Origin::signed(ALICE), secret(2)), Error::<Test>::InvalidSecret);
+		// the commitment binds its committer
+		assert_noop!(CommitReveal::reveal(Origin::signed(BOB), secret(1)), Error::<Test>::NoCommitment);
+	})
+}
+
+#[test]
+fn reveal_only_in_reveal_period() {
+	new_test_ext().execute_with(|| {
+		commit(ALICE, 1);
+		assert_noop!(CommitReveal::reveal(Origin::signed(ALICE), secret(1)), Error::<Test>::RevealTooEarly);
+
+		System::set_block_number(12);
+		assert_noop!(CommitReveal::reveal(Origin::signed(ALICE), secret(1)), Error::<Test>::RevealPeriodOver);
+
+		System::set_block_number(11);
+		assert_ok!(CommitReveal::reveal(Origin::signed(ALICE), secret(1)));
+	})
+}
+
+#[test]
+fn expire_slashes_deposit() {
+	new_test_ext().execute_with(|| {
+		commit(ALICE, 1);
+
+		System::set_block_number(11);
+		assert_noop!(CommitReveal::expire(Origin::signed(BOB), ALICE), Error::<Test>::RevealPeriodNotOver);
+
+		System::set_block_number(12);
+		assert_ok!(CommitReveal::expire(Origin::signed(BOB), ALICE));
+
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(Balances::free_balance(ALICE), 900);
+		assert!(CommitReveal::commitments(ALICE).is_none());
+	})
+}
+
+#[test]
+fn random_depends_on_seed_and_subject() {
+	new_test_ext().execute_with(|| {
+		let before = CommitReveal::random(b"loot");
+		assert_ne!(CommitReveal::random(b"loot"), CommitReveal::random(b"quest"));
+		assert_eq!(CommitReveal::random_seed(), CommitReveal::random(&[]));
+
+		commit(ALICE, 1);
+		System::set_block_number(2);
+		assert_ok!(CommitReveal::reveal(Origin::signed(ALICE), secret(1)));
+
+		assert_ne!(CommitReveal::random(b"loot"), before);
+	})
+}
+
+#[test]
+fn requests_resolve_from_reveals_after_them() {
+	new_test_ext().execute_with(|| {
+		commit(ALICE, 1);
+		System::set_block_number(2);
+		// committed in the block of the request, so left out of its round
+		commit(BOB, 2);
+
+		assert_ok!(CommitReveal::request_randomness(Origin::signed(ALICE)));
+		assert_eq!(CommitReveal::open_rounds(), vec![2]);
+		// nothing of the randomness is known yet
+		assert_eq!(CommitReveal::rounds(2), Some(Round { seed: H256::zero(), reveals: 0, requests: 1 }));
+		assert_eq!(CommitReveal::do_take_randomness(&ALICE, 0), Ok(None));
+
+		System::set_block_number(3);
+		assert_ok!(CommitReveal::reveal(Origin::signed(ALICE), secret(1)));
+		assert_ok!(CommitReveal::reveal(Origin::signed(BOB), secret(2)));
+		let seed = BlakeTwo256::hash_of(&(H256::zero(), secret(1)));
+		assert_eq!(CommitReveal::rounds(2), Some(Round { seed, reveals: 1, requests: 1 }));
+
+		System::set_block_number(11);
+		assert_noop!(CommitReveal::take_randomness(Origin::signed(ALICE), 0), Error::<Test>::RoundOpen);
+
+		System::set_block_number(12);
+		CommitReveal::on_initialize(12);
+		assert!(CommitReveal::open_rounds().is_empty());
+		assert_noop!(CommitReveal::take_randomness(Origin::signed(BOB), 0), Error::<Test>::NotRequester);
+
+		assert_eq!(CommitReveal::do_take_randomness(&ALICE, 0), Ok(Some(BlakeTwo256::hash_of(&(seed, 0u64)))));
+		assert!(CommitReveal::rounds(2).is_none());
+		assert_noop!(CommitReveal::take_randomness(Origin::signed(ALICE), 0), Error::<Test>::UnknownRequest);
+	})
+}
+
+#[test]
+fn rounds_without_reveals_have_no_randomness() {
+	new_test_ext().execute_with(|| {
+		commit(ALICE, 1);
+		assert_ok!(CommitReveal::request_randomness(Origin::signed(BOB)));
+
+		// revealed in the block of the request
+		System::set_block_number(2);
+		assert_ok!(CommitReveal::request_randomness(Origin::signed(BOB)));
+		assert_ok!(CommitReveal::reveal(Origin::signed(ALICE), secret(1)));
+
+		System::set_block_number(12);
+		assert_noop!(CommitReveal::take_randomness(Origin::signed(BOB), 0), Error::<Test>::NoReveals);
+		assert_noop!(CommitReveal::take_randomness(Origin::signed(BOB), 1), Error::<Test>::NoReveals);
+	})
+}
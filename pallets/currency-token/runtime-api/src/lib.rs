@@ -0,0 +1,20 @@
+//! Runtime API definition for the currency token pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	pub trait CurrencyTokenApi<CurrencyId, AssetMetadata, TaoId, TokenId> where
+		CurrencyId: Codec,
+		AssetMetadata: Codec,
+		TaoId: Codec,
+		TokenId: Codec,
+	{
+		/// The name, symbol, decimals and existential deposit of a currency.
+		fn asset_metadata(currency_id: CurrencyId) -> Option<AssetMetadata>;
+
+		/// The ERC-1155 `(tao_id, token_id)` wrapping a currency.
+		fn currency_token(currency_id: CurrencyId) -> Option<(TaoId, TokenId)>;
+	}
+}
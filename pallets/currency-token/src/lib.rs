@@ -3,15 +3,26 @@
 use codec::{Encode, Decode};
 use sp_runtime::{
 	RuntimeDebug, ModuleId,
+	traits::AccountIdConversion,
 };
-use frame_support::dispatch::DispatchError;
-use sp_std::{fmt::Debug, prelude::*};
+use frame_support::{
+	ensure, transactional,
+	traits::Get,
+	dispatch::{DispatchResult, DispatchError},
+};
+use sp_std::{fmt::Debug, convert::TryInto, prelude::*};
 use orml_traits::{
     MultiCurrency, MultiCurrencyExtended,
 };
 use primitives::{CurrencyId, Balance};
 
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
 pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod weights;
 
 #[cfg(test)]
 mod mock;
@@ -30,6 +41,53 @@ pub mod pallet {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
         type ModuleId: Get<ModuleId>;
         type Currency: MultiCurrencyExtended<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+        /// The origin which may create the currency tao and register currency tokens.
+        type RegistryOrigin: EnsureOrigin<Self::Origin>;
+        /// The maximum length of an asset name or symbol.
+        type StringLimit: Get<u32>;
+        /// Weight information for the extrinsics of this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig {
+        /// The data of the currency tao, which is created if given
+        pub currency_tao: Option<Vec<u8>>,
+        /// The currencies to register in the currency tao
+        pub currencies: Vec<CurrencyId>,
+        /// The metadata of currencies, as `(currency_id, name, symbol, decimals, existential_deposit)`
+        pub assets: Vec<(CurrencyId, Vec<u8>, Vec<u8>, u8, Balance)>,
+    }
+
+    #[cfg(feature = "std")]
+    impl Default for GenesisConfig {
+        fn default() -> Self {
+            GenesisConfig {
+                currency_tao: None,
+                currencies: vec![],
+                assets: vec![],
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig {
+        fn build(&self) {
+            self.assets.iter().for_each(|(currency_id, name, symbol, decimals, existential_deposit)| {
+                Pallet::<T>::do_set_asset_metadata(*currency_id, name.to_vec(), symbol.to_vec(), *decimals, *existential_deposit)
+                    .expect("Set asset metadata cannot fail while building genesis");
+            });
+
+            if let Some(data) = &self.currency_tao {
+                Pallet::<T>::do_create_tao(data.to_vec())
+                    .expect("Create currency tao cannot fail while building genesis");
+            }
+
+            self.currencies.iter().for_each(|currency_id| {
+                Pallet::<T>::do_create_token(*currency_id)
+                    .expect("Create currency token cannot fail while building genesis");
+            })
+        }
     }
 
     #[pallet::pallet]
@@ -52,13 +110,45 @@ pub mod pallet {
 		ValueQuery
 	>;
 
+    #[pallet::storage]
+    #[pallet::getter(fn asset_metadatas)]
+    pub(super) type AssetMetadatas<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        CurrencyId,
+        AssetMetadata<Balance>
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_asset_id)]
+    pub(super) type NextAssetId<T: Config> = StorageValue<
+        _,
+        u32,
+        ValueQuery
+    >;
+
+    /// ERC-20 allowances of the ERC-20 precompiles, keyed by currency and `(owner, spender)`.
+    #[pallet::storage]
+    pub(super) type Allowances<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        CurrencyId,
+        Blake2_128Concat,
+        (T::AccountId, T::AccountId),
+        Balance,
+        ValueQuery
+    >;
+
     #[pallet::event]
     #[pallet::metadata(T::AccountId = "AccountId")]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
-        TokenCreated(CurrencyId, T::AccountId),
-        TokenMint(CurrencyId, Balance, T::AccountId),
-        TokenBurn(CurrencyId, Balance, T::AccountId),
+        TaoCreated(T::TaoId),
+        TokenCreated(CurrencyId, T::TokenId),
+        AssetMetadataSet(CurrencyId),
+        AssetRegistered(CurrencyId),
+        Wrapped(CurrencyId, Balance, T::AccountId),
+        Unwrapped(CurrencyId, Balance, T::AccountId),
     }
     
     // Errors inform users that something went wrong.
@@ -68,6 +158,11 @@ pub mod pallet {
         AlreadyTaoCreated,
         CurrencyTaoNotFound,
         CurrencyTokenNotFound,
+        BadMetadata,
+        InvalidCurrencyId,
+        NoAvailableAssetId,
+        /// The vault would hold less currency than the wrapped token supply
+        NotFullyBacked,
     }
 
     #[pallet::hooks]
@@ -75,96 +170,77 @@ pub mod pallet {
 
     #[pallet::call]
     impl<T:Config> Pallet<T> {
-        #[pallet::weight(10_000)]
+        #[pallet::weight(<T as Config>::WeightInfo::create_tao(data.len() as u32))]
         pub fn create_tao(origin: OriginFor<T>, data: Vec<u8>) -> DispatchResultWithPostInfo {
-            let who = ensure_signed(origin)?;
+            T::RegistryOrigin::ensure_origin(origin)?;
 
-            ensure!(!CurrencyTao::<T>::exists(), Error::<T>::AlreadyTaoCreated);
-
-            let tao_id = token::Module::<T>::do_create_tao(&who, data)?;
-
-            CurrencyTao::<T>::put(tao_id);
+            Self::do_create_tao(data)?;
 
             Ok(().into())
         }
 
-        #[pallet::weight(10_000)]
+        #[pallet::weight(<T as Config>::WeightInfo::create_token())]
         pub fn create_token(origin: OriginFor<T>, currency_id: CurrencyId) -> DispatchResultWithPostInfo {
-            let who = ensure_signed(origin)?;
+            T::RegistryOrigin::ensure_origin(origin)?;
 
-            ensure!(CurrencyTao::<T>::exists(), Error::<T>::CurrencyTaoNotFound);
-            let tao_id = CurrencyTao::<T>::get();
-    
-			let token_id = Self::convert_to_token_id(currency_id);
-			token::Module::<T>::do_create_token(&who, tao_id, token_id, false, [].to_vec())?;
-	
-			let token_info = TokenInfo {
-                tao_id,
-				token_id: token_id.clone(),
-				total_supply: Default::default()
-			};
-
-			CurrencyTokens::<T>::insert(currency_id, token_info);
-
-			Self::deposit_event(Event::TokenCreated(currency_id, who));
+            Self::do_create_token(currency_id)?;
 
             Ok(().into())
         }
 
-        #[pallet::weight(10_000)]
-        pub fn mint(origin: OriginFor<T>, currency_id: CurrencyId, amount: Balance) -> DispatchResultWithPostInfo {
-            let who = ensure_signed(origin)?;
-
-            ensure!(CurrencyTao::<T>::exists(), Error::<T>::CurrencyTaoNotFound);
-            let tao_id = CurrencyTao::<T>::get();
-
-            T::Currency::deposit(currency_id, &who, amount)?;
+        #[pallet::weight(<T as Config>::WeightInfo::set_asset_metadata((name.len() + symbol.len()) as u32))]
+        pub fn set_asset_metadata(
+            origin: OriginFor<T>,
+            currency_id: CurrencyId,
+            name: Vec<u8>,
+            symbol: Vec<u8>,
+            decimals: u8,
+            existential_deposit: Balance,
+        ) -> DispatchResultWithPostInfo {
+            T::RegistryOrigin::ensure_origin(origin)?;
 
-            CurrencyTokens::<T>::try_mutate(currency_id, |token_info| -> DispatchResult {
-                let info = token_info
-                    .as_mut()
-                    .ok_or(Error::<T>::CurrencyTokenNotFound)?;
+            Self::do_set_asset_metadata(currency_id, name, symbol, decimals, existential_deposit)?;
 
-                token::Module::<T>::do_mint(&who, tao_id, info.token_id, amount)?;
+            Ok(().into())
+        }
 
-                info.total_supply = info
-                    .total_supply
-                    .checked_add(amount)
-                    .ok_or(Error::<T>::NumOverflow)?;
-                Ok(())
-            })?;
+        /// Allocate a new `CurrencyId::Registered` id with its metadata, and create its
+        /// currency token if the currency tao exists.
+        #[pallet::weight(<T as Config>::WeightInfo::register_asset((name.len() + symbol.len()) as u32))]
+        pub fn register_asset(
+            origin: OriginFor<T>,
+            name: Vec<u8>,
+            symbol: Vec<u8>,
+            decimals: u8,
+            existential_deposit: Balance,
+        ) -> DispatchResultWithPostInfo {
+            T::RegistryOrigin::ensure_origin(origin)?;
 
-			Self::deposit_event(Event::TokenMint(currency_id, amount, who));
+            Self::do_register_asset(name, symbol, decimals, existential_deposit)?;
 
-			Ok(().into())
+            Ok(().into())
         }
 
-        #[pallet::weight(10_000)]
-        pub fn burn(origin: OriginFor<T>, currency_id: CurrencyId, amount: Balance) -> DispatchResultWithPostInfo {
+        /// Lock `amount` of `currency_id` in the vault and mint the same amount of its ERC-1155 token.
+        #[pallet::weight(<T as Config>::WeightInfo::wrap())]
+        #[transactional]
+        pub fn wrap(origin: OriginFor<T>, currency_id: CurrencyId, amount: Balance) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
 
-            ensure!(CurrencyTao::<T>::exists(), Error::<T>::CurrencyTaoNotFound);
-            let tao_id = CurrencyTao::<T>::get();
-
-            T::Currency::withdraw(currency_id, &who, amount)?;
+            Self::do_wrap(&who, currency_id, amount)?;
 
-            CurrencyTokens::<T>::try_mutate(currency_id, |token_info| -> DispatchResult {
-                let info = token_info
-					.as_mut()
-                    .ok_or(Error::<T>::CurrencyTokenNotFound)?;
-                
-                token::Module::<T>::do_burn(&who, tao_id, info.token_id, amount)?;
+            Ok(().into())
+        }
 
-				info.total_supply = info
-					.total_supply
-					.checked_sub(amount)
-					.ok_or(Error::<T>::NumOverflow)?;
-				Ok(())
-            })?;
+        /// Burn `amount` of the ERC-1155 token of `currency_id` and release the same amount from the vault.
+        #[pallet::weight(<T as Config>::WeightInfo::unwrap())]
+        #[transactional]
+        pub fn unwrap(origin: OriginFor<T>, currency_id: CurrencyId, amount: Balance) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
 
-			Self::deposit_event(Event::TokenBurn(currency_id, amount, who));
+            Self::do_unwrap(&who, currency_id, amount)?;
 
-			Ok(().into())
+            Ok(().into())
         }
     }
 }
@@ -180,14 +256,224 @@ pub struct TokenInfo<
 	total_supply: Balance,
 }
 
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AssetMetadata<
+    Balance: Encode + Decode + Clone + Debug + Eq + PartialEq,
+> {
+    /// The display name of the asset
+    pub name: Vec<u8>,
+    /// The ticker symbol of the asset
+    pub symbol: Vec<u8>,
+    /// The number of decimals of the asset
+    pub decimals: u8,
+    /// The minimum balance of the asset an account may hold
+    pub existential_deposit: Balance,
+}
+
 impl<T: Config> Pallet<T> {
+    /// The account holding the currencies backing every wrapped token
+    pub fn account_id() -> T::AccountId {
+        T::ModuleId::get().into_account()
+    }
+
+    /// The currency tao is owned by the vault, not by whoever created it
+    pub fn do_create_tao(data: Vec<u8>) -> Result<T::TaoId, DispatchError> {
+        ensure!(!CurrencyTao::<T>::exists(), Error::<T>::AlreadyTaoCreated);
+
+        let tao_id = token::Module::<T>::do_create_tao(&Self::account_id(), data)?;
+
+        CurrencyTao::<T>::put(tao_id);
+
+        Self::deposit_event(Event::TaoCreated(tao_id));
+
+        Ok(tao_id)
+    }
+
+    pub fn do_create_token(currency_id: CurrencyId) -> Result<T::TokenId, DispatchError> {
+        ensure!(CurrencyTao::<T>::exists(), Error::<T>::CurrencyTaoNotFound);
+        let tao_id = CurrencyTao::<T>::get();
+
+        let token_id = Self::convert_to_token_id(currency_id)?;
+        let uri = Self::asset_metadatas(currency_id)
+            .map(|metadata| Self::metadata_uri(&metadata))
+            .unwrap_or_default();
+        token::Module::<T>::do_create_token(&Self::account_id(), tao_id, token_id, false, uri)?;
+
+        let token_info = TokenInfo {
+            tao_id,
+            token_id: token_id.clone(),
+            total_supply: Default::default()
+        };
+
+        CurrencyTokens::<T>::insert(currency_id, token_info);
+
+        Self::deposit_event(Event::TokenCreated(currency_id, token_id));
+
+        Ok(token_id)
+    }
+
+    pub fn do_set_asset_metadata(
+        currency_id: CurrencyId,
+        name: Vec<u8>,
+        symbol: Vec<u8>,
+        decimals: u8,
+        existential_deposit: Balance,
+    ) -> DispatchResult {
+        let limit = T::StringLimit::get() as usize;
+        ensure!(name.len() <= limit && symbol.len() <= limit, Error::<T>::BadMetadata);
+
+        AssetMetadatas::<T>::insert(currency_id, AssetMetadata {
+            name,
+            symbol,
+            decimals,
+            existential_deposit,
+        });
+
+        Self::deposit_event(Event::AssetMetadataSet(currency_id));
+
+        Ok(())
+    }
+
+    pub fn do_register_asset(
+        name: Vec<u8>,
+        symbol: Vec<u8>,
+        decimals: u8,
+        existential_deposit: Balance,
+    ) -> Result<CurrencyId, DispatchError> {
+        let asset_id =
+            NextAssetId::<T>::try_mutate(|id| -> Result<u32, DispatchError> {
+                let current_id = *id;
+                *id = id
+                    .checked_add(1)
+                    .ok_or(Error::<T>::NoAvailableAssetId)?;
+                Ok(current_id)
+            })?;
+        let currency_id = CurrencyId::Registered(asset_id);
+
+        Self::do_set_asset_metadata(currency_id, name, symbol, decimals, existential_deposit)?;
+
+        if CurrencyTao::<T>::exists() {
+            Self::do_create_token(currency_id)?;
+        }
+
+        Self::deposit_event(Event::AssetRegistered(currency_id));
+
+        Ok(currency_id)
+    }
+
+    /// The ERC-1155 uri of a currency token, an inline json of its metadata:
+    /// `data:application/json,{"name":"..","symbol":"..","decimals":..}`
+    ///
+    /// The name and symbol are escaped as json strings.
+    pub fn metadata_uri(metadata: &AssetMetadata<Balance>) -> Vec<u8> {
+        let mut uri = b"data:application/json,{\"name\":\"".to_vec();
+        Self::extend_json_escaped(&mut uri, &metadata.name);
+        uri.extend_from_slice(b"\",\"symbol\":\"");
+        Self::extend_json_escaped(&mut uri, &metadata.symbol);
+        uri.extend_from_slice(b"\",\"decimals\":");
+        if metadata.decimals >= 100 {
+            uri.push(b'0' + metadata.decimals / 100);
+        }
+        if metadata.decimals >= 10 {
+            uri.push(b'0' + metadata.decimals / 10 % 10);
+        }
+        uri.push(b'0' + metadata.decimals % 10);
+        uri.push(b'}');
+        uri
+    }
+
+    /// Append `bytes` escaping `"`, `\` and control characters, to be inside a json string.
+    fn extend_json_escaped(uri: &mut Vec<u8>, bytes: &[u8]) {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        for &byte in bytes {
+            match byte {
+                b'"' | b'\\' => uri.extend_from_slice(&[b'\\', byte]),
+                0..=0x1f => uri.extend_from_slice(&[
+                    b'\\', b'u', b'0', b'0', HEX[(byte >> 4) as usize], HEX[(byte & 0xf) as usize],
+                ]),
+                _ => uri.push(byte),
+            }
+        }
+    }
+
+    #[transactional]
+    pub fn do_wrap(who: &T::AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+        ensure!(CurrencyTao::<T>::exists(), Error::<T>::CurrencyTaoNotFound);
+        let tao_id = CurrencyTao::<T>::get();
+
+        CurrencyTokens::<T>::try_mutate(currency_id, |token_info| -> DispatchResult {
+            let info = token_info
+                .as_mut()
+                .ok_or(Error::<T>::CurrencyTokenNotFound)?;
+
+            info.total_supply = info
+                .total_supply
+                .checked_add(amount)
+                .ok_or(Error::<T>::NumOverflow)?;
+
+            T::Currency::transfer(currency_id, who, &Self::account_id(), amount)?;
+            token::Module::<T>::do_mint(who, tao_id, info.token_id, amount)?;
+            Ok(())
+        })?;
+
+        Self::deposit_event(Event::Wrapped(currency_id, amount, who.clone()));
+
+        Ok(())
+    }
+
+    #[transactional]
+    pub fn do_unwrap(who: &T::AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+        ensure!(CurrencyTao::<T>::exists(), Error::<T>::CurrencyTaoNotFound);
+        let tao_id = CurrencyTao::<T>::get();
+
+        CurrencyTokens::<T>::try_mutate(currency_id, |token_info| -> DispatchResult {
+            let info = token_info
+                .as_mut()
+                .ok_or(Error::<T>::CurrencyTokenNotFound)?;
+
+            info.total_supply = info
+                .total_supply
+                .checked_sub(amount)
+                .ok_or(Error::<T>::NumOverflow)?;
+
+            token::Module::<T>::do_burn(who, tao_id, info.token_id, amount)?;
+            T::Currency::transfer(currency_id, &Self::account_id(), who, amount)?;
+            Ok(())
+        })?;
+        ensure!(Self::is_fully_backed(currency_id), Error::<T>::NotFullyBacked);
+
+        Self::deposit_event(Event::Unwrapped(currency_id, amount, who.clone()));
+
+        Ok(())
+    }
+
+    /// Whether the vault holds at least the total supply of the wrapped token of `currency_id`,
+    /// checked by every unwrap.
+    ///
+    /// Currencies sent straight to the vault, bypassing `wrap`, only add to the backing.
+    pub fn is_fully_backed(currency_id: CurrencyId) -> bool {
+        CurrencyTokens::<T>::get(currency_id)
+            .map(|info| T::Currency::free_balance(currency_id, &Self::account_id()) >= info.total_supply)
+            .unwrap_or(true)
+    }
+
+    /// The amount of `currency_id` of `owner` that `spender` may transfer through the ERC-20 precompile.
+    pub fn allowance(currency_id: CurrencyId, owner: &T::AccountId, spender: &T::AccountId) -> Balance {
+        Allowances::<T>::get(currency_id, (owner.clone(), spender.clone()))
+    }
+
+    pub fn set_allowance(currency_id: CurrencyId, owner: &T::AccountId, spender: &T::AccountId, amount: Balance) {
+        Allowances::<T>::insert(currency_id, (owner.clone(), spender.clone()), amount);
+    }
+
     pub fn get_currency_token(currency_id: CurrencyId) -> Result<(T::TaoId, T::TokenId), DispatchError> {
         let token_info = CurrencyTokens::<T>::get(currency_id).ok_or(Error::<T>::CurrencyTokenNotFound)?;
         Ok((token_info.tao_id, token_info.token_id))
     }
 
-    pub fn convert_to_token_id(id: CurrencyId) -> T::TokenId {
-        let n: u64 = id.into();
-        n.into()
+    pub fn convert_to_token_id(id: CurrencyId) -> Result<T::TokenId, DispatchError> {
+        let n: u64 = id.try_into().map_err(|_| Error::<T>::InvalidCurrencyId)?;
+        Ok(n.into())
     }
 }
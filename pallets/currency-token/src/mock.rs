@@ -1,13 +1,15 @@
-use crate as pallet_template;
+use crate as pallet_currency_token;
 use sp_core::H256;
 use frame_support::parameter_types;
 use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup}, testing::Header,
+    traits::{BlakeTwo256, IdentityLookup, Zero}, testing::Header, ModuleId,
 };
-use frame_system as system;
+use frame_system::{self as system, EnsureRoot};
+use orml_traits::parameter_type_with_key;
+use primitives::{Amount, Balance, CurrencyId, TokenSymbol};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
-type Block = frame_system::mocking::MockBlock<Test>; 
+type Block = frame_system::mocking::MockBlock<Test>;
 
 // Configure a mock runtime to test the pallet.
 frame_support::construct_runtime!(
@@ -17,7 +19,10 @@ frame_support::construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Module, Call, Config, Storage, Event<T>},
-        TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
+        Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+        Tokens: orml_tokens::{Module, Storage, Event<T>, Config<T>},
+        Token: token::{Module, Call, Storage, Event<T>},
+        CurrencyToken: pallet_currency_token::{Module, Call, Storage, Event<T>, Config},
     }
 );
 
@@ -44,18 +49,87 @@ impl system::Config for Test {
     type BlockHashCount = BlockHashCount;
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<Balance>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type SystemWeightInfo = ();
     type SS58Prefix = SS58Prefix;
 }
 
-impl pallet_template::Config for Test {
+parameter_types! {
+    pub const ExistentialDeposit: Balance = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = Balance;
+    type MaxLocks = ();
+    type Event = Event;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
+parameter_type_with_key! {
+    pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+        Zero::zero()
+    };
+}
+
+impl orml_tokens::Config for Test {
+    type Event = Event;
+    type Balance = Balance;
+    type Amount = Amount;
+    type CurrencyId = CurrencyId;
+    type WeightInfo = ();
+    type ExistentialDeposits = ExistentialDeposits;
+    type OnDust = ();
+}
+
+parameter_types! {
+    pub const AttributeDepositBase: Balance = 10;
+    pub const DepositPerByte: Balance = 1;
+    pub const KeyLimit: u32 = 32;
+    pub const ValueLimit: u32 = 256;
+}
+
+impl token::Config for Test {
     type Event = Event;
+    type TokenId = u64;
+    type TaoId = u64;
+    type Currency = Balances;
+    type AttributeDepositBase = AttributeDepositBase;
+    type DepositPerByte = DepositPerByte;
+    type KeyLimit = KeyLimit;
+    type ValueLimit = ValueLimit;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const CurrencyTokenModuleId: ModuleId = ModuleId(*b"sgc/curr");
+    pub const StringLimit: u32 = 16;
 }
 
+impl pallet_currency_token::Config for Test {
+    type Event = Event;
+    type ModuleId = CurrencyTokenModuleId;
+    type Currency = Tokens;
+    type RegistryOrigin = EnsureRoot<u64>;
+    type StringLimit = StringLimit;
+    type WeightInfo = ();
+}
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const DOT: CurrencyId = CurrencyId::Token(TokenSymbol::DOT);
+
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+    let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    orml_tokens::GenesisConfig::<Test> {
+        endowed_accounts: vec![(ALICE, DOT, 1000), (BOB, DOT, 1000)],
+    }
+        .assimilate_storage(&mut t)
+        .unwrap();
+    t.into()
 }
@@ -1,23 +1,271 @@
-use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use crate as pallet_currency_token;
+use crate::{AssetMetadata, Error, mock::*};
+use frame_support::{assert_ok, assert_noop, traits::GenesisBuild};
+use orml_traits::MultiCurrency;
+use primitives::{CurrencyId, TokenSymbol};
+use sp_runtime::DispatchError;
+
+fn setup_currency_token() -> (u64, u64) {
+    assert_ok!(CurrencyToken::create_tao(Origin::root(), vec![]));
+    assert_ok!(CurrencyToken::create_token(Origin::root(), DOT));
+    CurrencyToken::get_currency_token(DOT).unwrap()
+}
+
+#[test]
+fn registry_requires_registry_origin() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(CurrencyToken::create_tao(Origin::signed(ALICE), vec![]), DispatchError::BadOrigin);
+
+        assert_ok!(CurrencyToken::create_tao(Origin::root(), vec![]));
+        assert_noop!(CurrencyToken::create_tao(Origin::root(), vec![]), Error::<Test>::AlreadyTaoCreated);
+
+        assert_noop!(CurrencyToken::create_token(Origin::signed(ALICE), DOT), DispatchError::BadOrigin);
+        assert_ok!(CurrencyToken::create_token(Origin::root(), DOT));
+
+        // the currency tao belongs to the vault
+        let tao_id = CurrencyToken::currency_tao();
+        assert!(Token::is_tao_owner(&CurrencyToken::account_id(), tao_id));
+    });
+}
+
+#[test]
+fn genesis_registers_currencies() {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    GenesisBuild::<Test>::assimilate_storage(
+        &pallet_currency_token::GenesisConfig {
+            currency_tao: Some(b"currencies".to_vec()),
+            currencies: vec![
+                CurrencyId::Token(TokenSymbol::SGC),
+                CurrencyId::Token(TokenSymbol::DOT),
+            ],
+            assets: vec![
+                (CurrencyId::Token(TokenSymbol::DOT), b"Polkadot".to_vec(), b"DOT".to_vec(), 10, 0),
+            ],
+        },
+        &mut t,
+    )
+        .unwrap();
+
+    sp_io::TestExternalities::from(t).execute_with(|| {
+        assert!(CurrencyToken::get_currency_token(CurrencyId::Token(TokenSymbol::SGC)).is_ok());
+        assert!(CurrencyToken::get_currency_token(CurrencyId::Token(TokenSymbol::DOT)).is_ok());
+        assert!(CurrencyToken::get_currency_token(CurrencyId::Token(TokenSymbol::ACA)).is_err());
+
+        assert_eq!(CurrencyToken::asset_metadatas(CurrencyId::Token(TokenSymbol::DOT)).unwrap().decimals, 10);
+    });
+}
+
+#[test]
+fn set_asset_metadata_works() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            CurrencyToken::set_asset_metadata(Origin::signed(ALICE), DOT, b"Polkadot".to_vec(), b"DOT".to_vec(), 10, 0),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            CurrencyToken::set_asset_metadata(Origin::root(), DOT, vec![b'a'; 17], b"DOT".to_vec(), 10, 0),
+            Error::<Test>::BadMetadata
+        );
+
+        assert_ok!(CurrencyToken::set_asset_metadata(Origin::root(), DOT, b"Polkadot".to_vec(), b"DOT".to_vec(), 10, 1));
+
+        let metadata = CurrencyToken::asset_metadatas(DOT).unwrap();
+        assert_eq!(metadata.name, b"Polkadot".to_vec());
+        assert_eq!(metadata.symbol, b"DOT".to_vec());
+        assert_eq!(metadata.decimals, 10);
+        assert_eq!(metadata.existential_deposit, 1);
+    });
+}
+
+#[test]
+fn register_asset_works() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            CurrencyToken::register_asset(Origin::signed(ALICE), b"Gold".to_vec(), b"GLD".to_vec(), 2, 0),
+            DispatchError::BadOrigin
+        );
+
+        setup_currency_token();
+
+        assert_ok!(CurrencyToken::register_asset(Origin::root(), b"Gold".to_vec(), b"GLD".to_vec(), 2, 0));
+        assert_ok!(CurrencyToken::register_asset(Origin::root(), b"Gem".to_vec(), b"GEM".to_vec(), 0, 0));
+
+        let gold = CurrencyId::Registered(0);
+        let gem = CurrencyId::Registered(1);
+        assert_eq!(CurrencyToken::next_asset_id(), 2);
+        assert_eq!(CurrencyToken::asset_metadatas(gold).unwrap().symbol, b"GLD".to_vec());
+
+        // registered assets are wrapped like any other currency
+        let (tao_id, token_id) = CurrencyToken::get_currency_token(gold).unwrap();
+        assert_ne!(CurrencyToken::get_currency_token(gem).unwrap(), (tao_id, token_id));
+
+        assert_ok!(Tokens::deposit(gold, &BOB, 100));
+        assert_ok!(CurrencyToken::wrap(Origin::signed(BOB), gold, 40));
+        assert_eq!(Token::balance_of(&BOB, tao_id, token_id), 40);
+        assert!(CurrencyToken::is_fully_backed(gold));
+    });
+}
+
+#[test]
+fn create_token_uses_metadata_uri() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(CurrencyToken::set_asset_metadata(Origin::root(), DOT, b"Polkadot".to_vec(), b"DOT".to_vec(), 10, 0));
+        let (tao_id, token_id) = setup_currency_token();
+
+        assert_eq!(
+            Token::tokens(tao_id, token_id).unwrap().uri,
+            br#"data:application/json,{"name":"Polkadot","symbol":"DOT","decimals":10}"#.to_vec()
+        );
+    });
+}
+
+#[test]
+fn metadata_uri_escapes_json() {
+    let metadata = AssetMetadata {
+        name: b"a\"b\\c\n".to_vec(),
+        symbol: b"\"}".to_vec(),
+        decimals: 0,
+        existential_deposit: 0,
+    };
+
+    assert_eq!(
+        CurrencyToken::metadata_uri(&metadata),
+        br#"data:application/json,{"name":"a\"b\\c\u000a","symbol":"\"}","decimals":0}"#.to_vec()
+    );
+}
+
+#[test]
+fn wrap_works() {
+    new_test_ext().execute_with(|| {
+        let (tao_id, token_id) = setup_currency_token();
+
+        assert_ok!(CurrencyToken::wrap(Origin::signed(BOB), DOT, 300));
+
+        assert_eq!(Tokens::free_balance(DOT, &BOB), 700);
+        assert_eq!(Tokens::free_balance(DOT, &CurrencyToken::account_id()), 300);
+        assert_eq!(Token::balance_of(&BOB, tao_id, token_id), 300);
+        assert!(CurrencyToken::is_fully_backed(DOT));
+    });
+}
+
+#[test]
+fn unwrap_works() {
+    new_test_ext().execute_with(|| {
+        let (tao_id, token_id) = setup_currency_token();
+
+        assert_ok!(CurrencyToken::wrap(Origin::signed(BOB), DOT, 300));
+        assert_ok!(CurrencyToken::unwrap(Origin::signed(BOB), DOT, 100));
+
+        assert_eq!(Tokens::free_balance(DOT, &BOB), 800);
+        assert_eq!(Tokens::free_balance(DOT, &CurrencyToken::account_id()), 200);
+        assert_eq!(Token::balance_of(&BOB, tao_id, token_id), 200);
+        assert!(CurrencyToken::is_fully_backed(DOT));
+    });
+}
+
+#[test]
+fn wrap_without_balance_fails() {
+    new_test_ext().execute_with(|| {
+        let (tao_id, token_id) = setup_currency_token();
+
+        assert!(CurrencyToken::wrap(Origin::signed(BOB), DOT, 1001).is_err());
+
+        assert_eq!(Tokens::free_balance(DOT, &BOB), 1000);
+        assert_eq!(Token::balance_of(&BOB, tao_id, token_id), 0);
+        assert!(CurrencyToken::is_fully_backed(DOT));
+    });
+}
+
+#[test]
+fn unwrap_cannot_take_other_deposits() {
+    new_test_ext().execute_with(|| {
+        setup_currency_token();
+
+        assert_ok!(CurrencyToken::wrap(Origin::signed(ALICE), DOT, 300));
+        assert_ok!(CurrencyToken::wrap(Origin::signed(BOB), DOT, 100));
+
+        assert!(CurrencyToken::unwrap(Origin::signed(BOB), DOT, 200).is_err());
+
+        assert_eq!(Tokens::free_balance(DOT, &CurrencyToken::account_id()), 400);
+        assert!(CurrencyToken::is_fully_backed(DOT));
+    });
+}
+
+#[test]
+fn failed_unwrap_keeps_wrapped_tokens() {
+    new_test_ext().execute_with(|| {
+        let (tao_id, token_id) = setup_currency_token();
+        assert_ok!(CurrencyToken::wrap(Origin::signed(BOB), DOT, 300));
+        // the vault lost currency, so releasing all of it fails after the burn
+        assert_ok!(Tokens::withdraw(DOT, &CurrencyToken::account_id(), 100));
+
+        assert_noop!(
+            CurrencyToken::unwrap(Origin::signed(BOB), DOT, 300),
+            orml_tokens::Error::<Test>::BalanceTooLow,
+        );
+        assert_noop!(
+            CurrencyToken::do_unwrap(&BOB, DOT, 300),
+            orml_tokens::Error::<Test>::BalanceTooLow,
+        );
+
+        assert_eq!(Token::balance_of(&BOB, tao_id, token_id), 300);
+        assert_eq!(crate::CurrencyTokens::<Test>::get(DOT).unwrap().total_supply, 300);
+    });
+}
 
 #[test]
-fn it_works_for_default_value() {
+fn only_wrap_and_unwrap_mint_and_burn_currency_tokens() {
     new_test_ext().execute_with(|| {
-        // Dispatch a signed extrinsic.
-        assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
-        // Read pallet storage and assert an expected result.
-        assert_eq!(TemplateModule::something(), Some(42));
+        let (tao_id, token_id) = setup_currency_token();
+        assert_ok!(CurrencyToken::wrap(Origin::signed(BOB), DOT, 300));
+
+        assert_noop!(
+            Token::mint(Origin::signed(BOB), BOB, tao_id, token_id, 1_000),
+            token::Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            Token::burn(Origin::signed(BOB), BOB, tao_id, token_id, 100),
+            token::Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            Token::transfer_from(Origin::signed(ALICE), BOB, ALICE, tao_id, token_id, 100),
+            token::Error::<Test>::NoPermission
+        );
+
+        assert_eq!(Token::balance_of(&BOB, tao_id, token_id), 300);
+        assert!(CurrencyToken::is_fully_backed(DOT));
     });
 }
 
 #[test]
-fn correct_error_for_none_value() {
+fn unwrap_keeps_the_vault_fully_backed() {
     new_test_ext().execute_with(|| {
-        // Ensure the expected error is thrown when no value is present.
+        let (tao_id, token_id) = setup_currency_token();
+        assert_ok!(CurrencyToken::wrap(Origin::signed(BOB), DOT, 300));
+
+        // currency sent straight to the vault only adds to the backing
+        assert_ok!(Tokens::transfer(DOT, &ALICE, &CurrencyToken::account_id(), 10));
+        assert!(CurrencyToken::is_fully_backed(DOT));
+        assert_ok!(CurrencyToken::unwrap(Origin::signed(BOB), DOT, 100));
+
+        assert_ok!(Tokens::withdraw(DOT, &CurrencyToken::account_id(), 100));
+        assert!(!CurrencyToken::is_fully_backed(DOT));
+        assert_noop!(
+            CurrencyToken::unwrap(Origin::signed(BOB), DOT, 50),
+            Error::<Test>::NotFullyBacked,
+        );
+        assert_eq!(Token::balance_of(&BOB, tao_id, token_id), 200);
+    });
+}
+
+#[test]
+fn wrap_unknown_currency_fails() {
+    new_test_ext().execute_with(|| {
+        setup_currency_token();
+
         assert_noop!(
-            TemplateModule::cause_error(Origin::signed(1)),
-            Error::<Test>::NoneValue
+            CurrencyToken::wrap(Origin::signed(BOB), primitives::CurrencyId::Token(primitives::TokenSymbol::ACA), 1),
+            Error::<Test>::CurrencyTokenNotFound
         );
     });
 }
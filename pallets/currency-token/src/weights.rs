@@ -0,0 +1,101 @@
+//! Weights for pallet_currency_token
+//!
+//! Estimated from the storage accesses of each function, until the pallet is benchmarked.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_currency_token.
+pub trait WeightInfo {
+    fn create_tao(b: u32, ) -> Weight;
+    fn create_token() -> Weight;
+    fn set_asset_metadata(b: u32, ) -> Weight;
+    fn register_asset(b: u32, ) -> Weight;
+    fn wrap() -> Weight;
+    fn unwrap() -> Weight;
+    fn get_currency_token() -> Weight;
+}
+
+/// Weights for pallet_currency_token using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn create_tao(b: u32, ) -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn create_token() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn set_asset_metadata(b: u32, ) -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn register_asset(b: u32, ) -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn wrap() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn unwrap() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn get_currency_token() -> Weight {
+        (5_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn create_tao(b: u32, ) -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn create_token() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn set_asset_metadata(b: u32, ) -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn register_asset(b: u32, ) -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add((2_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn wrap() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn unwrap() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn get_currency_token() -> Weight {
+        (5_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+    }
+}
@@ -2,7 +2,7 @@
 
 use codec::{Encode, Decode};
 use sp_runtime::{
-	RuntimeDebug, ModuleId,
+	RuntimeDebug, ModuleId, Permill,
 	traits::{
 		AccountIdConversion, One, Zero,
 	},
@@ -10,12 +10,27 @@ use sp_runtime::{
 use frame_support::{
 	ensure,
 	dispatch::{DispatchResult, DispatchError},
+	storage::{with_transaction, TransactionOutcome},
 };
 use sp_core::U256;
 use sp_std::{fmt::Debug, convert::TryInto, prelude::*};
 use primitives::{CurrencyId, Balance};
+pub use primitives::ExchangeId;
 
 pub use pallet::*;
+pub use weights::WeightInfo;
+
+environmental::environmental!(slippage_index: Option<u32>);
+
+/// Run `f` and return, with its result, the index of the token id it failed `SlippageExceeded` at.
+pub fn with_slippage_index<R>(f: impl FnOnce() -> R) -> (R, Option<u32>) {
+	let mut index = None;
+	let result = slippage_index::using(&mut index, f);
+	(result, index)
+}
+
+pub mod migrations;
+pub mod weights;
 
 #[cfg(test)]
 mod mock;
@@ -23,8 +38,6 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-pub type ExchangeId = u32;
-
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
@@ -36,6 +49,30 @@ pub mod pallet {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
 		type ModuleId: Get<ModuleId>;
+
+		/// The origin which sets the fee range and protocol fee share, and claims protocol fees.
+		type GovernanceOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The highest fee an exchange may charge, below 100%.
+		type MaxFee: Get<Permill>;
+
+		/// The account claimed protocol fees are paid to.
+		type TreasuryAccount: Get<Self::AccountId>;
+
+		/// Weight information for the extrinsics and price queries of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::genesis_config]
+	#[derive(Default)]
+	pub struct GenesisConfig {}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			// A new chain starts at the latest layout, there is nothing to migrate
+			StorageVersion::<T>::put(Releases::V3_0_0);
+		}
 	}
 
 	#[pallet::pallet]
@@ -58,26 +95,50 @@ pub mod pallet {
 		ValueQuery
 	>;
 
+	/// The liquidity tokens of each token pool of an exchange.
 	#[pallet::storage]
 	#[pallet::getter(fn total_supplies)]
-	pub(super) type TotalSupplies<T: Config> = StorageMap<
+	pub(super) type TotalSupplies<T: Config> = StorageDoubleMap<
 		_,
 		Blake2_128Concat,
+		ExchangeId,
+		Blake2_128Concat,
 		T::TokenId,
 		Balance,
 		ValueQuery
 	>;
 
+	/// The currency of each token pool of an exchange.
 	#[pallet::storage]
 	#[pallet::getter(fn currency_reserves)]
-	pub(super) type CurrencyReserves<T: Config> = StorageMap<
+	pub(super) type CurrencyReserves<T: Config> = StorageDoubleMap<
 		_,
 		Blake2_128Concat,
+		ExchangeId,
+		Blake2_128Concat,
 		T::TokenId,
 		Balance,
 		ValueQuery
 	>;
 
+	/// The lowest and highest fee of new exchanges, `(0, MaxFee)` until set.
+	#[pallet::storage]
+	pub(super) type FeeRange<T: Config> = StorageValue<_, (Permill, Permill)>;
+
+	/// The part of each fee taken for the protocol rather than the liquidity providers.
+	#[pallet::storage]
+	#[pallet::getter(fn protocol_fee_share)]
+	pub(super) type ProtocolFeeShare<T: Config> = StorageValue<_, Permill, ValueQuery>;
+
+	/// The protocol fees of each exchange not claimed yet, held in its vault.
+	#[pallet::storage]
+	#[pallet::getter(fn protocol_fees)]
+	pub(super) type ProtocolFees<T: Config> = StorageMap<_, Blake2_128Concat, ExchangeId, Balance, ValueQuery>;
+
+	/// The layout of the storage, for migrations.
+	#[pallet::storage]
+	pub(super) type StorageVersion<T: Config> = StorageValue<_, Releases, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::metadata(T::AccountId = "AccountId")]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -87,6 +148,13 @@ pub mod pallet {
 		TokenToCurrency(ExchangeId, T::AccountId, T::AccountId, Vec<T::TokenId>, Vec<Balance>, Vec<Balance>),
 		LiquidityAdded(T::AccountId, T::AccountId, Vec<T::TokenId>, Vec<Balance>, Vec<Balance>),
 		LiquidityRemoved(T::AccountId, T::AccountId, Vec<T::TokenId>, Vec<Balance>, Vec<Balance>),
+		/// The fees of a trade in currency, per token id: the total fees and the protocol share of them.
+		FeesTaken(ExchangeId, Vec<T::TokenId>, Vec<Balance>, Vec<Balance>),
+		FeeRangeSet(Permill, Permill),
+		ProtocolFeeShareSet(Permill),
+		ProtocolFeesClaimed(ExchangeId, T::AccountId, Balance),
+		/// A trade or liquidity change of the account failed `SlippageExceeded` at the index of its token id.
+		SlippageExceeded(ExchangeId, T::AccountId, u32),
 	}
 
 	#[pallet::error]
@@ -95,27 +163,56 @@ pub mod pallet {
 		InvalidExchangeId,
 		NoAvailableExchangeId,
 		InvalidMaxCurrency,
-		InsufficientCurrencyAmount,
 		InsufficientTokenAmount,
 		SameCurrencyAndToken,
-		MaxCurrencyAmountExceeded,
 		InvalidCurrencyAmount,
 		InsufficientLiquidity,
 		NullTokensBought,
 		NullTokensSold,
 		EmptyReserve,
+		InvalidArrayLength,
+		FeeOutOfRange,
+		InvalidFeeRange,
+		NoProtocolFees,
+		/// The block of the deadline has passed.
+		DeadlineExpired,
+		/// The amount of a token id went over its max in or under its min out.
+		///
+		/// Errors carry no data, so the extrinsics report the index of the token id in
+		/// [`Event::SlippageExceeded`], and it is kept for other callers running the `do_*` function in
+		/// [`with_slippage_index`], such as the DEX precompile and the chain extension.
+		SlippageExceeded,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = 0;
+			if StorageVersion::<T>::get() == Releases::V1_0_0 {
+				weight += migrations::v2::migrate::<T>();
+			}
+			if StorageVersion::<T>::get() == Releases::V2_0_0 {
+				weight += migrations::v3::migrate::<T>();
+			}
+			weight
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 
-		#[pallet::weight(10_000)]
-		pub fn create_exchange(origin: OriginFor<T>, currency_id: CurrencyId, token_tao: T::TaoId) -> DispatchResultWithPostInfo {
+		#[pallet::weight(<T as Config>::WeightInfo::create_exchange())]
+		pub fn create_exchange(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			token_tao: T::TaoId,
+			fee: Permill,
+		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
+			let (min_fee, max_fee) = Self::fee_range();
+			ensure!(fee >= min_fee && fee <= max_fee, Error::<T>::FeeOutOfRange);
+
 			let exchange_id =
 				NextExchangeId::<T>::try_mutate(|id| -> Result<ExchangeId, DispatchError> {
 					let current_id = *id;
@@ -137,6 +234,7 @@ pub mod pallet {
 				currency_token,
 				liquidity_tao,
 				vault: fund_id,
+				fee,
 			};
 
 			Exchanges::<T>::insert(exchange_id, new_exchange);
@@ -146,39 +244,45 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		#[pallet::weight(<T as Config>::WeightInfo::buy_tokens(token_ids.len() as u32))]
 		pub fn buy_tokens(
 			origin: OriginFor<T>,
 			exchange_id: ExchangeId,
 			token_ids: Vec<T::TokenId>,
 			token_amounts_out: Vec<Balance>,
-			max_currency: Balance,
+			max_currencys: Vec<Balance>,
 			to: T::AccountId,
+			deadline: Option<T::BlockNumber>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
-			Self::do_buy_tokens(&who, exchange_id, token_ids, token_amounts_out, max_currency, &to)?;
+			Self::reporting_slippage(exchange_id, &who, || {
+				Self::do_buy_tokens(&who, exchange_id, token_ids, token_amounts_out, max_currencys, &to, deadline)
+			})?;
 
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		#[pallet::weight(<T as Config>::WeightInfo::sell_tokens(token_ids.len() as u32))]
 		pub fn sell_tokens(
 			origin: OriginFor<T>,
 			exchange_id: ExchangeId,
 			token_ids: Vec<T::TokenId>,
 			token_amounts_in: Vec<Balance>,
-			min_currency: Balance,
+			min_currencys: Vec<Balance>,
 			to: T::AccountId,
+			deadline: Option<T::BlockNumber>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
-			Self::do_sell_tokens(&who, exchange_id, token_ids, token_amounts_in, min_currency, &to)?;
+			Self::reporting_slippage(exchange_id, &who, || {
+				Self::do_sell_tokens(&who, exchange_id, token_ids, token_amounts_in, min_currencys, &to, deadline)
+			})?;
 
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		#[pallet::weight(<T as Config>::WeightInfo::add_liquidity(token_ids.len() as u32))]
 		pub fn add_liquidity(
 			origin: OriginFor<T>,
 			exchange_id: ExchangeId,
@@ -186,15 +290,19 @@ pub mod pallet {
 			token_ids: Vec<T::TokenId>,
 			token_amounts: Vec<Balance>,
 			max_currencys: Vec<Balance>,
+			deadline: Option<T::BlockNumber>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
-			Self::do_add_liquidity(&who, exchange_id, &to, token_ids, token_amounts, max_currencys)?;
+			Self::reporting_slippage(exchange_id, &who, || {
+				Self::do_add_liquidity(&who, exchange_id, &to, token_ids, token_amounts, max_currencys, deadline)
+			})?;
 
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		#[pallet::weight(<T as Config>::WeightInfo::remove_liquidity(token_ids.len() as u32))]
+		#[allow(clippy::too_many_arguments)]
 		pub fn remove_liquidity(
 			origin: OriginFor<T>,
 			exchange_id: ExchangeId,
@@ -203,10 +311,45 @@ pub mod pallet {
 			liquidities: Vec<Balance>,
 			min_currencys: Vec<Balance>,
 			min_tokens: Vec<Balance>,
+			deadline: Option<T::BlockNumber>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
-			Self::do_remove_liquidity(&who, exchange_id, &to, token_ids, liquidities, min_currencys, min_tokens)?;
+			Self::reporting_slippage(exchange_id, &who, || {
+				Self::do_remove_liquidity(&who, exchange_id, &to, token_ids, liquidities, min_currencys, min_tokens, deadline)
+			})?;
+
+			Ok(().into())
+		}
+
+		#[pallet::weight(<T as Config>::WeightInfo::set_fee_range())]
+		pub fn set_fee_range(origin: OriginFor<T>, min_fee: Permill, max_fee: Permill) -> DispatchResultWithPostInfo {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(min_fee <= max_fee && max_fee <= T::MaxFee::get(), Error::<T>::InvalidFeeRange);
+
+			FeeRange::<T>::put((min_fee, max_fee));
+
+			Self::deposit_event(Event::FeeRangeSet(min_fee, max_fee));
+
+			Ok(().into())
+		}
+
+		#[pallet::weight(<T as Config>::WeightInfo::set_protocol_fee_share())]
+		pub fn set_protocol_fee_share(origin: OriginFor<T>, share: Permill) -> DispatchResultWithPostInfo {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+
+			ProtocolFeeShare::<T>::put(share);
+
+			Self::deposit_event(Event::ProtocolFeeShareSet(share));
+
+			Ok(().into())
+		}
+
+		#[pallet::weight(<T as Config>::WeightInfo::claim_protocol_fees())]
+		pub fn claim_protocol_fees(origin: OriginFor<T>, exchange_id: ExchangeId) -> DispatchResultWithPostInfo {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+
+			Self::do_claim_protocol_fees(exchange_id)?;
 
 			Ok(().into())
 		}
@@ -215,6 +358,26 @@ pub mod pallet {
 
 }
 
+/// The storage layouts of the pallet.
+///
+/// Chains without a stored version predate versioning and are at `V1_0_0`, new chains are set to the
+/// latest at genesis.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum Releases {
+	/// Reserves and liquidity supplies keyed by token id alone.
+	V1_0_0,
+	/// Reserves and liquidity supplies keyed by exchange and token id.
+	V2_0_0,
+	/// Exchanges with a fee.
+	V3_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
 pub struct Exchange<
 	TaoId: Encode + Decode + Clone + Debug + Eq + PartialEq,
@@ -233,26 +396,35 @@ pub struct Exchange<
 	pub liquidity_tao: TaoId,
 	/// The fund account of exchange
 	pub vault: AccountId,
+	/// The fee taken from each trade, left in the reserves for the liquidity providers
+	/// but for the protocol share
+	pub fee: Permill,
 }
 
 impl<T: Config> Pallet<T> {
-	// currency to token
+	// currency to token, paying at most `max_currencys[i]` for `token_amounts_out[i]`
 	pub fn do_buy_tokens(
 		who: &T::AccountId,
 		exchange_id: ExchangeId,
 		token_ids: Vec<T::TokenId>,
 		token_amounts_out: Vec<Balance>,
-		max_currency: Balance,
+		max_currencys: Vec<Balance>,
 		to: &T::AccountId,
+		deadline: Option<T::BlockNumber>,
 	) -> DispatchResult {
+		Self::ensure_deadline(deadline)?;
 		let exchange = Exchanges::<T>::get(exchange_id).ok_or(Error::<T>::InvalidExchangeId)?;
-
-		// Transfer currency token to exchange vault
-		token::Module::<T>::do_transfer_from(who, &exchange.vault, exchange.currency_tao, exchange.currency_token, max_currency)?;
+		ensure!(
+			token_ids.len() == token_amounts_out.len() && token_ids.len() == max_currencys.len(),
+			Error::<T>::InvalidArrayLength
+		);
 
 		let n = token_ids.len();
-		let mut total_refund_currency: Balance = max_currency;
+		let mut total_currency = Balance::from(0u128);
 		let mut amounts_in = vec![Balance::from(0u128); n];
+		let mut fees = vec![Balance::from(0u128); n];
+		let mut protocol_fees = vec![Balance::from(0u128); n];
+		let protocol_fee_share = Self::protocol_fee_share();
 
 		let token_reserves = Self::get_token_reserves(&exchange.vault, exchange.token_tao, token_ids.clone());
 
@@ -263,44 +435,57 @@ impl<T: Config> Pallet<T> {
 
 			ensure!(amount_out > Zero::zero() , Error::<T>::NullTokensBought);
 
-			let currency_reserve = Self::currency_reserves(id);
-			let currency_amount = Self::get_amount_in(amount_out, currency_reserve, token_reserve)?;
+			let currency_reserve = Self::currency_reserves(exchange_id, id);
+			let currency_amount = Self::get_amount_in(amount_out, currency_reserve, token_reserve, exchange.fee)?;
+			let fee = currency_amount.saturating_sub(
+				Self::get_amount_in(amount_out, currency_reserve, token_reserve, Permill::zero())?);
+			let protocol_fee = protocol_fee_share * fee;
+			ensure!(currency_amount <= max_currencys[i], Self::slippage_exceeded(i));
 
-			total_refund_currency = total_refund_currency.saturating_sub(currency_amount);
+			total_currency = total_currency.saturating_add(currency_amount);
 
 			amounts_in[i] = currency_amount;
+			fees[i] = fee;
+			protocol_fees[i] = protocol_fee;
 
-			CurrencyReserves::<T>::try_mutate(id, |currency_reserve| -> DispatchResult {
+			CurrencyReserves::<T>::try_mutate(exchange_id, id, |currency_reserve| -> DispatchResult {
 				*currency_reserve = currency_reserve
-					.checked_add(currency_amount)
+					.checked_add(currency_amount.saturating_sub(protocol_fee))
 					.ok_or(Error::<T>::Overflow)?;
 				Ok(())
 			})?;
 		}
 
-		// Refund currency token if any
-		if total_refund_currency > Zero::zero()  {
-			token::Module::<T>::do_transfer_from(&exchange.vault, &to, exchange.currency_tao, exchange.currency_token, total_refund_currency)?;
-		}
+		Self::accrue_protocol_fees(exchange_id, &protocol_fees)?;
+
+		// Transfer currency token to exchange vault
+		token::Module::<T>::do_transfer_from(who, &exchange.vault, exchange.currency_tao, exchange.currency_token, total_currency)?;
 
 		// Send Tokens all tokens purchased
 		token::Module::<T>::do_batch_transfer_from(&exchange.vault, &to, exchange.token_tao, token_ids.clone(), token_amounts_out.clone())?;
 
-		Self::deposit_event(Event::CurrencyToToken(exchange_id, who.clone(), to.clone(), token_ids, token_amounts_out, amounts_in));
+		Self::deposit_event(Event::CurrencyToToken(exchange_id, who.clone(), to.clone(), token_ids.clone(), token_amounts_out, amounts_in));
+		Self::deposit_event(Event::FeesTaken(exchange_id, token_ids, fees, protocol_fees));
 
 		Ok(())
 	}
 
-	// token to currency
+	// token to currency, receiving at least `min_currencys[i]` for `token_amounts_in[i]`
 	pub fn do_sell_tokens(
 		who: &T::AccountId,
 		exchange_id: ExchangeId,
 		token_ids: Vec<T::TokenId>,
 		token_amounts_in: Vec<Balance>,
-		min_currency: Balance,
+		min_currencys: Vec<Balance>,
 		to: &T::AccountId,
+		deadline: Option<T::BlockNumber>,
 	) -> DispatchResult {
+		Self::ensure_deadline(deadline)?;
 		let exchange = Exchanges::<T>::get(exchange_id).ok_or(Error::<T>::InvalidExchangeId)?;
+		ensure!(
+			token_ids.len() == token_amounts_in.len() && token_ids.len() == min_currencys.len(),
+			Error::<T>::InvalidArrayLength
+		);
 
 		// Transfer the tokens to sell to exchange vault
 		token::Module::<T>::do_batch_transfer_from(who, &exchange.vault, exchange.token_tao, token_ids.clone(), token_amounts_in.clone())?;
@@ -308,6 +493,9 @@ impl<T: Config> Pallet<T> {
 		let n = token_ids.len();
 		let mut total_currency = Balance::from(0u128);
 		let mut amounts_out = vec![Balance::from(0u128); n];
+		let mut fees = vec![Balance::from(0u128); n];
+		let mut protocol_fees = vec![Balance::from(0u128); n];
+		let protocol_fee_share = Self::protocol_fee_share();
 
 		let token_reserves = Self::get_token_reserves(&exchange.vault, exchange.token_tao, token_ids.clone());
 
@@ -318,31 +506,40 @@ impl<T: Config> Pallet<T> {
 
 			ensure!(amount_in > Zero::zero() , Error::<T>::NullTokensSold);
 
-			let currency_reserve = Self::currency_reserves(id);
-			let currency_amount = Self::get_amount_out(amount_in, token_reserve.saturating_sub(amount_in), currency_reserve)?;
+			let currency_reserve = Self::currency_reserves(exchange_id, id);
+			let token_reserve_in = token_reserve.saturating_sub(amount_in);
+			let currency_amount = Self::get_amount_out(amount_in, token_reserve_in, currency_reserve, exchange.fee)?;
+			let fee = Self::get_amount_out(amount_in, token_reserve_in, currency_reserve, Permill::zero())?
+				.saturating_sub(currency_amount);
+			let protocol_fee = protocol_fee_share * fee;
+			ensure!(currency_amount >= min_currencys[i], Self::slippage_exceeded(i));
 
 			total_currency = total_currency.saturating_add(currency_amount);
 			amounts_out[i] = currency_amount;
+			fees[i] = fee;
+			protocol_fees[i] = protocol_fee;
 
-			CurrencyReserves::<T>::try_mutate(id, |currency_reserve| -> DispatchResult {
+			CurrencyReserves::<T>::try_mutate(exchange_id, id, |currency_reserve| -> DispatchResult {
 				*currency_reserve = currency_reserve
 					.checked_sub(currency_amount)
+					.and_then(|reserve| reserve.checked_sub(protocol_fee))
 					.ok_or(Error::<T>::Overflow)?;
 				Ok(())
 			})?;
 		}
 
-		ensure!(total_currency >= min_currency, Error::<T>::InsufficientCurrencyAmount);
+		Self::accrue_protocol_fees(exchange_id, &protocol_fees)?;
 
 		// Transfer currency here
 		token::Module::<T>::do_transfer_from(&exchange.vault, &to, exchange.currency_tao, exchange.currency_token, total_currency)?;
 
-		Self::deposit_event(Event::TokenToCurrency(exchange_id, who.clone(), to.clone(), token_ids, token_amounts_in, amounts_out));
+		Self::deposit_event(Event::TokenToCurrency(exchange_id, who.clone(), to.clone(), token_ids.clone(), token_amounts_in, amounts_out));
+		Self::deposit_event(Event::FeesTaken(exchange_id, token_ids, fees, protocol_fees));
 
 		Ok(())
 	}
 
-	// add liquidity
+	// add liquidity, paying at most `max_currencys[i]` along with `token_amounts[i]`
 	pub fn do_add_liquidity(
 		who: &T::AccountId,
 		exchange_id: ExchangeId,
@@ -350,8 +547,14 @@ impl<T: Config> Pallet<T> {
 		token_ids: Vec<T::TokenId>,
 		token_amounts: Vec<Balance>,
 		max_currencys: Vec<Balance>,
+		deadline: Option<T::BlockNumber>,
 	) -> DispatchResult {
+		Self::ensure_deadline(deadline)?;
 		let exchange = Exchanges::<T>::get(exchange_id).ok_or(Error::<T>::InvalidExchangeId)?;
+		ensure!(
+			token_ids.len() == token_amounts.len() && token_ids.len() == max_currencys.len(),
+			Error::<T>::InvalidArrayLength
+		);
 
 		// Transfer the tokens to add to the exchange liquidity pools
 		token::Module::<T>::do_batch_transfer_from(who, &exchange.vault, exchange.token_tao, token_ids.clone(), token_amounts.clone())?;
@@ -374,16 +577,16 @@ impl<T: Config> Pallet<T> {
 				ensure!(exchange.currency_token != id, Error::<T>::SameCurrencyAndToken);
 			}
 
-			let total_liquidity = Self::total_supplies(id);
+			let total_liquidity = Self::total_supplies(exchange_id, id);
 
 			if total_liquidity > Zero::zero()  {
-				let currency_reserve = Self::currency_reserves(id);
+				let currency_reserve = Self::currency_reserves(exchange_id, id);
 				let token_reserve = token_reserves[i];
 
 				let (currency_amount, rounded) = Self::div_round(
 					U256::from(amount).saturating_mul(U256::from(currency_reserve)),
-					U256::from(token_reserve).saturating_sub(U256::from(amount)));
-				ensure!(max_currencys[i] >= currency_amount, Error::<T>::MaxCurrencyAmountExceeded);
+					U256::from(token_reserve).saturating_sub(U256::from(amount)))?;
+				ensure!(max_currencys[i] >= currency_amount, Self::slippage_exceeded(i));
 
 				total_currency = total_currency.saturating_add(currency_amount);
 
@@ -391,14 +594,14 @@ impl<T: Config> Pallet<T> {
 				liquidities_to_mint[i] = (fixed_currency_amount.saturating_mul(total_liquidity)) / currency_reserve;
 				currency_amounts[i] = currency_amount;
 
-				CurrencyReserves::<T>::try_mutate(id, |currency_reserve| -> DispatchResult {
+				CurrencyReserves::<T>::try_mutate(exchange_id, id, |currency_reserve| -> DispatchResult {
 					*currency_reserve = currency_reserve
 						.checked_add(currency_amount)
 						.ok_or(Error::<T>::Overflow)?;
 					Ok(())
 				})?;
 
-				TotalSupplies::<T>::try_mutate(id, |total_supply| -> DispatchResult {
+				TotalSupplies::<T>::try_mutate(exchange_id, id, |total_supply| -> DispatchResult {
 					*total_supply = total_liquidity
 						.checked_add(liquidities_to_mint[i])
 						.ok_or(Error::<T>::Overflow)?;
@@ -414,8 +617,8 @@ impl<T: Config> Pallet<T> {
 				liquidities_to_mint[i] = max_currency;
 				currency_amounts[i] = max_currency;
 
-				CurrencyReserves::<T>::mutate(id, |currency_reserve| *currency_reserve = max_currency);
-				TotalSupplies::<T>::mutate(id, |total_supply| *total_supply = max_currency);
+				CurrencyReserves::<T>::mutate(exchange_id, id, |currency_reserve| *currency_reserve = max_currency);
+				TotalSupplies::<T>::mutate(exchange_id, id, |total_supply| *total_supply = max_currency);
 			}
 		}
 
@@ -430,7 +633,8 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
-	// remove liquidity
+	// remove liquidity, receiving at least `min_currencys[i]` and `min_tokens[i]` for `liquidities[i]`
+	#[allow(clippy::too_many_arguments)]
 	pub fn do_remove_liquidity(
 		who: &T::AccountId,
 		exchange_id: ExchangeId,
@@ -439,8 +643,16 @@ impl<T: Config> Pallet<T> {
 		liquidities: Vec<Balance>,
 		min_currencys: Vec<Balance>,
 		min_tokens: Vec<Balance>,
+		deadline: Option<T::BlockNumber>,
 	) -> DispatchResult {
+		Self::ensure_deadline(deadline)?;
 		let exchange = Exchanges::<T>::get(exchange_id).ok_or(Error::<T>::InvalidExchangeId)?;
+		ensure!(
+			token_ids.len() == liquidities.len()
+				&& token_ids.len() == min_currencys.len()
+				&& token_ids.len() == min_tokens.len(),
+			Error::<T>::InvalidArrayLength
+		);
 
 		// Transfer the liquidity pool tokens to burn to exchange vault
 		token::Module::<T>::do_batch_transfer_from(who, &exchange.vault, exchange.liquidity_tao, token_ids.clone(), liquidities.clone())?;
@@ -457,29 +669,31 @@ impl<T: Config> Pallet<T> {
 			let liquidity = liquidities[i];
 			let token_reserve = token_reserves[i];
 
-			let total_liquidity = Self::total_supplies(id);
+			let total_liquidity = Self::total_supplies(exchange_id, id);
 			ensure!(total_liquidity > Zero::zero() , Error::<T>::InsufficientLiquidity);
 
-			let currency_reserve = Self::currency_reserves(id);
+			let currency_reserve = Self::currency_reserves(exchange_id, id);
 
 			let currency_amount = liquidity.saturating_mul(currency_reserve) / total_liquidity;
 			let token_amount = liquidity.saturating_mul(token_reserve) / total_liquidity;
 
-			ensure!(currency_amount >= min_currencys[i], Error::<T>::InsufficientCurrencyAmount);
-			ensure!(token_amount >= min_tokens[i], Error::<T>::InsufficientTokenAmount);
+			ensure!(
+				currency_amount >= min_currencys[i] && token_amount >= min_tokens[i],
+				Self::slippage_exceeded(i)
+			);
 
 			total_currency = total_currency.saturating_add(currency_amount);
 			token_amounts[i] = token_amount;
 			currency_amounts[i] = currency_amount;
 
-			CurrencyReserves::<T>::try_mutate(id, |currency_reserve| -> DispatchResult {
+			CurrencyReserves::<T>::try_mutate(exchange_id, id, |currency_reserve| -> DispatchResult {
 				*currency_reserve = currency_reserve
 					.checked_sub(currency_amount)
 					.ok_or(Error::<T>::Overflow)?;
 				Ok(())
 			})?;
 
-			TotalSupplies::<T>::try_mutate(id, |total_supply| -> DispatchResult {
+			TotalSupplies::<T>::try_mutate(exchange_id, id, |total_supply| -> DispatchResult {
 				*total_supply = total_liquidity
 					.checked_sub(liquidity)
 					.ok_or(Error::<T>::Overflow)?;
@@ -499,16 +713,123 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Pay the protocol fees accrued by an exchange to the treasury account.
+	pub fn do_claim_protocol_fees(exchange_id: ExchangeId) -> DispatchResult {
+		let exchange = Exchanges::<T>::get(exchange_id).ok_or(Error::<T>::InvalidExchangeId)?;
+
+		let amount = ProtocolFees::<T>::take(exchange_id);
+		ensure!(amount > Zero::zero(), Error::<T>::NoProtocolFees);
+
+		let treasury = T::TreasuryAccount::get();
+		token::Module::<T>::do_transfer_from(&exchange.vault, &treasury, exchange.currency_tao, exchange.currency_token, amount)?;
+
+		Self::deposit_event(Event::ProtocolFeesClaimed(exchange_id, treasury, amount));
+
+		Ok(())
+	}
+
+	/// Run `f` in a transaction, as the extrinsic of `who`. A `SlippageExceeded` failure is rolled back
+	/// but deposits [`Event::SlippageExceeded`], which stays with the failed extrinsic.
+	fn reporting_slippage(exchange_id: ExchangeId, who: &T::AccountId, f: impl FnOnce() -> DispatchResult) -> DispatchResult {
+		let (result, index) = with_slippage_index(|| with_transaction(|| match f() {
+			Ok(()) => TransactionOutcome::Commit(Ok(())),
+			Err(e) => TransactionOutcome::Rollback(Err(e)),
+		}));
+		if let (Err(e), Some(index)) = (result, index) {
+			if e == Error::<T>::SlippageExceeded.into() {
+				Self::deposit_event(Event::SlippageExceeded(exchange_id, who.clone(), index));
+			}
+		}
+		result
+	}
+
+	/// `SlippageExceeded` at the `index`th token id, noted for [`with_slippage_index`].
+	fn slippage_exceeded(index: usize) -> DispatchError {
+		slippage_index::with(|slippage_index| *slippage_index = Some(index as u32));
+		Error::<T>::SlippageExceeded.into()
+	}
+
+	/// Fail once the block of `deadline` has passed.
+	fn ensure_deadline(deadline: Option<T::BlockNumber>) -> DispatchResult {
+		if let Some(deadline) = deadline {
+			ensure!(frame_system::Module::<T>::block_number() <= deadline, Error::<T>::DeadlineExpired);
+		}
+		Ok(())
+	}
+
+	/// The lowest and highest fee of new exchanges.
+	pub fn fee_range() -> (Permill, Permill) {
+		FeeRange::<T>::get().unwrap_or_else(|| (Permill::zero(), T::MaxFee::get()))
+	}
+
+	fn accrue_protocol_fees(exchange_id: ExchangeId, protocol_fees: &[Balance]) -> DispatchResult {
+		let total = protocol_fees.iter().fold(0, |total: Balance, fee| total.saturating_add(*fee));
+		if total.is_zero() {
+			return Ok(());
+		}
+
+		ProtocolFees::<T>::try_mutate(exchange_id, |fees| -> DispatchResult {
+			*fees = fees.checked_add(total).ok_or(Error::<T>::Overflow)?;
+			Ok(())
+		})
+	}
+
+	/// The currency needed to buy `token_amounts_out` of `token_ids`.
+	pub fn get_buy_prices(
+		exchange_id: ExchangeId,
+		token_ids: Vec<T::TokenId>,
+		token_amounts_out: Vec<Balance>,
+	) -> Result<Vec<Balance>, DispatchError> {
+		let exchange = Exchanges::<T>::get(exchange_id).ok_or(Error::<T>::InvalidExchangeId)?;
+		ensure!(token_ids.len() == token_amounts_out.len(), Error::<T>::InvalidArrayLength);
+
+		let token_reserves = Self::get_token_reserves(&exchange.vault, exchange.token_tao, token_ids.clone());
+
+		token_ids
+			.iter()
+			.zip(token_amounts_out.iter())
+			.zip(token_reserves.iter())
+			.map(|((id, amount_out), token_reserve)| {
+				Self::get_amount_in(*amount_out, Self::currency_reserves(exchange_id, id), *token_reserve, exchange.fee)
+			})
+			.collect()
+	}
+
+	/// The currency received for selling `token_amounts_in` of `token_ids`.
+	pub fn get_sell_prices(
+		exchange_id: ExchangeId,
+		token_ids: Vec<T::TokenId>,
+		token_amounts_in: Vec<Balance>,
+	) -> Result<Vec<Balance>, DispatchError> {
+		let exchange = Exchanges::<T>::get(exchange_id).ok_or(Error::<T>::InvalidExchangeId)?;
+		ensure!(token_ids.len() == token_amounts_in.len(), Error::<T>::InvalidArrayLength);
+
+		let token_reserves = Self::get_token_reserves(&exchange.vault, exchange.token_tao, token_ids.clone());
+
+		token_ids
+			.iter()
+			.zip(token_amounts_in.iter())
+			.zip(token_reserves.iter())
+			.map(|((id, amount_in), token_reserve)| {
+				Self::get_amount_out(*amount_in, *token_reserve, Self::currency_reserves(exchange_id, id), exchange.fee)
+			})
+			.collect()
+	}
+
 	fn get_amount_in(
 		amount_out: Balance,
 		reserve_in: Balance,
 		reserve_out: Balance,
+		fee: Permill,
 	) -> Result<Balance, DispatchError> {
 		ensure!(reserve_in > Zero::zero()  && reserve_out > Zero::zero() , Error::<T>::EmptyReserve);
+		// No amount in buys the whole reserve out
+		ensure!(amount_out < reserve_out, Error::<T>::InsufficientLiquidity);
 
-		let numerator: U256 = U256::from(reserve_in).saturating_mul(U256::from(amount_out)).saturating_mul(U256::from(1000u128));
-		let denominator: U256 = (U256::from(reserve_out).saturating_sub(U256::from(amount_out))).saturating_mul(U256::from(995u128));
-		let (amount_in, _) = Self::div_round(numerator, denominator);
+		let (fee_numerator, fee_denominator) = Self::fee_fraction(fee);
+		let numerator: U256 = U256::from(reserve_in).saturating_mul(U256::from(amount_out)).saturating_mul(fee_denominator);
+		let denominator: U256 = (U256::from(reserve_out).saturating_sub(U256::from(amount_out))).saturating_mul(fee_numerator);
+		let (amount_in, _) = Self::div_round(numerator, denominator)?;
 
 		Ok(amount_in)
 	}
@@ -517,12 +838,14 @@ impl<T: Config> Pallet<T> {
 		amount_in: Balance,
 		reserve_in: Balance,
 		reserve_out: Balance,
+		fee: Permill,
 	) -> Result<Balance, DispatchError> {
 		ensure!(reserve_in > Zero::zero()  && reserve_out > Zero::zero() , Error::<T>::EmptyReserve);
 
-		let amount_in_with_fee: U256 = U256::from(amount_in).saturating_mul(U256::from(995u128));
+		let (fee_numerator, fee_denominator) = Self::fee_fraction(fee);
+		let amount_in_with_fee: U256 = U256::from(amount_in).saturating_mul(fee_numerator);
 		let numerator: U256 = U256::from(amount_in_with_fee).saturating_mul(U256::from(reserve_out));
-		let denominator: U256 = (U256::from(reserve_in).saturating_mul(U256::from(1000u128))).saturating_add(amount_in_with_fee);
+		let denominator: U256 = (U256::from(reserve_in).saturating_mul(fee_denominator)).saturating_add(amount_in_with_fee);
 
 		let amount_out = numerator
 			.checked_div(denominator)
@@ -532,6 +855,12 @@ impl<T: Config> Pallet<T> {
 		Ok(amount_out)
 	}
 
+	/// The part of an amount left after `fee`, as `(numerator, denominator)`.
+	fn fee_fraction(fee: Permill) -> (U256, U256) {
+		let one = Permill::one().deconstruct();
+		(U256::from(one.saturating_sub(fee.deconstruct())), U256::from(one))
+	}
+
 	fn get_token_reserves(vault: &T::AccountId, tao_id: T::TaoId, token_ids: Vec<T::TokenId>) -> Vec<Balance> {
 		let n = token_ids.len();
 
@@ -547,21 +876,18 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Divides two numbers and add 1 if there is a rounding error
-	fn div_round(numerator: U256, denominator: U256) -> (Balance, bool) {
-		let remainder = numerator.checked_rem(denominator).unwrap();
-		if remainder.is_zero() {
-			(numerator
-				.checked_div(denominator)
-				.and_then(|n| TryInto::<Balance>::try_into(n).ok())
-				.unwrap_or_else(Zero::zero)
-			, false)
-		} else {
-			(numerator
-				.checked_div(denominator)
-				.and_then(|r| r.checked_add(U256::one()))
-				.and_then(|n| TryInto::<Balance>::try_into(n).ok())
-				.unwrap_or_else(Zero::zero)
-			, true)
-		}
+	///
+	/// A zero denominator is a pool with no tokens left to pay out
+	fn div_round(numerator: U256, denominator: U256) -> Result<(Balance, bool), DispatchError> {
+		let remainder = numerator.checked_rem(denominator).ok_or(Error::<T>::InsufficientLiquidity)?;
+		let rounded = !remainder.is_zero();
+
+		let quotient = numerator
+			.checked_div(denominator)
+			.and_then(|r| if rounded { r.checked_add(U256::one()) } else { Some(r) })
+			.and_then(|n| TryInto::<Balance>::try_into(n).ok())
+			.ok_or(Error::<T>::Overflow)?;
+
+		Ok((quotient, rounded))
 	}
 }
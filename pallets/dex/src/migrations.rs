@@ -0,0 +1,176 @@
+//! Storage migrations of `pallet_dex`.
+
+use codec::{Encode, Decode};
+use sp_runtime::RuntimeDebug;
+
+/// The layout of an exchange before `v3`, without a fee.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct ExchangeV1<TaoId, TokenId, AccountId> {
+	pub creator: AccountId,
+	pub token_tao: TaoId,
+	pub currency_tao: TaoId,
+	pub currency_token: TokenId,
+	pub liquidity_tao: TaoId,
+	pub vault: AccountId,
+}
+
+/// Key `CurrencyReserves` and `TotalSupplies` by exchange and token id.
+pub mod v2 {
+	use frame_support::{
+		Blake2_128Concat,
+		storage::{migration::storage_key_iter, unhashed},
+		traits::{Get, PalletInfo},
+		weights::Weight,
+	};
+	use sp_core::U256;
+	use sp_std::{convert::TryInto, prelude::*};
+	use primitives::{Balance, ExchangeId};
+	use crate::*;
+	use super::ExchangeV1;
+
+	/// Move the reserve and liquidity supply of each token id to the exchanges holding the token.
+	///
+	/// Before this migration every exchange trading a token id shared one reserve and one supply,
+	/// so there is no record of which exchange owns what. The supply of an exchange is what is
+	/// outstanding of its liquidity tokens. The shared reserve is split between the exchanges whose
+	/// vault holds the token in proportion to the tokens each holds, then the reserves of each
+	/// exchange are scaled to the currency its vault actually holds, less protocol fees. Entries of a token no vault
+	/// holds and no liquidity token backs are dropped.
+	pub fn migrate<T: Config>() -> Weight {
+		let pallet = <T as frame_system::Config>::PalletInfo::name::<Pallet<T>>()
+			.unwrap_or("Dex")
+			.as_bytes();
+
+		// Drain the old entries before writing any new one, they share the storage prefix
+		let reserves: Vec<(T::TokenId, Balance)> =
+			storage_key_iter::<T::TokenId, Balance, Blake2_128Concat>(pallet, b"CurrencyReserves").drain().collect();
+		let supplies: Vec<(T::TokenId, Balance)> =
+			storage_key_iter::<T::TokenId, Balance, Blake2_128Concat>(pallet, b"TotalSupplies").drain().collect();
+
+		// Read in the layout of the time, exchanges gain a fee in `v3`
+		let exchanges: Vec<(ExchangeId, ExchangeV1<T::TaoId, T::TokenId, T::AccountId>)> = (0..NextExchangeId::<T>::get())
+			.filter_map(|exchange_id| {
+				unhashed::get(&Exchanges::<T>::hashed_key_for(exchange_id)).map(|exchange| (exchange_id, exchange))
+			})
+			.collect();
+
+		let mut reads = 1 + exchanges.len() + reserves.len() + supplies.len();
+		let mut writes = reserves.len() + supplies.len() + 1;
+
+		// The outstanding liquidity tokens of every exchange, by liquidity tao and token id
+		let mut liquidity: Vec<((T::TaoId, T::TokenId), Balance)> = Vec::new();
+		for (_, (tao_id, token_id), balance) in token::Module::<T>::all_balances() {
+			reads += 1;
+			if !exchanges.iter().any(|(_, exchange)| exchange.liquidity_tao == tao_id) {
+				continue;
+			}
+			match liquidity.iter_mut().find(|(key, _)| *key == (tao_id, token_id)) {
+				Some((_, total)) => *total = total.saturating_add(balance),
+				None => liquidity.push(((tao_id, token_id), balance)),
+			}
+		}
+
+		let mut token_ids: Vec<T::TokenId> = Vec::new();
+		let ids = reserves.iter().chain(supplies.iter()).map(|(id, _)| *id)
+			.chain(liquidity.iter().map(|((_, id), _)| *id));
+		for id in ids {
+			if !token_ids.contains(&id) {
+				token_ids.push(id);
+			}
+		}
+
+		// The tokens each exchange holds of each token id
+		let holdings: Vec<Vec<Balance>> = exchanges
+			.iter()
+			.map(|(_, exchange)| {
+				token_ids.iter().map(|id| token::Module::<T>::balance_of(&exchange.vault, exchange.token_tao, *id)).collect()
+			})
+			.collect();
+		reads += exchanges.len() * token_ids.len();
+
+		for (e, (exchange_id, exchange)) in exchanges.iter().enumerate() {
+			// The shared reserve of each token id, split by the tokens each vault holds
+			let split: Vec<Balance> = token_ids
+				.iter()
+				.enumerate()
+				.map(|(i, id)| {
+					let total_held = holdings.iter().fold(0, |total: Balance, held| total.saturating_add(held[i]));
+					share(value_of(&reserves, *id), holdings[e][i], total_held)
+				})
+				.collect();
+
+			// Scaled to the currency the vault holds for its pools, the last token id with a share gets
+			// what rounding left over
+			let vault_currency = token::Module::<T>::balance_of(&exchange.vault, exchange.currency_tao, exchange.currency_token)
+				.saturating_sub(ProtocolFees::<T>::get(exchange_id));
+			reads += 2;
+			let split_total = split.iter().fold(0, |total: Balance, reserve| total.saturating_add(*reserve));
+			let pools: Vec<usize> = (0..token_ids.len())
+				.filter(|i| holdings[e][*i] > 0 || value_of(&liquidity, (exchange.liquidity_tao, token_ids[*i])) > 0)
+				.collect();
+			let last = pools.iter().rev().find(|i| split[**i] > 0).copied();
+			let mut reserve_left = vault_currency;
+
+			for i in pools.iter() {
+				let id = token_ids[*i];
+				let reserve = if Some(*i) == last {
+					reserve_left
+				} else {
+					share(vault_currency, split[*i], split_total)
+				};
+				reserve_left = reserve_left.saturating_sub(reserve);
+
+				CurrencyReserves::<T>::insert(exchange_id, id, reserve);
+				TotalSupplies::<T>::insert(exchange_id, id, value_of(&liquidity, (exchange.liquidity_tao, id)));
+				writes += 2;
+			}
+		}
+
+		StorageVersion::<T>::put(Releases::V2_0_0);
+
+		T::DbWeight::get().reads_writes(reads as Weight, writes as Weight)
+	}
+
+	fn value_of<Key: PartialEq>(entries: &[(Key, Balance)], id: Key) -> Balance {
+		entries.iter().find(|(key, _)| *key == id).map(|(_, value)| *value).unwrap_or(0)
+	}
+
+	/// `value * part / total`, rounded down.
+	fn share(value: Balance, part: Balance, total: Balance) -> Balance {
+		U256::from(value)
+			.saturating_mul(U256::from(part))
+			.checked_div(U256::from(total))
+			.and_then(|n| TryInto::<Balance>::try_into(n).ok())
+			.unwrap_or(0)
+	}
+}
+
+/// Give every exchange a fee.
+pub mod v3 {
+	use frame_support::{traits::Get, weights::Weight};
+	use sp_runtime::Permill;
+	use crate::*;
+	use super::ExchangeV1;
+
+	/// Set the fee of each exchange to 0.5%, the fee every exchange took before fees were configurable.
+	pub fn migrate<T: Config>() -> Weight {
+		let mut translated: Weight = 0;
+
+		Exchanges::<T>::translate_values::<ExchangeV1<T::TaoId, T::TokenId, T::AccountId>, _>(|old| {
+			translated += 1;
+			Some(Exchange {
+				creator: old.creator,
+				token_tao: old.token_tao,
+				currency_tao: old.currency_tao,
+				currency_token: old.currency_token,
+				liquidity_tao: old.liquidity_tao,
+				vault: old.vault,
+				fee: Permill::from_parts(5_000),
+			})
+		});
+
+		StorageVersion::<T>::put(Releases::V3_0_0);
+
+		T::DbWeight::get().reads_writes(translated, translated + 1)
+	}
+}
@@ -1,143 +1,164 @@
-use crate::{Module, Trait};
-
+use crate as pallet_dex;
 use sp_core::H256;
-use frame_support::{impl_outer_origin, impl_outer_event ,parameter_types, weights::Weight, traits::OnFinalize, traits::OnInitialize};
+use frame_support::{parameter_types, traits::GenesisBuild};
 use sp_runtime::{
-    traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill, ModuleId,
+	traits::{BlakeTwo256, IdentityLookup, Zero}, testing::Header, ModuleId, Permill,
 };
-use frame_system as system;
-
-pub const MILLISECS_PER_BLOCK: u64 = 4000;
+use frame_system::{self as system, EnsureRoot};
+use orml_traits::parameter_type_with_key;
+use primitives::{Amount, Balance, CurrencyId, TokenSymbol};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Tokens: orml_tokens::{Module, Storage, Event<T>, Config<T>},
+		Token: token::{Module, Call, Storage, Event<T>},
+		CurrencyToken: currency_token::{Module, Call, Storage, Event<T>, Config},
+		Dex: pallet_dex::{Module, Call, Storage, Event<T>},
+	}
+);
 
-pub const SLOT_DURATION: u64 = MILLISECS_PER_BLOCK;
-
-impl_outer_origin! {
-    pub enum Origin for Test where system = frame_system {}
-}
-
-mod dex {
-    // pub use crate::Event;
-    pub use super::super::*;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
 }
 
-impl_outer_event! {
-    pub enum TestEvent for Test {
-        system<T>,
-        pallet_balances<T>,
-        dex<T>,
-        token<T>,
-        currency<T>,
-        tao<T>,
-    }
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
 }
 
-#[derive(Clone, Eq, PartialEq, Debug)]
-pub struct Test;
 parameter_types! {
-    pub const BlockHashCount: u64 = 250;
-    pub const MaximumBlockWeight: Weight = 1024;
-    pub const MaximumBlockLength: u32 = 2 * 1024;
-    pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
-
-    pub const ExistentialDeposit: u64 = 1;
+	pub const ExistentialDeposit: Balance = 1;
 }
-impl system::Trait for Test {
-    type Origin = Origin;
-    type Call = ();
-    type Index = u64;
-    type BlockNumber = u64;
-    type Hash = H256;
-    type Hashing = BlakeTwo256;
-    type AccountId = u64;
-    type Lookup = IdentityLookup<Self::AccountId>;
-    type Header = Header;
-    type Event = TestEvent;
-    type BlockHashCount = BlockHashCount;
-    type MaximumBlockWeight = MaximumBlockWeight;
-    type DbWeight = ();
-    type BlockExecutionWeight = ();
-    type ExtrinsicBaseWeight = ();
-    type MaximumExtrinsicWeight = MaximumBlockWeight;
-    type MaximumBlockLength = MaximumBlockLength;
-    type AvailableBlockRatio = AvailableBlockRatio;
-    type Version = ();
-    type SystemWeightInfo = ();
-    type PalletInfo = ();
-    type AccountData = pallet_balances::AccountData<u64>;
-    type OnNewAccount = ();
-    type OnKilledAccount = ();
-    type BaseCallFilter = ();
+
+impl pallet_balances::Config for Test {
+	type Balance = Balance;
+	type MaxLocks = ();
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
 }
-impl pallet_balances::Trait for Test {
-    type Balance = u64;
-    type MaxLocks = ();
-    type Event = TestEvent;
-    type DustRemoval = ();
-    type ExistentialDeposit = ExistentialDeposit;
-    type AccountStore = system::Module<Test>;
-    type WeightInfo = ();
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+		Zero::zero()
+	};
 }
 
-parameter_types! {
-	pub const MinimumPeriod: u64 = SLOT_DURATION / 2;
+impl orml_tokens::Config for Test {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
 }
 
-impl pallet_timestamp::Trait for Test {
-    type Moment = u64;
-    type OnTimestampSet = ();
-    type MinimumPeriod = MinimumPeriod;
-    type WeightInfo = ();
+parameter_types! {
+	pub const AttributeDepositBase: Balance = 10;
+	pub const DepositPerByte: Balance = 1;
+	pub const KeyLimit: u32 = 32;
+	pub const ValueLimit: u32 = 256;
 }
 
-impl token::Trait for Test {
-    type Event = TestEvent;
-    type TokenBalance = u128;
-    type TokenId = u64;
+impl token::Config for Test {
+	type Event = Event;
+	type TokenId = u64;
+	type TaoId = u64;
+	type Currency = Balances;
+	type AttributeDepositBase = AttributeDepositBase;
+	type DepositPerByte = DepositPerByte;
+	type KeyLimit = KeyLimit;
+	type ValueLimit = ValueLimit;
+	type WeightInfo = ();
 }
 
-impl currency::Trait for Test {
-    type Event = TestEvent;
+parameter_types! {
+	pub const CurrencyTokenModuleId: ModuleId = ModuleId(*b"sgc/curr");
+	pub const StringLimit: u32 = 16;
 }
 
-impl tao::Trait for Test {
-    type Event = TestEvent;
-    type TaoId = u64;
+impl currency_token::Config for Test {
+	type Event = Event;
+	type ModuleId = CurrencyTokenModuleId;
+	type Currency = Tokens;
+	type RegistryOrigin = EnsureRoot<u64>;
+	type StringLimit = StringLimit;
+	type WeightInfo = ();
 }
 
 parameter_types! {
-    pub const DexModuleId: ModuleId = ModuleId(*b"spr/dexm");
+	pub const DexModuleId: ModuleId = ModuleId(*b"sgc/dexm");
+	pub const MaxFee: Permill = Permill::from_percent(3);
+	pub const TreasuryAccount: u64 = TREASURY;
 }
-impl Trait for Test {
-    type Event = TestEvent;
-    type ModuleId = DexModuleId;
+
+impl pallet_dex::Config for Test {
+	type Event = Event;
+	type ModuleId = DexModuleId;
+	type GovernanceOrigin = EnsureRoot<u64>;
+	type MaxFee = MaxFee;
+	type TreasuryAccount = TreasuryAccount;
+	type WeightInfo = ();
 }
 
-pub type Dex = Module<Test>;
-pub type Currency = currency::Module<Test>;
-pub type Token = token::Module<Test>;
-pub type Tao = tao::Module<Test>;
-pub type System = frame_system::Module<Test>;
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const TREASURY: u64 = 100;
+pub const DOT: CurrencyId = CurrencyId::Token(TokenSymbol::DOT);
 
+// Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    let mut t = system::GenesisConfig::default()
-        .build_storage::<Test>()
-        .unwrap();
-    pallet_balances::GenesisConfig::<Test> {
-        balances: vec![(1, 10000), (2, 11000), (3, 12000), (4, 13000), (5, 14000)],
-    }
-        .assimilate_storage(&mut t)
-        .unwrap();
-    let mut ext: sp_io::TestExternalities = t.into();
-    ext.execute_with(|| System::set_block_number(1));
-    ext
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	orml_tokens::GenesisConfig::<Test> {
+		endowed_accounts: vec![(ALICE, DOT, 1_000_000_000_000), (BOB, DOT, 1_000_000_000_000)],
+	}
+		.assimilate_storage(&mut t)
+		.unwrap();
+	GenesisBuild::<Test>::assimilate_storage(
+		&currency_token::GenesisConfig {
+			// tao 0, so taos created in tests start at 1
+			currency_tao: Some(vec![]),
+			currencies: vec![DOT],
+			assets: vec![],
+		},
+		&mut t,
+	)
+		.unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
 }
-
-pub fn run_to_block(n: u64) {
-    while System::block_number() < n {
-        Dex::on_finalize(System::block_number());
-        System::on_finalize(System::block_number());
-        System::set_block_number(System::block_number() + 1);
-        System::on_initialize(System::block_number());
-        Dex::on_initialize(System::block_number());
-    }
-}
\ No newline at end of file
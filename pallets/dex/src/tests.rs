@@ -1,61 +1,379 @@
-use crate::mock::*;
-use super::*;
-use frame_support::{assert_ok, assert_noop};
+use crate::{
+	Error, Event, Exchanges, CurrencyReserves, TotalSupplies, StorageVersion, Releases,
+	migrations::ExchangeV1, mock::{self, *}, with_slippage_index,
+};
+use codec::Encode;
+use frame_support::{
+	assert_err, assert_noop, assert_ok, Blake2_128Concat, StorageHasher,
+	storage::{unhashed, migration::{get_storage_value, put_storage_value}},
+	traits::{GenesisBuild, OnRuntimeUpgrade},
+};
+use primitives::Balance;
+use sp_runtime::{DispatchError, Permill};
+
+/// The fee every exchange took before fees were configurable.
+const LEGACY_FEE: Permill = Permill::from_parts(5_000);
+
+fn last_event() -> mock::Event {
+	System::events().pop().expect("Event expected").event
+}
+
+/// Taos 1 and 2 of `ALICE`, each with token 1, and an exchange of DOT for each,
+/// so exchanges 0 and 1 trade the same token id.
+fn setup_exchanges() {
+	assert_ok!(Token::create_tao(Origin::signed(ALICE), vec![]));
+	assert_ok!(Token::create_tao(Origin::signed(ALICE), vec![]));
+	assert_ok!(Token::mint(Origin::signed(ALICE), ALICE, 1, 1, 10_000));
+	assert_ok!(Token::mint(Origin::signed(ALICE), ALICE, 2, 1, 10_000));
+	assert_ok!(CurrencyToken::wrap(Origin::signed(ALICE), DOT, 500_000_000_000));
+	assert_ok!(CurrencyToken::wrap(Origin::signed(BOB), DOT, 500_000_000_000));
+
+	assert_ok!(Dex::create_exchange(Origin::signed(ALICE), DOT, 1, LEGACY_FEE));
+	assert_ok!(Dex::create_exchange(Origin::signed(ALICE), DOT, 2, LEGACY_FEE));
+}
+
+/// Rewrite an exchange in its layout before `v3`, without a fee.
+fn store_as_v1(exchange_id: u32) {
+	let exchange = Exchanges::<Test>::get(exchange_id).unwrap();
+	unhashed::put(&Exchanges::<Test>::hashed_key_for(exchange_id), &ExchangeV1 {
+		creator: exchange.creator,
+		token_tao: exchange.token_tao,
+		currency_tao: exchange.currency_tao,
+		currency_token: exchange.currency_token,
+		liquidity_tao: exchange.liquidity_tao,
+		vault: exchange.vault,
+	});
+}
 
 #[test]
 fn create_exchange_works() {
-    new_test_ext().execute_with(|| {
-		run_to_block(10);
-		assert!(Dex::exchanges(0).is_none());
-		assert_ok!(Dex::create_exchange(Origin::signed(1), 1));
+	new_test_ext().execute_with(|| {
+		assert_ok!(Dex::create_exchange(Origin::signed(ALICE), DOT, 1, LEGACY_FEE));
+
+		let exchange = Exchanges::<Test>::get(0).unwrap();
+		assert_eq!(exchange.creator, ALICE);
+		assert_eq!(exchange.token_tao, 1);
+		assert!(Token::is_tao_owner(&exchange.vault, exchange.liquidity_tao));
+		assert_eq!(Dex::next_exchange_id(), 1);
+	})
+}
+
+#[test]
+fn exchanges_sharing_a_token_id_do_not_interfere() {
+	new_test_ext().execute_with(|| {
+		setup_exchanges();
+
+		assert_ok!(Dex::add_liquidity(Origin::signed(ALICE), 0, ALICE, vec![1], vec![1_000], vec![10_000_000_000], None));
+		// the first deposit of exchange 1 sets its price, whatever exchange 0 holds
+		assert_ok!(Dex::add_liquidity(Origin::signed(ALICE), 1, ALICE, vec![1], vec![4_000], vec![20_000_000_000], None));
+
+		assert_eq!(Dex::currency_reserves(0, 1), 10_000_000_000);
+		assert_eq!(Dex::currency_reserves(1, 1), 20_000_000_000);
+		assert_eq!(Dex::total_supplies(0, 1), 10_000_000_000);
+		assert_eq!(Dex::total_supplies(1, 1), 20_000_000_000);
+
+		let price_0 = Dex::get_buy_prices(0, vec![1], vec![100]).unwrap();
+		let price_1 = Dex::get_buy_prices(1, vec![1], vec![100]).unwrap();
+		assert_eq!(price_0, vec![1_116_694_585]);
+		assert_eq!(price_1, vec![515_397_501]);
+
+		assert_ok!(Dex::buy_tokens(Origin::signed(BOB), 0, vec![1], vec![100], vec![price_0[0]], BOB, None));
+
+		assert_eq!(Token::balance_of(&BOB, 1, 1), 100);
+		assert_eq!(Dex::currency_reserves(0, 1), 10_000_000_000 + price_0[0]);
+		assert_eq!(Dex::currency_reserves(1, 1), 20_000_000_000);
+		assert_eq!(Dex::get_buy_prices(1, vec![1], vec![100]).unwrap(), price_1);
+
+		// all the liquidity of exchange 1 is withdrawn at its own reserves
+		let liquidity_tao = Exchanges::<Test>::get(1).unwrap().liquidity_tao;
+		assert_eq!(Token::balance_of(&ALICE, liquidity_tao, 1), 20_000_000_000);
+		assert_ok!(Dex::remove_liquidity(
+			Origin::signed(ALICE), 1, ALICE, vec![1], vec![20_000_000_000], vec![20_000_000_000], vec![4_000], None,
+		));
+
+		assert_eq!(Dex::currency_reserves(1, 1), 0);
+		assert_eq!(Dex::total_supplies(1, 1), 0);
+		assert_eq!(Dex::total_supplies(0, 1), 10_000_000_000);
+		assert_eq!(Token::balance_of(&ALICE, 2, 1), 10_000);
+	})
+}
+
+#[test]
+fn migration_keys_entries_by_exchange() {
+	new_test_ext().execute_with(|| {
+		setup_exchanges();
+		let exchange_0 = Exchanges::<Test>::get(0).unwrap();
+		let exchange_1 = Exchanges::<Test>::get(1).unwrap();
+		let (vault_0, vault_1) = (exchange_0.vault.clone(), exchange_1.vault.clone());
+		assert_ok!(Token::transfer_from(Origin::signed(ALICE), ALICE, vault_0.clone(), 1, 1, 1_000));
+		assert_ok!(Token::transfer_from(Origin::signed(ALICE), ALICE, vault_1.clone(), 2, 1, 3_000));
+		let (currency_tao, currency_token) = (exchange_0.currency_tao, exchange_0.currency_token);
+		assert_ok!(Token::do_transfer_from(&ALICE, &vault_0, currency_tao, currency_token, 7_000_000));
+		assert_ok!(Token::do_transfer_from(&ALICE, &vault_1, currency_tao, currency_token, 2_000_000));
+		// the liquidity tokens are split 3:1 while the vaults hold the token 1:3
+		assert_ok!(Token::do_mint(&ALICE, exchange_0.liquidity_tao, 1, 500));
+		assert_ok!(Token::do_mint(&BOB, exchange_0.liquidity_tao, 1, 100));
+		assert_ok!(Token::do_mint(&ALICE, exchange_1.liquidity_tao, 1, 200));
+
+		// token 1 is shared by both exchanges, no vault holds token 2
+		let put_v1 = |item: &[u8], token_id: u64, value: Balance| {
+			put_storage_value(b"Dex", item, &Blake2_128Concat::hash(&token_id.encode()), value);
+		};
+		put_v1(b"CurrencyReserves", 1, 4_000_000);
+		put_v1(b"TotalSupplies", 1, 400);
+		put_v1(b"CurrencyReserves", 2, 50);
+		put_v1(b"TotalSupplies", 2, 5);
+		store_as_v1(0);
+		store_as_v1(1);
+
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V1_0_0);
+		Dex::on_runtime_upgrade();
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V3_0_0);
+
+		// the supplies are the outstanding liquidity tokens and the reserves what the vaults hold
+		assert_eq!(Dex::currency_reserves(0, 1), 7_000_000);
+		assert_eq!(Dex::currency_reserves(1, 1), 2_000_000);
+		assert_eq!(Dex::total_supplies(0, 1), 600);
+		assert_eq!(Dex::total_supplies(1, 1), 200);
+		assert_eq!(Exchanges::<Test>::get(0).unwrap().fee, LEGACY_FEE);
+		assert_eq!(Exchanges::<Test>::get(1).unwrap().vault, vault_1);
+
+		assert_eq!(CurrencyReserves::<Test>::iter().count(), 2);
+		assert_eq!(TotalSupplies::<Test>::iter().count(), 2);
+		assert_eq!(get_storage_value::<Balance>(b"Dex", b"CurrencyReserves", &Blake2_128Concat::hash(&1u64.encode())), None);
+
+		// every liquidity token can be withdrawn, the last one for all that is left
+		assert_ok!(Dex::remove_liquidity(Origin::signed(ALICE), 0, ALICE, vec![1], vec![500], vec![0], vec![0], None));
+		assert_ok!(Dex::remove_liquidity(Origin::signed(BOB), 0, BOB, vec![1], vec![100], vec![0], vec![0], None));
+		assert_eq!(Dex::total_supplies(0, 1), 0);
+		assert_eq!(Dex::currency_reserves(0, 1), 0);
+		assert_eq!(Token::balance_of(&vault_0, currency_tao, currency_token), 0);
+		assert_eq!(Token::balance_of(&vault_0, 1, 1), 0);
+	})
+}
+
+#[test]
+fn genesis_starts_at_latest_storage_version() {
+	let t = GenesisBuild::<Test>::build_storage(&crate::GenesisConfig::default()).unwrap();
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V3_0_0);
+		assert_eq!(Dex::on_runtime_upgrade(), 0);
+	})
+}
+
+#[test]
+fn create_exchange_checks_fee_range() {
+	new_test_ext().execute_with(|| {
+		// `(0, MaxFee)` until set
+		assert_noop!(
+			Dex::create_exchange(Origin::signed(ALICE), DOT, 1, Permill::from_percent(4)),
+			Error::<Test>::FeeOutOfRange,
+		);
+		assert_ok!(Dex::create_exchange(Origin::signed(ALICE), DOT, 1, Permill::zero()));
+
+		assert_noop!(
+			Dex::set_fee_range(Origin::signed(ALICE), Permill::from_percent(1), Permill::from_percent(2)),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Dex::set_fee_range(Origin::root(), Permill::from_percent(2), Permill::from_percent(1)),
+			Error::<Test>::InvalidFeeRange,
+		);
+		assert_noop!(
+			Dex::set_fee_range(Origin::root(), Permill::from_percent(1), Permill::from_percent(4)),
+			Error::<Test>::InvalidFeeRange,
+		);
+		assert_ok!(Dex::set_fee_range(Origin::root(), Permill::from_percent(1), Permill::from_percent(2)));
+		assert_eq!(last_event(), mock::Event::pallet_dex(Event::FeeRangeSet(Permill::from_percent(1), Permill::from_percent(2))));
 
-		assert!(Dex::exchanges(0).is_some());
+		assert_noop!(
+			Dex::create_exchange(Origin::signed(ALICE), DOT, 1, LEGACY_FEE),
+			Error::<Test>::FeeOutOfRange,
+		);
+		assert_ok!(Dex::create_exchange(Origin::signed(ALICE), DOT, 1, Permill::from_parts(15_000)));
+		assert_eq!(Exchanges::<Test>::get(1).unwrap().fee, Permill::from_parts(15_000));
+	})
+}
+
+#[test]
+fn higher_fees_raise_buy_prices_and_lower_sell_prices() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Dex::set_fee_range(Origin::root(), Permill::zero(), Permill::from_percent(3)));
+		setup_exchanges();
+		assert_ok!(Dex::create_exchange(Origin::signed(ALICE), DOT, 1, Permill::from_percent(3)));
+		assert_ok!(Token::mint(Origin::signed(ALICE), ALICE, 1, 1, 1_000));
+
+		assert_ok!(Dex::add_liquidity(Origin::signed(ALICE), 0, ALICE, vec![1], vec![1_000], vec![10_000_000_000], None));
+		assert_ok!(Dex::add_liquidity(Origin::signed(ALICE), 2, ALICE, vec![1], vec![1_000], vec![10_000_000_000], None));
+
+		let buy = |exchange_id| Dex::get_buy_prices(exchange_id, vec![1], vec![100]).unwrap()[0];
+		let sell = |exchange_id| Dex::get_sell_prices(exchange_id, vec![1], vec![100]).unwrap()[0];
+		assert!(buy(2) > buy(0));
+		assert!(sell(2) < sell(0));
+	})
+}
+
+#[test]
+fn protocol_fees_accrue_and_are_claimed() {
+	new_test_ext().execute_with(|| {
+		setup_exchanges();
+		assert_ok!(Dex::add_liquidity(Origin::signed(ALICE), 0, ALICE, vec![1], vec![1_000], vec![10_000_000_000], None));
 
-		assert_eq!(
-			System::events()
-				.into_iter()
-				.map(|r| r.event)
-				.filter_map(|e| {
-					if let TestEvent::dex(inner) = e {
-						Some(inner)
-					} else {
-						None
-					}
-				})
-				.last()
-				.unwrap(),
-			RawEvent::ExchangeCreated(0, 1),
+		assert_noop!(
+			Dex::set_protocol_fee_share(Origin::signed(ALICE), Permill::from_percent(50)),
+			DispatchError::BadOrigin,
 		);
-    })
+		assert_ok!(Dex::set_protocol_fee_share(Origin::root(), Permill::from_percent(50)));
+
+		let reserve = Dex::currency_reserves(0, 1);
+		let price = Dex::get_buy_prices(0, vec![1], vec![100]).unwrap()[0];
+		let fee = price - Dex::get_amount_in(100, reserve, 1_000, Permill::zero()).unwrap();
+		let protocol_fee = fee / 2;
+		assert!(protocol_fee > 0);
+
+		assert_ok!(Dex::buy_tokens(Origin::signed(BOB), 0, vec![1], vec![100], vec![price], BOB, None));
+		assert_eq!(last_event(), mock::Event::pallet_dex(Event::FeesTaken(0, vec![1], vec![fee], vec![protocol_fee])));
+		assert_eq!(Dex::currency_reserves(0, 1), reserve + price - protocol_fee);
+		assert_eq!(Dex::protocol_fees(0), protocol_fee);
+
+		// selling takes the protocol share out of the reserve too
+		let reserve = Dex::currency_reserves(0, 1);
+		let amount_out = Dex::get_sell_prices(0, vec![1], vec![50]).unwrap()[0];
+		let sell_fee = Dex::get_amount_out(50, 900, reserve, Permill::zero()).unwrap() - amount_out;
+		assert_ok!(Dex::sell_tokens(Origin::signed(BOB), 0, vec![1], vec![50], vec![amount_out], BOB, None));
+		assert_eq!(last_event(), mock::Event::pallet_dex(Event::FeesTaken(0, vec![1], vec![sell_fee], vec![sell_fee / 2])));
+		assert_eq!(Dex::currency_reserves(0, 1), reserve - amount_out - sell_fee / 2);
+
+		let accrued = protocol_fee + sell_fee / 2;
+		assert_eq!(Dex::protocol_fees(0), accrued);
+
+		assert_noop!(Dex::claim_protocol_fees(Origin::signed(ALICE), 0), DispatchError::BadOrigin);
+		assert_ok!(Dex::claim_protocol_fees(Origin::root(), 0));
+		assert_eq!(last_event(), mock::Event::pallet_dex(Event::ProtocolFeesClaimed(0, TREASURY, accrued)));
+
+		let exchange = Exchanges::<Test>::get(0).unwrap();
+		assert_eq!(Token::balance_of(&TREASURY, exchange.currency_tao, exchange.currency_token), accrued);
+		assert_eq!(Dex::protocol_fees(0), 0);
+		assert_noop!(Dex::claim_protocol_fees(Origin::root(), 0), Error::<Test>::NoProtocolFees);
+	})
 }
 
-pub fn before_exchange() {
-	Tao::create_tao(Origin::signed(1), [0].to_vec());
-	Tao::create_tao_item(Origin::signed(1), 0, false, [0].to_vec());
-	assert!(Token::tokens(0).is_some());
+#[test]
+fn migration_gives_exchanges_the_legacy_fee() {
+	new_test_ext().execute_with(|| {
+		setup_exchanges();
+		store_as_v1(0);
+		StorageVersion::<Test>::put(Releases::V2_0_0);
 
-	Currency::create(Origin::signed(1), [0].to_vec());
-	Currency::mint(Origin::signed(1), 0, 2000, 1);
-	assert!(Currency::currencies(0).is_some());
+		Dex::on_runtime_upgrade();
 
-	Dex::create_exchange(Origin::signed(1), 0);
-	assert!(Dex::exchanges(0).is_some());
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V3_0_0);
+		assert_eq!(Exchanges::<Test>::get(0).unwrap().fee, LEGACY_FEE);
+	})
 }
 
 #[test]
-fn add_liquidity_works() {
+fn swaps_fail_after_the_deadline() {
 	new_test_ext().execute_with(|| {
-		before_exchange();
-		run_to_block(10);
+		setup_exchanges();
+		assert_ok!(Dex::add_liquidity(Origin::signed(ALICE), 0, ALICE, vec![1], vec![1_000], vec![10_000_000_000], Some(1)));
+		System::set_block_number(5);
+
+		let price = Dex::get_buy_prices(0, vec![1], vec![100]).unwrap()[0];
+		assert_noop!(
+			Dex::buy_tokens(Origin::signed(BOB), 0, vec![1], vec![100], vec![price], BOB, Some(4)),
+			Error::<Test>::DeadlineExpired,
+		);
+		assert_noop!(
+			Dex::remove_liquidity(Origin::signed(ALICE), 0, ALICE, vec![1], vec![1_000], vec![0], vec![0], Some(4)),
+			Error::<Test>::DeadlineExpired,
+		);
+		assert_ok!(Dex::buy_tokens(Origin::signed(BOB), 0, vec![1], vec![100], vec![price], BOB, Some(5)));
 
+		let amount_out = Dex::get_sell_prices(0, vec![1], vec![50]).unwrap()[0];
+		assert_noop!(
+			Dex::sell_tokens(Origin::signed(BOB), 0, vec![1], vec![50], vec![amount_out], BOB, Some(4)),
+			Error::<Test>::DeadlineExpired,
+		);
+		assert_ok!(Dex::sell_tokens(Origin::signed(BOB), 0, vec![1], vec![50], vec![amount_out], BOB, None));
+	})
+}
+
+#[test]
+fn slippage_is_limited_per_token() {
+	new_test_ext().execute_with(|| {
+		setup_exchanges();
+		assert_ok!(Token::mint(Origin::signed(ALICE), ALICE, 1, 2, 10_000));
 		assert_ok!(Dex::add_liquidity(
-			Origin::signed(1),
-			0,
-			2,
-			[0].to_vec(),
-			[100].to_vec(),
-			[1000].to_vec(),
-			20
+			Origin::signed(ALICE), 0, ALICE, vec![1, 2], vec![1_000, 1_000], vec![10_000_000_000, 20_000_000_000], None,
 		));
-	});
+
+		let prices = Dex::get_buy_prices(0, vec![1, 2], vec![100, 100]).unwrap();
+		let (currency_tao, currency_token) = CurrencyToken::get_currency_token(DOT).unwrap();
+		let bob_currency = || Token::balance_of(&BOB, currency_tao, currency_token);
+		let currency_before = bob_currency();
+
+		// the batch covers the total, but not the price of token 2, whose index the extrinsic reports
+		assert_err!(
+			Dex::buy_tokens(Origin::signed(BOB), 0, vec![1, 2], vec![100, 100], vec![prices[0] + prices[1], prices[1] - 1], BOB, None),
+			Error::<Test>::SlippageExceeded,
+		);
+		assert_eq!(last_event(), mock::Event::pallet_dex(Event::SlippageExceeded(0, BOB, 1)));
+		assert_eq!(bob_currency(), currency_before);
+		assert_noop!(
+			Dex::buy_tokens(Origin::signed(BOB), 0, vec![1, 2], vec![100, 100], vec![prices[0]], BOB, None),
+			Error::<Test>::InvalidArrayLength,
+		);
+		assert_ok!(Dex::buy_tokens(Origin::signed(BOB), 0, vec![1, 2], vec![100, 100], prices.clone(), BOB, None));
+		assert_eq!(bob_currency(), currency_before - prices[0] - prices[1]);
+
+		let amounts_out = Dex::get_sell_prices(0, vec![1, 2], vec![50, 50]).unwrap();
+		assert_err!(
+			Dex::sell_tokens(Origin::signed(BOB), 0, vec![1, 2], vec![50, 50], vec![0, amounts_out[1] + 1], BOB, None),
+			Error::<Test>::SlippageExceeded,
+		);
+		assert_eq!(last_event(), mock::Event::pallet_dex(Event::SlippageExceeded(0, BOB, 1)));
+		assert_ok!(Dex::sell_tokens(Origin::signed(BOB), 0, vec![1, 2], vec![50, 50], amounts_out, BOB, None));
+
+		assert_err!(
+			Dex::add_liquidity(Origin::signed(ALICE), 0, ALICE, vec![1], vec![100], vec![1], None),
+			Error::<Test>::SlippageExceeded,
+		);
+		assert_eq!(last_event(), mock::Event::pallet_dex(Event::SlippageExceeded(0, ALICE, 0)));
+		assert_err!(
+			Dex::remove_liquidity(Origin::signed(ALICE), 0, ALICE, vec![1], vec![1_000], vec![0], vec![1_000], None),
+			Error::<Test>::SlippageExceeded,
+		);
+		assert_eq!(last_event(), mock::Event::pallet_dex(Event::SlippageExceeded(0, ALICE, 0)));
+
+		// the index of the token id is kept for callers asking for it, last as the failed
+		// `do_*` functions are not rolled back
+		let amounts_out = Dex::get_sell_prices(0, vec![1, 2], vec![50, 50]).unwrap();
+		let (result, index) = with_slippage_index(|| {
+			Dex::do_sell_tokens(&BOB, 0, vec![1, 2], vec![50, 50], vec![0, amounts_out[1] + 1], &BOB, None)
+		});
+		assert_eq!(result, Err(Error::<Test>::SlippageExceeded.into()));
+		assert_eq!(index, Some(1));
+		let (result, index) = with_slippage_index(|| {
+			Dex::do_sell_tokens(&BOB, 0, vec![1, 2], vec![50, 50], vec![0, 0], &BOB, Some(0))
+		});
+		assert_eq!(result, Err(Error::<Test>::DeadlineExpired.into()));
+		assert_eq!(index, None);
+	})
+}
+
+#[test]
+fn quoting_the_whole_reserve_fails() {
+	new_test_ext().execute_with(|| {
+		setup_exchanges();
+		assert_ok!(Dex::add_liquidity(Origin::signed(ALICE), 0, ALICE, vec![1], vec![1_000], vec![10_000_000_000], None));
+
+		assert_eq!(Dex::get_buy_prices(0, vec![1], vec![1_000]), Err(Error::<Test>::InsufficientLiquidity.into()));
+		assert_eq!(Dex::get_buy_prices(0, vec![1], vec![1_001]), Err(Error::<Test>::InsufficientLiquidity.into()));
+		assert_noop!(
+			Dex::buy_tokens(Origin::signed(BOB), 0, vec![1], vec![1_000], vec![u128::MAX], BOB, None),
+			Error::<Test>::InsufficientLiquidity,
+		);
+		assert_ok!(Dex::get_buy_prices(0, vec![1], vec![999]));
+	})
 }
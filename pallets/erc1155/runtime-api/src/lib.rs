@@ -0,0 +1,19 @@
+//! Runtime API definition for the erc1155 pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	pub trait Erc1155Api<TaoId, TokenId> where
+		TaoId: Codec,
+		TokenId: Codec,
+	{
+		/// The value of an attribute of a tao, or of a token when `token_id` is given.
+		fn attribute(tao_id: TaoId, token_id: Option<TokenId>, key: Vec<u8>) -> Option<Vec<u8>>;
+
+		/// All `(key, value)` attributes of a tao, or of a token when `token_id` is given.
+		fn attributes(tao_id: TaoId, token_id: Option<TokenId>) -> Vec<(Vec<u8>, Vec<u8>)>;
+	}
+}
@@ -0,0 +1,168 @@
+//! Benchmarks for pallet_erc1155
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{benchmarks, account, whitelisted_caller, impl_benchmark_test_suite};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+const SEED: u32 = 0;
+const MAX_BATCH: u32 = 100;
+const MAX_DATA_LEN: u32 = 1024;
+
+fn create_tao<T: Config>(owner: &T::AccountId) -> T::TaoId {
+	Pallet::<T>::do_create_tao(owner, vec![0u8; 32]).expect("tao can be created")
+}
+
+fn mint_tokens<T: Config>(to: &T::AccountId, tao_id: T::TaoId, n: u32) -> (Vec<T::TokenId>, Vec<Balance>) {
+	let token_ids: Vec<T::TokenId> = (0..n as u64).map(T::TokenId::from).collect();
+	let amounts = vec![100; n as usize];
+	Pallet::<T>::do_batch_mint(to, tao_id, token_ids.clone(), amounts.clone()).expect("tokens can be minted");
+	(token_ids, amounts)
+}
+
+benchmarks! {
+	create_tao {
+		let b in 0 .. MAX_DATA_LEN;
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), vec![0u8; b as usize])
+
+	create_token {
+		let b in 0 .. MAX_DATA_LEN;
+		let caller: T::AccountId = whitelisted_caller();
+		let tao_id = create_tao::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), tao_id, T::TokenId::from(0), false, vec![0u8; b as usize])
+
+	set_approval_for_all {
+		let caller: T::AccountId = whitelisted_caller();
+		let operator: T::AccountId = account("operator", 0, SEED);
+	}: _(RawOrigin::Signed(caller), operator, true)
+
+	mint {
+		let caller: T::AccountId = whitelisted_caller();
+		let tao_id = create_tao::<T>(&caller);
+	}: _(RawOrigin::Signed(caller.clone()), caller.clone(), tao_id, T::TokenId::from(0), 100)
+
+	batch_mint {
+		let n in 1 .. MAX_BATCH;
+		let caller: T::AccountId = whitelisted_caller();
+		let tao_id = create_tao::<T>(&caller);
+		let token_ids: Vec<T::TokenId> = (0..n as u64).map(T::TokenId::from).collect();
+	}: _(RawOrigin::Signed(caller.clone()), caller.clone(), tao_id, token_ids, vec![100; n as usize])
+
+	burn {
+		let caller: T::AccountId = whitelisted_caller();
+		let tao_id = create_tao::<T>(&caller);
+		mint_tokens::<T>(&caller, tao_id, 1);
+	}: _(RawOrigin::Signed(caller.clone()), caller.clone(), tao_id, T::TokenId::from(0), 100)
+
+	batch_burn {
+		let n in 1 .. MAX_BATCH;
+		let caller: T::AccountId = whitelisted_caller();
+		let tao_id = create_tao::<T>(&caller);
+		let (token_ids, amounts) = mint_tokens::<T>(&caller, tao_id, n);
+	}: _(RawOrigin::Signed(caller.clone()), caller.clone(), tao_id, token_ids, amounts)
+
+	transfer_from {
+		let caller: T::AccountId = whitelisted_caller();
+		let to: T::AccountId = account("to", 0, SEED);
+		let tao_id = create_tao::<T>(&caller);
+		mint_tokens::<T>(&caller, tao_id, 1);
+	}: _(RawOrigin::Signed(caller.clone()), caller.clone(), to, tao_id, T::TokenId::from(0), 100)
+
+	batch_transfer_from {
+		let n in 1 .. MAX_BATCH;
+		let caller: T::AccountId = whitelisted_caller();
+		let to: T::AccountId = account("to", 0, SEED);
+		let tao_id = create_tao::<T>(&caller);
+		let (token_ids, amounts) = mint_tokens::<T>(&caller, tao_id, n);
+	}: _(RawOrigin::Signed(caller.clone()), caller.clone(), to, tao_id, token_ids, amounts)
+
+	set_game_server {
+		let caller: T::AccountId = whitelisted_caller();
+		let server: T::AccountId = account("server", 0, SEED);
+		let tao_id = create_tao::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), tao_id, Some(server))
+
+	set_attribute {
+		let b in 0 .. T::ValueLimit::get();
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let tao_id = create_tao::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), tao_id, None, vec![0u8], vec![0u8; b as usize])
+
+	clear_attribute {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let tao_id = create_tao::<T>(&caller);
+		Pallet::<T>::do_set_attribute(&caller, tao_id, None, vec![0u8], vec![0u8; T::ValueLimit::get() as usize])?;
+	}: _(RawOrigin::Signed(caller), tao_id, None, vec![0u8])
+
+	balance_of {
+		let caller: T::AccountId = whitelisted_caller();
+		let tao_id = create_tao::<T>(&caller);
+		mint_tokens::<T>(&caller, tao_id, 1);
+	}: {
+		Pallet::<T>::balance_of(&caller, tao_id, T::TokenId::from(0));
+	}
+
+	balance_of_batch {
+		let n in 1 .. MAX_BATCH;
+		let caller: T::AccountId = whitelisted_caller();
+		let tao_id = create_tao::<T>(&caller);
+		let (token_ids, _) = mint_tokens::<T>(&caller, tao_id, n);
+		let owners = vec![caller; n as usize];
+	}: {
+		Pallet::<T>::balance_of_batch(&owners, tao_id, token_ids)?;
+	}
+
+	approved_or_owner {
+		let caller: T::AccountId = whitelisted_caller();
+		let owner: T::AccountId = account("owner", 0, SEED);
+	}: {
+		Pallet::<T>::approved_or_owner(&owner, &caller);
+	}
+
+	is_approved_for_all {
+		let caller: T::AccountId = whitelisted_caller();
+		let owner: T::AccountId = account("owner", 0, SEED);
+	}: {
+		Pallet::<T>::is_approved_for_all(&owner, &caller);
+	}
+
+	attribute {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let tao_id = create_tao::<T>(&caller);
+		Pallet::<T>::do_set_attribute(&caller, tao_id, None, vec![0u8], vec![0u8; T::ValueLimit::get() as usize])?;
+	}: {
+		Pallet::<T>::attribute(tao_id, None, vec![0u8]);
+	}
+
+	tao {
+		let caller: T::AccountId = whitelisted_caller();
+		let tao_id = create_tao::<T>(&caller);
+	}: {
+		Pallet::<T>::taos(tao_id);
+	}
+
+	token {
+		let caller: T::AccountId = whitelisted_caller();
+		let tao_id = create_tao::<T>(&caller);
+		Pallet::<T>::do_create_token(&caller, tao_id, T::TokenId::from(0), false, vec![0u8; MAX_DATA_LEN as usize])?;
+	}: {
+		Pallet::<T>::tokens(tao_id, T::TokenId::from(0));
+	}
+
+	next_tao_id {
+	}: {
+		Pallet::<T>::next_tao_id();
+	}
+}
+
+impl_benchmark_test_suite!(
+	Pallet,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
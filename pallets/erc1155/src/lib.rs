@@ -4,17 +4,28 @@ use sp_std::{fmt::Debug, prelude::*};
 use sp_runtime::{
 	RuntimeDebug,
 	traits::{
-		AtLeast32BitUnsigned, CheckedAdd, One,
+		AtLeast32BitUnsigned, CheckedAdd, One, Saturating,
 	},
 };
 use codec::{Encode, Decode, HasCompact};
 use frame_support::{
 	ensure,
 	dispatch::{DispatchResult, DispatchError},
+	traits::{Currency, Get, ReservableCurrency},
+	transactional,
 };
 use primitives::Balance;
 
 pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 #[cfg(test)]
 mod mock;
@@ -35,6 +46,24 @@ pub mod pallet {
 		type TokenId: Member + Parameter + Default + Copy + HasCompact + From<u64> + Into<u64>;
 
 		type TaoId: Member + Parameter + AtLeast32BitUnsigned + Default + Copy + From<u64> + Into<u64>;
+
+		/// The currency in which attribute deposits are reserved.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The base deposit reserved for every attribute.
+		type AttributeDepositBase: Get<BalanceOf<Self>>;
+
+		/// The additional deposit reserved per byte of attribute key and value.
+		type DepositPerByte: Get<BalanceOf<Self>>;
+
+		/// The maximum length of an attribute key.
+		type KeyLimit: Get<u32>;
+
+		/// The maximum length of an attribute value.
+		type ValueLimit: Get<u32>;
+
+		/// Weight information for the extrinsics and queries of this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	// pub type GenesisTaos<T> = (
@@ -69,6 +98,7 @@ pub mod pallet {
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
+	#[pallet::getter(fn taos)]
 	pub(super) type Taos<T: Config> = StorageMap<
 		_,
 		Blake2_128Concat,
@@ -85,6 +115,7 @@ pub mod pallet {
 	>;
 
 	#[pallet::storage]
+	#[pallet::getter(fn tokens)]
 	pub(super) type Tokens<T: Config> = StorageDoubleMap<
 		_,
 		Blake2_128Concat,
@@ -118,6 +149,26 @@ pub mod pallet {
 		ValueQuery
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn game_servers)]
+	pub(super) type GameServers<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::TaoId,
+		T::AccountId
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn attributes)]
+	pub(super) type Attributes<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(T::TaoId, Option<T::TokenId>),
+		Blake2_128Concat,
+		Vec<u8>,
+		(Vec<u8>, BalanceOf<T>)
+	>;
+
 	#[pallet::event]
 	#[pallet::metadata(T::AccountId = "AccountId")]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -131,6 +182,9 @@ pub mod pallet {
 		Transferred(T::AccountId, T::AccountId, T::TaoId, T::TokenId, Balance),
 		BatchTransferred(T::AccountId, T::AccountId, T::TaoId, Vec<T::TokenId>, Vec<Balance>),
 		ApprovalForAll(T::AccountId, T::AccountId, bool),
+		GameServerSet(T::TaoId, Option<T::AccountId>),
+		AttributeSet(T::TaoId, Option<T::TokenId>, Vec<u8>, Vec<u8>),
+		AttributeCleared(T::TaoId, Option<T::TokenId>, Vec<u8>),
 	}
 
 	#[pallet::error]
@@ -144,6 +198,10 @@ pub mod pallet {
 		Overflow,
 		NoAvailableTaoId,
 		InvalidTaoId,
+		NoPermission,
+		KeyTooLong,
+		ValueTooLong,
+		AttributeNotFound,
 	}
 
 	#[pallet::hooks]
@@ -152,7 +210,7 @@ pub mod pallet {
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::create_tao(data.len() as u32))]
 		pub fn create_tao(origin: OriginFor<T>, data: Vec<u8>) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
@@ -160,7 +218,7 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::create_token(uri.len() as u32))]
 		pub fn create_token(
 			origin: OriginFor<T>,
 			tao_id: T::TaoId,
@@ -174,7 +232,7 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::set_approval_for_all())]
 		pub fn set_approval_for_all(
 			origin: OriginFor<T>,
 			operator: T::AccountId,
@@ -191,7 +249,8 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		/// Transfer a token of `from`, the caller or an account that approved it
+		#[pallet::weight(T::WeightInfo::transfer_from())]
 		pub fn transfer_from(
 			origin: OriginFor<T>,
 			from: T::AccountId,
@@ -200,14 +259,16 @@ pub mod pallet {
 			token_id: T::TokenId,
 			amount: Balance,
 		) -> DispatchResultWithPostInfo {
-			let _who = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			ensure!(Self::approved_or_owner(&from, &who), Error::<T>::NoPermission);
 
 			Self::do_transfer_from(&from, &to, tao_id, token_id, amount)?;
 			
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		/// Transfer tokens of `from`, the caller or an account that approved it
+		#[pallet::weight(T::WeightInfo::batch_transfer_from(token_ids.len() as u32))]
 		pub fn batch_transfer_from(
 			origin: OriginFor<T>,
 			from: T::AccountId,
@@ -216,14 +277,16 @@ pub mod pallet {
 			token_ids: Vec<T::TokenId>,
 			amounts: Vec<Balance>
 		) -> DispatchResultWithPostInfo {
-			let _who = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			ensure!(Self::approved_or_owner(&from, &who), Error::<T>::NoPermission);
 
 			Self::do_batch_transfer_from(&from, &to, tao_id, token_ids, amounts)?;
 			
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		/// Mint a token of a tao owned by the caller
+		#[pallet::weight(T::WeightInfo::mint())]
 		pub fn mint(
 			origin: OriginFor<T>,
 			to: T::AccountId,
@@ -231,14 +294,16 @@ pub mod pallet {
 			token_id: T::TokenId,
 			amount: Balance,
 		) -> DispatchResultWithPostInfo {
-			let _who = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_tao_owner(&who, tao_id), Error::<T>::NoPermission);
 
 			Self::do_mint(&to, tao_id, token_id, amount)?;
 			
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		/// Mint tokens of a tao owned by the caller
+		#[pallet::weight(T::WeightInfo::batch_mint(token_ids.len() as u32))]
 		pub fn batch_mint(
 			origin: OriginFor<T>,
 			to: T::AccountId,
@@ -246,14 +311,16 @@ pub mod pallet {
 			token_ids: Vec<T::TokenId>,
 			amounts: Vec<Balance>
 		) -> DispatchResultWithPostInfo {
-			let _who = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_tao_owner(&who, tao_id), Error::<T>::NoPermission);
 
 			Self::do_batch_mint(&to, tao_id, token_ids, amounts)?;
 			
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		/// Burn a token of a tao owned by the caller, from the caller or an account that approved it
+		#[pallet::weight(T::WeightInfo::burn())]
 		pub fn burn(
 			origin: OriginFor<T>,
 			from: T::AccountId,
@@ -261,14 +328,17 @@ pub mod pallet {
 			token_id: T::TokenId,
 			amount: Balance,
 		) -> DispatchResultWithPostInfo {
-			let _who = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_tao_owner(&who, tao_id), Error::<T>::NoPermission);
+			ensure!(Self::approved_or_owner(&from, &who), Error::<T>::NoPermission);
 
 			Self::do_burn(&from, tao_id, token_id, amount)?;
 			
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		/// Burn tokens of a tao owned by the caller, from the caller or an account that approved it
+		#[pallet::weight(T::WeightInfo::batch_burn(token_ids.len() as u32))]
 		pub fn batch_burn(
 			origin: OriginFor<T>,
 			from: T::AccountId,
@@ -276,12 +346,56 @@ pub mod pallet {
 			token_ids: Vec<T::TokenId>,
 			amounts: Vec<Balance>
 		) -> DispatchResultWithPostInfo {
-			let _who = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+			ensure!(Self::is_tao_owner(&who, tao_id), Error::<T>::NoPermission);
+			ensure!(Self::approved_or_owner(&from, &who), Error::<T>::NoPermission);
 
 			Self::do_batch_burn(&from, tao_id, token_ids, amounts)?;
 			
 			Ok(().into())
 		}
+
+		#[pallet::weight(T::WeightInfo::set_game_server())]
+		pub fn set_game_server(
+			origin: OriginFor<T>,
+			tao_id: T::TaoId,
+			game_server: Option<T::AccountId>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_set_game_server(&who, tao_id, game_server)?;
+
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::set_attribute((key.len() + value.len()) as u32))]
+		pub fn set_attribute(
+			origin: OriginFor<T>,
+			tao_id: T::TaoId,
+			token_id: Option<T::TokenId>,
+			key: Vec<u8>,
+			value: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_set_attribute(&who, tao_id, token_id, key, value)?;
+
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::clear_attribute())]
+		pub fn clear_attribute(
+			origin: OriginFor<T>,
+			tao_id: T::TaoId,
+			token_id: Option<T::TokenId>,
+			key: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::do_clear_attribute(&who, tao_id, token_id, key)?;
+
+			Ok(().into())
+		}
 	}
 }
 
@@ -289,8 +403,10 @@ pub mod pallet {
 pub struct Tao<
 	AccountId: Encode + Decode + Clone + Debug + Eq + PartialEq,
 > {
-	owner: AccountId,
-	data: Vec<u8>,
+	/// The owner of Tao
+	pub owner: AccountId,
+	/// The data of Tao
+	pub data: Vec<u8>,
 }
 
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
@@ -298,10 +414,14 @@ pub struct Token<
 	TaoId: Encode + Decode + Clone + Debug + Eq + PartialEq,
 	AccountId: Encode + Decode + Clone + Debug + Eq + PartialEq,
 > {
-	tao_id: TaoId,
-	creator: AccountId,
-	is_nf: bool,
-	uri: Vec<u8>,
+	/// The tao of Token
+	pub tao_id: TaoId,
+	/// The creator of Token
+	pub creator: AccountId,
+	/// Whether Token is non-fungible
+	pub is_nf: bool,
+	/// The uri of Token
+	pub uri: Vec<u8>,
 }
 
 impl<T: Config> Pallet<T> {
@@ -582,6 +702,123 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Move every token balance of `source` to `dest`. Taos owned by `source` stay with it.
+	#[transactional]
+	pub fn do_merge_account(source: &T::AccountId, dest: &T::AccountId) -> DispatchResult {
+		if source == dest {
+			return Ok(());
+		}
+
+		for ((tao_id, token_id), amount) in Balances::<T>::drain_prefix(source).collect::<Vec<_>>() {
+			Balances::<T>::try_mutate(dest, (tao_id, token_id), |balance| -> DispatchResult {
+				*balance = balance
+					.checked_add(amount)
+					.ok_or(Error::<T>::NumOverflow)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Transferred(source.clone(), dest.clone(), tao_id, token_id, amount));
+		}
+
+		Ok(())
+	}
+
+	/// Every balance, by account and token. Iterates the whole map, so only for migrations.
+	pub fn all_balances() -> impl Iterator<Item = (T::AccountId, (T::TaoId, T::TokenId), Balance)> {
+		Balances::<T>::iter()
+	}
+
+	pub fn is_tao_owner(who: &T::AccountId, tao_id: T::TaoId) -> bool {
+		Self::taos(tao_id)
+			.map(|tao| tao.owner == *who)
+			.unwrap_or(false)
+	}
+
+	/// Attributes of a tao can be written by its owner or its game server
+	pub fn can_write_attributes(who: &T::AccountId, tao_id: T::TaoId) -> bool {
+		Self::is_tao_owner(who, tao_id)
+			|| Self::game_servers(tao_id).map(|server| server == *who).unwrap_or(false)
+	}
+
+	pub fn do_set_game_server(
+		who: &T::AccountId,
+		tao_id: T::TaoId,
+		game_server: Option<T::AccountId>,
+	) -> DispatchResult {
+		ensure!(Self::is_tao_owner(who, tao_id), Error::<T>::NoPermission);
+
+		GameServers::<T>::mutate_exists(tao_id, |server| *server = game_server.clone());
+
+		Self::deposit_event(Event::GameServerSet(tao_id, game_server));
+
+		Ok(())
+	}
+
+	// The deposit is always reserved from the tao owner, even if the game server writes.
+	pub fn do_set_attribute(
+		who: &T::AccountId,
+		tao_id: T::TaoId,
+		token_id: Option<T::TokenId>,
+		key: Vec<u8>,
+		value: Vec<u8>,
+	) -> DispatchResult {
+		let tao = Self::taos(tao_id).ok_or(Error::<T>::InvalidTaoId)?;
+		ensure!(Self::can_write_attributes(who, tao_id), Error::<T>::NoPermission);
+		if let Some(token_id) = token_id {
+			ensure!(Tokens::<T>::contains_key(tao_id, token_id), Error::<T>::InvalidTokenId);
+		}
+		ensure!(key.len() <= T::KeyLimit::get() as usize, Error::<T>::KeyTooLong);
+		ensure!(value.len() <= T::ValueLimit::get() as usize, Error::<T>::ValueTooLong);
+
+		let old_deposit = Attributes::<T>::get((tao_id, token_id), &key)
+			.map(|(_, deposit)| deposit)
+			.unwrap_or_default();
+		let bytes = (key.len() + value.len()) as u32;
+		let deposit = T::DepositPerByte::get()
+			.saturating_mul(bytes.into())
+			.saturating_add(T::AttributeDepositBase::get());
+
+		if deposit > old_deposit {
+			T::Currency::reserve(&tao.owner, deposit - old_deposit)?;
+		} else if deposit < old_deposit {
+			T::Currency::unreserve(&tao.owner, old_deposit - deposit);
+		}
+
+		Attributes::<T>::insert((tao_id, token_id), &key, (value.clone(), deposit));
+
+		Self::deposit_event(Event::AttributeSet(tao_id, token_id, key, value));
+
+		Ok(())
+	}
+
+	pub fn do_clear_attribute(
+		who: &T::AccountId,
+		tao_id: T::TaoId,
+		token_id: Option<T::TokenId>,
+		key: Vec<u8>,
+	) -> DispatchResult {
+		let tao = Self::taos(tao_id).ok_or(Error::<T>::InvalidTaoId)?;
+		ensure!(Self::can_write_attributes(who, tao_id), Error::<T>::NoPermission);
+
+		let (_, deposit) = Attributes::<T>::take((tao_id, token_id), &key)
+			.ok_or(Error::<T>::AttributeNotFound)?;
+		T::Currency::unreserve(&tao.owner, deposit);
+
+		Self::deposit_event(Event::AttributeCleared(tao_id, token_id, key));
+
+		Ok(())
+	}
+
+	pub fn attribute(tao_id: T::TaoId, token_id: Option<T::TokenId>, key: Vec<u8>) -> Option<Vec<u8>> {
+		Self::attributes((tao_id, token_id), key).map(|(value, _)| value)
+	}
+
+	pub fn attributes_of(tao_id: T::TaoId, token_id: Option<T::TokenId>) -> Vec<(Vec<u8>, Vec<u8>)> {
+		Attributes::<T>::iter_prefix((tao_id, token_id))
+			.map(|(key, (value, _))| (key, value))
+			.collect()
+	}
+
 	// func_id 1011  approved_or_owner(who: &T::AccountId, account: &T::AccountId) -> bool
 	pub fn approved_or_owner(who: &T::AccountId, account: &T::AccountId) -> bool {
 		*account != T::AccountId::default()
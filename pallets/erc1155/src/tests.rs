@@ -5,10 +5,156 @@ use frame_support::{assert_ok, assert_noop};
 fn create_token_works() {
     new_test_ext().execute_with(|| {
         let uri = vec![0, 1];
-        assert_ok!(TokenModule::create_token(&1, true, &uri));
+        assert_ok!(TokenModule::create_tao(Origin::signed(ALICE), vec![]));
+        assert_ok!(TokenModule::create_token(Origin::signed(ALICE), 0, 1, true, uri.clone()));
 
-        println!("token: {:?}", Tokens::<Test>::get(&0).unwrap());
+        println!("token: {:?}", Tokens::<Test>::get(0, 1).unwrap());
 
-        assert_eq!(TokenCount::<Test>::get().unwrap(), 1);
+        assert_eq!(Tokens::<Test>::get(0, 1).unwrap().uri, uri);
+        assert_eq!(NextTaoId::<Test>::get(), 1);
+    })
+}
+
+fn setup_tao() {
+    assert_ok!(TokenModule::create_tao(Origin::signed(ALICE), vec![]));
+    assert_ok!(TokenModule::create_token(Origin::signed(ALICE), 0, 1, true, vec![]));
+}
+
+#[test]
+fn set_attribute_works() {
+    new_test_ext().execute_with(|| {
+        setup_tao();
+
+        assert_ok!(TokenModule::set_attribute(Origin::signed(ALICE), 0, None, b"name".to_vec(), b"sword".to_vec()));
+        assert_ok!(TokenModule::set_attribute(Origin::signed(ALICE), 0, Some(1), b"level".to_vec(), vec![3]));
+
+        assert_eq!(TokenModule::attribute(0, None, b"name".to_vec()), Some(b"sword".to_vec()));
+        assert_eq!(TokenModule::attribute(0, Some(1), b"level".to_vec()), Some(vec![3]));
+        assert_eq!(TokenModule::attributes_of(0, Some(1)), vec![(b"level".to_vec(), vec![3])]);
+
+        // 10 + 9 bytes, 10 + 6 bytes
+        assert_eq!(Balances::reserved_balance(ALICE), 35);
+    })
+}
+
+#[test]
+fn overwrite_attribute_adjusts_deposit() {
+    new_test_ext().execute_with(|| {
+        setup_tao();
+
+        assert_ok!(TokenModule::set_attribute(Origin::signed(ALICE), 0, Some(1), b"level".to_vec(), vec![3; 10]));
+        assert_eq!(Balances::reserved_balance(ALICE), 25);
+
+        assert_ok!(TokenModule::set_attribute(Origin::signed(ALICE), 0, Some(1), b"level".to_vec(), vec![4]));
+        assert_eq!(Balances::reserved_balance(ALICE), 16);
+    })
+}
+
+#[test]
+fn clear_attribute_works() {
+    new_test_ext().execute_with(|| {
+        setup_tao();
+
+        assert_ok!(TokenModule::set_attribute(Origin::signed(ALICE), 0, Some(1), b"level".to_vec(), vec![3]));
+        assert_ok!(TokenModule::clear_attribute(Origin::signed(ALICE), 0, Some(1), b"level".to_vec()));
+
+        assert_eq!(TokenModule::attribute(0, Some(1), b"level".to_vec()), None);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+
+        assert_noop!(
+            TokenModule::clear_attribute(Origin::signed(ALICE), 0, Some(1), b"level".to_vec()),
+            Error::<Test>::AttributeNotFound
+        );
+    })
+}
+
+#[test]
+fn game_server_can_write_attributes() {
+    new_test_ext().execute_with(|| {
+        setup_tao();
+
+        assert_noop!(
+            TokenModule::set_attribute(Origin::signed(BOB), 0, Some(1), b"level".to_vec(), vec![3]),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            TokenModule::set_game_server(Origin::signed(BOB), 0, Some(BOB)),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(TokenModule::set_game_server(Origin::signed(ALICE), 0, Some(BOB)));
+        assert_ok!(TokenModule::set_attribute(Origin::signed(BOB), 0, Some(1), b"level".to_vec(), vec![3]));
+
+        // the deposit is taken from the tao owner
+        assert_eq!(Balances::reserved_balance(ALICE), 16);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+
+        assert_ok!(TokenModule::set_game_server(Origin::signed(ALICE), 0, None));
+        assert_noop!(
+            TokenModule::clear_attribute(Origin::signed(BOB), 0, Some(1), b"level".to_vec()),
+            Error::<Test>::NoPermission
+        );
+    })
+}
+
+#[test]
+fn set_attribute_checks_bounds() {
+    new_test_ext().execute_with(|| {
+        setup_tao();
+
+        assert_noop!(
+            TokenModule::set_attribute(Origin::signed(ALICE), 0, Some(1), vec![0; 9], vec![]),
+            Error::<Test>::KeyTooLong
+        );
+        assert_noop!(
+            TokenModule::set_attribute(Origin::signed(ALICE), 0, Some(1), vec![0], vec![0; 17]),
+            Error::<Test>::ValueTooLong
+        );
+        assert_noop!(
+            TokenModule::set_attribute(Origin::signed(ALICE), 0, Some(2), vec![0], vec![]),
+            Error::<Test>::InvalidTokenId
+        );
+    })
+}
+
+#[test]
+fn mint_burn_and_transfer_check_the_caller() {
+    new_test_ext().execute_with(|| {
+        setup_tao();
+
+        assert_noop!(
+            TokenModule::mint(Origin::signed(BOB), BOB, 0, 1, 100),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            TokenModule::batch_mint(Origin::signed(BOB), BOB, 0, vec![1], vec![100]),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(TokenModule::mint(Origin::signed(ALICE), BOB, 0, 1, 100));
+
+        assert_noop!(
+            TokenModule::transfer_from(Origin::signed(ALICE), BOB, ALICE, 0, 1, 10),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            TokenModule::batch_transfer_from(Origin::signed(ALICE), BOB, ALICE, 0, vec![1], vec![10]),
+            Error::<Test>::NoPermission
+        );
+        // the holder is not the tao owner
+        assert_noop!(
+            TokenModule::burn(Origin::signed(BOB), BOB, 0, 1, 10),
+            Error::<Test>::NoPermission
+        );
+        // the tao owner is not approved by the holder
+        assert_noop!(
+            TokenModule::batch_burn(Origin::signed(ALICE), BOB, 0, vec![1], vec![10]),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(TokenModule::set_approval_for_all(Origin::signed(BOB), ALICE, true));
+        assert_ok!(TokenModule::transfer_from(Origin::signed(ALICE), BOB, ALICE, 0, 1, 10));
+        assert_ok!(TokenModule::burn(Origin::signed(ALICE), BOB, 0, 1, 10));
+        assert_eq!(TokenModule::balance_of(&ALICE, 0, 1), 10);
+        assert_eq!(TokenModule::balance_of(&BOB, 0, 1), 80);
     })
 }
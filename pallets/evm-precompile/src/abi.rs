@@ -0,0 +1,204 @@
+//! Minimal Solidity ABI helpers shared by the SGC precompiles.
+
+use evm::ExitError;
+use sp_core::{H160, H256, U256};
+use sp_std::{convert::TryInto, prelude::*};
+use primitives::Balance;
+
+/// Reads a call in Solidity ABI: a 4 byte selector followed by 32 byte words.
+pub struct EvmDataReader<'a> {
+    input: &'a [u8],
+    cursor: usize,
+}
+
+impl<'a> EvmDataReader<'a> {
+    /// Split the selector from the arguments of a call.
+    pub fn new_with_selector(input: &'a [u8]) -> Result<(u32, Self), ExitError> {
+        if input.len() < 4 {
+            return Err(ExitError::Other("input too short to contain a selector".into()));
+        }
+
+        let selector = u32::from_be_bytes(input[0..4].try_into().map_err(|_| ExitError::Other("invalid selector".into()))?);
+
+        Ok((selector, Self::new(&input[4..])))
+    }
+
+    pub fn new(input: &'a [u8]) -> Self {
+        Self { input, cursor: 0 }
+    }
+
+    fn read_word(&mut self) -> Result<&'a [u8], ExitError> {
+        let end = self.cursor.checked_add(32).ok_or(ExitError::Other("input overflow".into()))?;
+        let word = self.input.get(self.cursor..end).ok_or(ExitError::Other("input too short".into()))?;
+        self.cursor = end;
+        Ok(word)
+    }
+
+    pub fn read_u256(&mut self) -> Result<U256, ExitError> {
+        Ok(U256::from_big_endian(self.read_word()?))
+    }
+
+    pub fn read_balance(&mut self) -> Result<Balance, ExitError> {
+        let value = self.read_u256()?;
+        if value > U256::from(Balance::max_value()) {
+            return Err(ExitError::Other("value does not fit in a balance".into()));
+        }
+        Ok(value.low_u128())
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, ExitError> {
+        let value = self.read_u256()?;
+        if value > U256::from(u64::max_value()) {
+            return Err(ExitError::Other("value does not fit in a u64".into()));
+        }
+        Ok(value.low_u64())
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, ExitError> {
+        let value = self.read_u256()?;
+        if value > U256::from(u32::max_value()) {
+            return Err(ExitError::Other("value does not fit in a u32".into()));
+        }
+        Ok(value.low_u32())
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, ExitError> {
+        match self.read_u256()? {
+            v if v.is_zero() => Ok(false),
+            v if v == U256::one() => Ok(true),
+            _ => Err(ExitError::Other("invalid bool".into())),
+        }
+    }
+
+    pub fn read_bytes32(&mut self) -> Result<H256, ExitError> {
+        Ok(H256::from_slice(self.read_word()?))
+    }
+
+    pub fn read_address(&mut self) -> Result<H160, ExitError> {
+        let word = self.read_word()?;
+        if word[0..12].iter().any(|b| *b != 0) {
+            return Err(ExitError::Other("invalid address".into()));
+        }
+        Ok(H160::from_slice(&word[12..32]))
+    }
+
+    /// Follow the offset in the next word to the tail of a dynamic argument.
+    fn read_tail(&mut self) -> Result<(usize, Self), ExitError> {
+        let offset: usize = self.read_u256()?
+            .try_into()
+            .map_err(|_| ExitError::Other("invalid offset".into()))?;
+        let mut tail = Self { input: self.input, cursor: offset };
+        let len: usize = tail.read_u256()?
+            .try_into()
+            .map_err(|_| ExitError::Other("invalid length".into()))?;
+        Ok((len, tail))
+    }
+
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, ExitError> {
+        let (len, tail) = self.read_tail()?;
+        let end = tail.cursor.checked_add(len).ok_or(ExitError::Other("input overflow".into()))?;
+        tail.input
+            .get(tail.cursor..end)
+            .map(|bytes| bytes.to_vec())
+            .ok_or(ExitError::Other("input too short".into()))
+    }
+
+    pub fn read_address_array(&mut self) -> Result<Vec<H160>, ExitError> {
+        let (len, mut tail) = self.read_tail()?;
+        (0..len).map(|_| tail.read_address()).collect()
+    }
+
+    pub fn read_u256_array(&mut self) -> Result<Vec<U256>, ExitError> {
+        let (len, mut tail) = self.read_tail()?;
+        (0..len).map(|_| tail.read_u256()).collect()
+    }
+
+    pub fn read_u64_array(&mut self) -> Result<Vec<u64>, ExitError> {
+        let (len, mut tail) = self.read_tail()?;
+        (0..len).map(|_| tail.read_u64()).collect()
+    }
+
+    pub fn read_balance_array(&mut self) -> Result<Vec<Balance>, ExitError> {
+        let (len, mut tail) = self.read_tail()?;
+        (0..len).map(|_| tail.read_balance()).collect()
+    }
+}
+
+/// Writes return data in Solidity ABI, placing dynamic values after the static head.
+#[derive(Default)]
+pub struct EvmDataWriter {
+    head: Vec<u8>,
+    tails: Vec<(usize, Vec<u8>)>,
+}
+
+impl EvmDataWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write_u256(mut self, value: U256) -> Self {
+        let mut word = [0u8; 32];
+        value.to_big_endian(&mut word);
+        self.head.extend_from_slice(&word);
+        self
+    }
+
+    pub fn write_balance(self, value: Balance) -> Self {
+        self.write_u256(U256::from(value))
+    }
+
+    pub fn write_bool(self, value: bool) -> Self {
+        self.write_u256(if value { U256::one() } else { U256::zero() })
+    }
+
+    pub fn write_address(self, value: H160) -> Self {
+        self.write_u256(U256::from_big_endian(value.as_bytes()))
+    }
+
+    /// Write `bytes` or `string`.
+    pub fn write_bytes(self, value: &[u8]) -> Self {
+        let mut tail = EvmDataWriter::new().write_u256(U256::from(value.len())).head;
+        tail.extend_from_slice(value);
+        tail.resize((tail.len() + 31) / 32 * 32, 0);
+        self.write_tail(tail)
+    }
+
+    pub fn write_balance_array(self, values: &[Balance]) -> Self {
+        let tail = values
+            .iter()
+            .fold(EvmDataWriter::new().write_u256(U256::from(values.len())), |writer, v| writer.write_balance(*v))
+            .head;
+        self.write_tail(tail)
+    }
+
+    pub fn write_u256_array(self, values: &[U256]) -> Self {
+        let tail = values
+            .iter()
+            .fold(EvmDataWriter::new().write_u256(U256::from(values.len())), |writer, v| writer.write_u256(*v))
+            .head;
+        self.write_tail(tail)
+    }
+
+    fn write_tail(mut self, tail: Vec<u8>) -> Self {
+        self.tails.push((self.head.len(), tail));
+        // placeholder for the offset, filled in by `build`
+        self.head.extend_from_slice(&[0u8; 32]);
+        self
+    }
+
+    pub fn build(self) -> Vec<u8> {
+        let mut output = self.head;
+        for (offset_position, tail) in self.tails {
+            let mut offset = [0u8; 32];
+            U256::from(output.len()).to_big_endian(&mut offset);
+            output[offset_position..offset_position + 32].copy_from_slice(&offset);
+            output.extend_from_slice(&tail);
+        }
+        output
+    }
+}
+
+/// Left pad an address to a log topic.
+pub fn address_topic(address: H160) -> H256 {
+    H256::from(address)
+}
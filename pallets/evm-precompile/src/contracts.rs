@@ -0,0 +1,74 @@
+use codec::Decode;
+use fp_evm::Precompile;
+use evm::{ExitSucceed, ExitError, Context};
+use frame_support::{
+    storage::{with_transaction, TransactionOutcome},
+    traits::Get,
+    weights::Weight,
+};
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use primitives::bridge;
+use sp_runtime::traits::Zero;
+use sp_std::{marker::PhantomData, prelude::*, result};
+use crate::{
+    Config,
+    abi::{EvmDataReader, EvmDataWriter},
+};
+
+// call(bytes32,bytes)
+const SELECTOR_CALL: u32 = 0x3ae7af08;
+
+/// Calls ink! contracts of `pallet_contracts` as the caller.
+///
+/// The gas left to the precompile becomes the weight limit of the call, converted by `GasWeightMapping`,
+/// and the weight it consumed is charged back as gas. No value is transferred.
+pub struct ContractsPrecompile<T: Config> {
+    _marker: PhantomData<T>,
+}
+
+impl<T> Precompile for ContractsPrecompile<T>
+where
+    T: pallet_evm::Config + Config,
+{
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+    ) -> result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        let (selector, mut input) = EvmDataReader::new_with_selector(input)?;
+
+        match selector {
+            SELECTOR_CALL => {
+                let dest = input.read_bytes32()?;
+                let data = input.read_bytes()?;
+                let dest = T::AccountId::decode(&mut dest.as_bytes())
+                    .map_err(|_| ExitError::Other("invalid contract address".into()))?;
+                let origin = T::AddressMapping::into_account_id(context.caller);
+                let gas_limit = target_gas
+                    .map(T::GasWeightMapping::gas_to_weight)
+                    .unwrap_or(Weight::max_value());
+
+                let result = bridge::enter(T::MaxBridgeDepth::get(), || {
+                    // A trapped or reverted contract keeps its writes, so discard them
+                    with_transaction(|| {
+                        let result = pallet_contracts::Module::<T>::bare_call(origin, dest, Zero::zero(), gas_limit, data);
+                        match result.exec_result {
+                            Ok(ref value) if value.is_success() => TransactionOutcome::Commit(result),
+                            _ => TransactionOutcome::Rollback(result),
+                        }
+                    })
+                }).ok_or(ExitError::Other(bridge::DEPTH_EXCEEDED.into()))?;
+
+                let gas_used = T::GasWeightMapping::weight_to_gas(result.gas_consumed);
+                match result.exec_result {
+                    Ok(value) if value.is_success() => {
+                        Ok((ExitSucceed::Returned, EvmDataWriter::new().write_bytes(&value.data).build(), gas_used))
+                    }
+                    Ok(_) => Err(ExitError::Other("contract reverted".into())),
+                    Err(e) => Err(ExitError::Other(Into::<&str>::into(e.error).into())),
+                }
+            }
+            _ => Err(ExitError::Other("unknown selector".into())),
+        }
+    }
+}
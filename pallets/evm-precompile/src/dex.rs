@@ -0,0 +1,188 @@
+use fp_evm::Precompile;
+use evm::{ExitSucceed, ExitError, Context};
+use frame_support::{
+    storage::{with_transaction, TransactionOutcome},
+    weights::Weight,
+};
+use pallet_evm::AddressMapping;
+use sp_runtime::DispatchError;
+use sp_std::{marker::PhantomData, prelude::*, result};
+use alloc::format;
+use crate::{
+    Config, gas_cost,
+    abi::{EvmDataReader, EvmDataWriter},
+};
+
+// buyTokens(uint32,uint64[],uint256[],uint256[],address,uint32)
+const SELECTOR_BUY_TOKENS: u32 = 0x25c3d9c8;
+// sellTokens(uint32,uint64[],uint256[],uint256[],address,uint32)
+const SELECTOR_SELL_TOKENS: u32 = 0xc46c553d;
+// addLiquidity(uint32,address,uint64[],uint256[],uint256[],uint32)
+const SELECTOR_ADD_LIQUIDITY: u32 = 0x42b9511c;
+// removeLiquidity(uint32,address,uint64[],uint256[],uint256[],uint256[],uint32)
+const SELECTOR_REMOVE_LIQUIDITY: u32 = 0xe4197a23;
+// getBuyPrices(uint32,uint64[],uint256[])
+const SELECTOR_GET_BUY_PRICES: u32 = 0x512ae67e;
+// getSellPrices(uint32,uint64[],uint256[])
+const SELECTOR_GET_SELL_PRICES: u32 = 0xfcbd6130;
+
+/// Swaps and liquidity of `pallet_dex` for the caller.
+///
+/// Each swap and liquidity function ends with a `uint32` deadline block, 0 for none.
+/// A max or min limit exceeded reverts with `SlippageExceeded(i)`, `i` the index of its token id.
+pub struct DexPrecompile<T: Config> {
+    _marker: PhantomData<T>,
+}
+
+impl<T> Precompile for DexPrecompile<T>
+where
+    T: pallet_evm::Config + Config,
+{
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+    ) -> result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        let (selector, mut input) = EvmDataReader::new_with_selector(input)?;
+        let who = T::AddressMapping::into_account_id(context.caller);
+
+        let (output, reads, writes) = match selector {
+            SELECTOR_BUY_TOKENS => {
+                let exchange_id = input.read_u32()?;
+                let token_ids = Self::read_token_ids(&mut input)?;
+                let token_amounts_out = input.read_balance_array()?;
+                let max_currencys = input.read_balance_array()?;
+                let to = T::AddressMapping::into_account_id(input.read_address()?);
+                let deadline = Self::read_deadline(&mut input)?;
+                let (reads, writes) = Self::swap_cost(token_ids.len());
+                Self::check_gas(target_gas, reads, writes)?;
+
+                Self::transactional(|| {
+                    pallet_dex::Module::<T>::do_buy_tokens(&who, exchange_id, token_ids, token_amounts_out, max_currencys, &to, deadline)
+                })?;
+                (Vec::new(), reads, writes)
+            }
+            SELECTOR_SELL_TOKENS => {
+                let exchange_id = input.read_u32()?;
+                let token_ids = Self::read_token_ids(&mut input)?;
+                let token_amounts_in = input.read_balance_array()?;
+                let min_currencys = input.read_balance_array()?;
+                let to = T::AddressMapping::into_account_id(input.read_address()?);
+                let deadline = Self::read_deadline(&mut input)?;
+                let (reads, writes) = Self::swap_cost(token_ids.len());
+                Self::check_gas(target_gas, reads, writes)?;
+
+                Self::transactional(|| {
+                    pallet_dex::Module::<T>::do_sell_tokens(&who, exchange_id, token_ids, token_amounts_in, min_currencys, &to, deadline)
+                })?;
+                (Vec::new(), reads, writes)
+            }
+            SELECTOR_ADD_LIQUIDITY => {
+                let exchange_id = input.read_u32()?;
+                let to = T::AddressMapping::into_account_id(input.read_address()?);
+                let token_ids = Self::read_token_ids(&mut input)?;
+                let token_amounts = input.read_balance_array()?;
+                let max_currencys = input.read_balance_array()?;
+                let deadline = Self::read_deadline(&mut input)?;
+                let (reads, writes) = Self::liquidity_cost(token_ids.len());
+                Self::check_gas(target_gas, reads, writes)?;
+
+                Self::transactional(|| {
+                    pallet_dex::Module::<T>::do_add_liquidity(&who, exchange_id, &to, token_ids, token_amounts, max_currencys, deadline)
+                })?;
+                (Vec::new(), reads, writes)
+            }
+            SELECTOR_REMOVE_LIQUIDITY => {
+                let exchange_id = input.read_u32()?;
+                let to = T::AddressMapping::into_account_id(input.read_address()?);
+                let token_ids = Self::read_token_ids(&mut input)?;
+                let liquidities = input.read_balance_array()?;
+                let min_currencys = input.read_balance_array()?;
+                let min_tokens = input.read_balance_array()?;
+                let deadline = Self::read_deadline(&mut input)?;
+                let (reads, writes) = Self::liquidity_cost(token_ids.len());
+                Self::check_gas(target_gas, reads, writes)?;
+
+                Self::transactional(|| {
+                    pallet_dex::Module::<T>::do_remove_liquidity(&who, exchange_id, &to, token_ids, liquidities, min_currencys, min_tokens, deadline)
+                })?;
+                (Vec::new(), reads, writes)
+            }
+            SELECTOR_GET_BUY_PRICES => {
+                let exchange_id = input.read_u32()?;
+                let token_ids = Self::read_token_ids(&mut input)?;
+                let token_amounts_out = input.read_balance_array()?;
+                let reads = 1 + 2 * token_ids.len() as Weight;
+                Self::check_gas(target_gas, reads, 0)?;
+
+                let prices = pallet_dex::Module::<T>::get_buy_prices(exchange_id, token_ids, token_amounts_out)
+                    .map_err(Self::dispatch_error)?;
+                (EvmDataWriter::new().write_balance_array(&prices).build(), reads, 0)
+            }
+            SELECTOR_GET_SELL_PRICES => {
+                let exchange_id = input.read_u32()?;
+                let token_ids = Self::read_token_ids(&mut input)?;
+                let token_amounts_in = input.read_balance_array()?;
+                let reads = 1 + 2 * token_ids.len() as Weight;
+                Self::check_gas(target_gas, reads, 0)?;
+
+                let prices = pallet_dex::Module::<T>::get_sell_prices(exchange_id, token_ids, token_amounts_in)
+                    .map_err(Self::dispatch_error)?;
+                (EvmDataWriter::new().write_balance_array(&prices).build(), reads, 0)
+            }
+            _ => return Err(ExitError::Other("unknown selector".into())),
+        };
+
+        Ok((ExitSucceed::Returned, output, gas_cost::<T>(reads, writes)))
+    }
+}
+
+impl<T: Config> DexPrecompile<T> {
+    fn read_token_ids(input: &mut EvmDataReader) -> result::Result<Vec<T::TokenId>, ExitError> {
+        Ok(input.read_u64_array()?.into_iter().map(T::TokenId::from).collect())
+    }
+
+    // 0 is no deadline
+    fn read_deadline(input: &mut EvmDataReader) -> result::Result<Option<T::BlockNumber>, ExitError> {
+        Ok(match input.read_u32()? {
+            0 => None,
+            deadline => Some(deadline.into()),
+        })
+    }
+
+    // exchange, currency balances and per token reserves, supplies and balances
+    fn swap_cost(n: usize) -> (Weight, Weight) {
+        let n = n as Weight;
+        (3 + 3 * n, 2 + 3 * n)
+    }
+
+    // as a swap, plus liquidity token supplies and balances
+    fn liquidity_cost(n: usize) -> (Weight, Weight) {
+        let n = n as Weight;
+        (3 + 5 * n, 2 + 5 * n)
+    }
+
+    fn check_gas(target_gas: Option<u64>, reads: Weight, writes: Weight) -> result::Result<(), ExitError> {
+        match target_gas {
+            Some(gas_limit) if gas_cost::<T>(reads, writes) > gas_limit => Err(ExitError::OutOfGas),
+            _ => Ok(()),
+        }
+    }
+
+    fn dispatch_error(e: DispatchError) -> ExitError {
+        ExitError::Other(Into::<&str>::into(e).into())
+    }
+
+    /// Run a `do_*` function, discarding its writes if it fails halfway.
+    ///
+    /// `SlippageExceeded` reverts as `SlippageExceeded(i)`, naming the index of the token id.
+    fn transactional(f: impl FnOnce() -> Result<(), DispatchError>) -> result::Result<(), ExitError> {
+        with_transaction(|| match pallet_dex::with_slippage_index(f) {
+            (Ok(()), _) => TransactionOutcome::Commit(Ok(())),
+            (Err(e), Some(index)) if e == pallet_dex::Error::<T>::SlippageExceeded.into() => {
+                TransactionOutcome::Rollback(Err(ExitError::Other(format!("SlippageExceeded({})", index).into())))
+            }
+            (Err(e), _) => TransactionOutcome::Rollback(Err(Self::dispatch_error(e))),
+        })
+    }
+}
@@ -0,0 +1,57 @@
+use codec::Decode;
+use fp_evm::Precompile;
+use evm::{ExitSucceed, ExitError, Context};
+use frame_support::{
+    dispatch::{Dispatchable, GetDispatchInfo},
+    storage::{with_transaction, TransactionOutcome},
+    traits::Filter,
+};
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use sp_std::{marker::PhantomData, prelude::*, result};
+use crate::Config;
+
+/// Dispatches a SCALE encoded runtime call, signed by the caller.
+///
+/// Only calls allowed by `Config::CallFilter` are dispatched, and gas is charged from their weight.
+pub struct DispatchPrecompile<T: Config> {
+    _marker: PhantomData<T>,
+}
+
+impl<T> Precompile for DispatchPrecompile<T>
+where
+    T: pallet_evm::Config + Config,
+{
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+    ) -> result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        let call = <T as Config>::Call::decode(&mut &input[..])
+            .map_err(|_| ExitError::Other("decode call failed".into()))?;
+
+        if !T::CallFilter::filter(&call) {
+            return Err(ExitError::Other("call not allowed".into()));
+        }
+
+        let info = call.get_dispatch_info();
+        let gas_limit = T::GasWeightMapping::weight_to_gas(info.weight);
+        if let Some(target_gas) = target_gas {
+            if gas_limit > target_gas {
+                return Err(ExitError::OutOfGas);
+            }
+        }
+
+        let origin = T::AddressMapping::into_account_id(context.caller);
+
+        // Dispatched calls are not transactional, so discard the writes of a failed one
+        with_transaction(|| match call.dispatch(frame_system::RawOrigin::Signed(origin).into()) {
+            Ok(post_info) => {
+                let gas_used = post_info.actual_weight
+                    .map(T::GasWeightMapping::weight_to_gas)
+                    .unwrap_or(gas_limit);
+                TransactionOutcome::Commit(Ok((ExitSucceed::Stopped, Vec::new(), gas_used)))
+            }
+            Err(e) => TransactionOutcome::Rollback(Err(ExitError::Other(Into::<&str>::into(e.error).into()))),
+        })
+    }
+}
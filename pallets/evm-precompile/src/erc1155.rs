@@ -1,86 +1,243 @@
 use fp_evm::Precompile;
 use evm::{ExitSucceed, ExitError, Context};
-// use pallet_evm::{AddressMapping, GasWeightMapping};
+use frame_support::{
+    storage::{with_transaction, TransactionOutcome},
+    weights::Weight,
+};
+use pallet_evm::AddressMapping;
+use sp_core::{H160, H256, U256};
 use sp_std::{marker::PhantomData, prelude::*, result};
-use crate::Config;
+use primitives::Balance;
+use crate::{
+    Config, deposit_log, gas_cost,
+    abi::{EvmDataReader, EvmDataWriter, address_topic},
+};
 
+// balanceOf(address,uint256)
+const SELECTOR_BALANCE_OF: u32 = 0x00fdd58e;
+// balanceOfBatch(address[],uint256[])
+const SELECTOR_BALANCE_OF_BATCH: u32 = 0x4e1273f4;
+// setApprovalForAll(address,bool)
+const SELECTOR_SET_APPROVAL_FOR_ALL: u32 = 0xa22cb465;
+// isApprovedForAll(address,address)
+const SELECTOR_IS_APPROVED_FOR_ALL: u32 = 0xe985e9c5;
+// safeTransferFrom(address,address,uint256,uint256,bytes)
+const SELECTOR_SAFE_TRANSFER_FROM: u32 = 0xf242432a;
+// safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)
+const SELECTOR_SAFE_BATCH_TRANSFER_FROM: u32 = 0x2eb2c2d6;
+// uri(uint256)
+const SELECTOR_URI: u32 = 0x0e89341c;
+
+// TransferSingle(address,address,address,uint256,uint256)
+const TRANSFER_SINGLE_TOPIC: [u8; 32] = hex_literal::hex!("c3d58168c5ae7397731d063d5bbf3d657854427343f4c083240f7aacaa2d0f62");
+// TransferBatch(address,address,address,uint256[],uint256[])
+const TRANSFER_BATCH_TOPIC: [u8; 32] = hex_literal::hex!("4a39dc06d4c0dbc64b70af90fd698a233a518aa5d07e595d983b8c0526c8f7fb");
+// ApprovalForAll(address,address,bool)
+const APPROVAL_FOR_ALL_TOPIC: [u8; 32] = hex_literal::hex!("17307eab39ab6107e8899845ad3d59bd9653f200f220920489ca2b5937696c31");
+
+/// The ERC-1155 interface of all SGC items.
+///
+/// An ERC-1155 `id` carries both halves of an item, `tao_id << 64 | token_id`.
+/// Receiver hooks (`onERC1155Received`) are not called, as precompiles cannot call back into the EVM.
 pub struct Erc1155Precompile<T: Config> {
     _marker: PhantomData<T>,
 }
 
-
 impl<T> Precompile for Erc1155Precompile<T>
 where
     T: pallet_evm::Config + Config,
 {
     fn execute(
         input: &[u8],
-        _target_gas: Option<u64>,
-        _context: &Context,
+        target_gas: Option<u64>,
+        context: &Context,
     ) -> result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        let (selector, mut input) = EvmDataReader::new_with_selector(input)?;
+
+        let (output, reads, writes) = match selector {
+            SELECTOR_BALANCE_OF => {
+                let owner = input.read_address()?;
+                let (tao_id, token_id) = Self::decode_id(input.read_u256()?)?;
+                Self::check_gas(target_gas, 1, 0)?;
+
+                let balance = pallet_erc1155::Module::<T>::balance_of(
+                    &T::AddressMapping::into_account_id(owner),
+                    tao_id,
+                    token_id,
+                );
+                (EvmDataWriter::new().write_balance(balance).build(), 1, 0)
+            }
+            SELECTOR_BALANCE_OF_BATCH => {
+                let owners = input.read_address_array()?;
+                let ids = input.read_u256_array()?;
+                if owners.len() != ids.len() {
+                    return Err(ExitError::Other("owners and ids length mismatch".into()));
+                }
+                let n = owners.len() as Weight;
+                Self::check_gas(target_gas, n, 0)?;
 
-        log::debug!(target: "evm", "input: {:?}", input);
-        
-        log::info!("input: {:?}", input);
+                let balances = owners
+                    .into_iter()
+                    .zip(ids.into_iter())
+                    .map(|(owner, id)| {
+                        let (tao_id, token_id) = Self::decode_id(id)?;
+                        Ok(pallet_erc1155::Module::<T>::balance_of(
+                            &T::AddressMapping::into_account_id(owner),
+                            tao_id,
+                            token_id,
+                        ))
+                    })
+                    .collect::<result::Result<Vec<Balance>, ExitError>>()?;
+                (EvmDataWriter::new().write_balance_array(&balances).build(), n, 0)
+            }
+            SELECTOR_SET_APPROVAL_FOR_ALL => {
+                let operator = input.read_address()?;
+                let approved = input.read_bool()?;
+                Self::check_gas(target_gas, 0, 1)?;
 
-        let owner = T::AccountId::default();
-        let tao_id = T::TaoId::from(2u64);
-        let token_id = T::TokenId::from(1u64);
-        let balance = pallet_erc1155::Module::<T>::balance_of(&owner, tao_id, token_id);
-        log::info!("balance: {:?}", balance);
+                pallet_erc1155::Module::<T>::do_set_approval_for_all(
+                    &T::AddressMapping::into_account_id(context.caller),
+                    &T::AddressMapping::into_account_id(operator),
+                    approved,
+                ).map_err(|e| ExitError::Other(Into::<&str>::into(e).into()))?;
 
-        Ok((ExitSucceed::Returned, [].to_vec(), 0))
+                deposit_log::<T>(
+                    context.address,
+                    vec![
+                        H256::from(APPROVAL_FOR_ALL_TOPIC),
+                        address_topic(context.caller),
+                        address_topic(operator),
+                    ],
+                    EvmDataWriter::new().write_bool(approved).build(),
+                );
+                (Vec::new(), 0, 1)
+            }
+            SELECTOR_IS_APPROVED_FOR_ALL => {
+                let owner = input.read_address()?;
+                let operator = input.read_address()?;
+                Self::check_gas(target_gas, 1, 0)?;
 
-        // let input = Input::<Action, T::AccountId, AddressMapping<T::AccountId>>::new(input);
+                let approved = pallet_erc1155::Module::<T>::is_approved_for_all(
+                    &T::AddressMapping::into_account_id(owner),
+                    &T::AddressMapping::into_account_id(operator),
+                );
+                (EvmDataWriter::new().write_bool(approved).build(), 1, 0)
+            }
+            SELECTOR_SAFE_TRANSFER_FROM => {
+                let from = input.read_address()?;
+                let to = input.read_address()?;
+                let id = input.read_u256()?;
+                let amount = input.read_balance()?;
+                let _data = input.read_bytes()?;
+                Self::check_gas(target_gas, 3, 2)?;
 
-        // let action = input.action()?;
+                Self::transfer(context.caller, from, to, vec![id], vec![amount])?;
 
-        // match action {
-        // 	Action::QueryBalance => {
-        //         log::debug!(target: "evm", "into QueryBalance");
-        // 		Ok((ExitSucceed::Returned, [].to_vec(), 0))
-        // 	}
-        // 	Action::TransferFrom => {
-        //         log::debug!(target: "evm", "into TransferFrom");
-        //         Ok((ExitSucceed::Returned, [].to_vec(), 0))
-        // 	}
-        // }
+                deposit_log::<T>(
+                    context.address,
+                    vec![
+                        H256::from(TRANSFER_SINGLE_TOPIC),
+                        address_topic(context.caller),
+                        address_topic(from),
+                        address_topic(to),
+                    ],
+                    EvmDataWriter::new().write_u256(id).write_balance(amount).build(),
+                );
+                (Vec::new(), 3, 2)
+            }
+            SELECTOR_SAFE_BATCH_TRANSFER_FROM => {
+                let from = input.read_address()?;
+                let to = input.read_address()?;
+                let ids = input.read_u256_array()?;
+                let amounts = input.read_balance_array()?;
+                let _data = input.read_bytes()?;
+                if ids.len() != amounts.len() {
+                    return Err(ExitError::Other("ids and amounts length mismatch".into()));
+                }
+                let n = ids.len() as Weight;
+                Self::check_gas(target_gas, 1 + 2 * n, 2 * n)?;
+
+                Self::transfer(context.caller, from, to, ids.clone(), amounts.clone())?;
+
+                deposit_log::<T>(
+                    context.address,
+                    vec![
+                        H256::from(TRANSFER_BATCH_TOPIC),
+                        address_topic(context.caller),
+                        address_topic(from),
+                        address_topic(to),
+                    ],
+                    EvmDataWriter::new().write_u256_array(&ids).write_balance_array(&amounts).build(),
+                );
+                (Vec::new(), 1 + 2 * n, 2 * n)
+            }
+            SELECTOR_URI => {
+                let (tao_id, token_id) = Self::decode_id(input.read_u256()?)?;
+                Self::check_gas(target_gas, 1, 0)?;
+
+                let uri = pallet_erc1155::Module::<T>::tokens(tao_id, token_id)
+                    .map(|token| token.uri)
+                    .unwrap_or_default();
+                (EvmDataWriter::new().write_bytes(&uri).build(), 1, 0)
+            }
+            _ => return Err(ExitError::Other("unknown selector".into())),
+        };
+
+        Ok((ExitSucceed::Returned, output, gas_cost::<T>(reads, writes)))
     }
 }
 
+impl<T: Config> Erc1155Precompile<T> {
+    /// Split an ERC-1155 `id` into its tao and token.
+    pub fn decode_id(id: U256) -> result::Result<(T::TaoId, T::TokenId), ExitError> {
+        if id.bits() > 128 {
+            return Err(ExitError::Other("invalid id".into()));
+        }
 
-// enum Action {
-//     QueryBalance,
-//     TransferFrom,
-// }
-
-// impl TryFrom<u8> for Action {
-//     type Error = ();
-
-//     fn try_from(value: u8) -> Result<Self, Self::Error> {
-//         match value {
-//             0 => Ok(Action::QueryBalance),
-//             1 => Ok(Action::TransferFrom),
-//             _ => Err(()),
-//         }
-//     }
-// }
-
-// pub struct Input<'a, Action, AccountId, AddressMapping> {
-// 	content: &'a [u8],
-// 	_marker: PhantomData<(Action, AccountId, AddressMapping)>,
-// }
-// impl<'a, Action, AccountId, AddressMapping> Input<'a, Action, AccountId, AddressMapping> {
-// 	pub fn new(content: &'a [u8]) -> Self {
-// 		Self {
-// 			content,
-// 			_marker: PhantomData,
-// 		}
-// 	}
-// }
-
-// fn vec_u8_from_balance(b: u128) -> Vec<u8> {
-// 	let mut be_bytes = [0u8; 32];
-// 	U256::from(b).to_big_endian(&mut be_bytes[..]);
-// 	be_bytes.to_vec()
-// }
+        let tao_id = T::TaoId::from((id >> 64).low_u64());
+        let token_id = T::TokenId::from(id.low_u64());
+        Ok((tao_id, token_id))
+    }
+
+    /// The ERC-1155 `id` of a token.
+    pub fn encode_id(tao_id: T::TaoId, token_id: T::TokenId) -> U256 {
+        (U256::from(tao_id.into()) << 64) | U256::from(token_id.into())
+    }
+
+    fn check_gas(target_gas: Option<u64>, reads: Weight, writes: Weight) -> result::Result<(), ExitError> {
+        match target_gas {
+            Some(gas_limit) if gas_cost::<T>(reads, writes) > gas_limit => Err(ExitError::OutOfGas),
+            _ => Ok(()),
+        }
+    }
+
+    /// Move `amounts` of `ids` on behalf of `operator`, all or nothing.
+    fn transfer(
+        operator: H160,
+        from: H160,
+        to: H160,
+        ids: Vec<U256>,
+        amounts: Vec<Balance>,
+    ) -> result::Result<(), ExitError> {
+        let operator = T::AddressMapping::into_account_id(operator);
+        let from = T::AddressMapping::into_account_id(from);
+        let to = T::AddressMapping::into_account_id(to);
+
+        if !pallet_erc1155::Module::<T>::approved_or_owner(&from, &operator) {
+            return Err(ExitError::Other("caller is not owner nor approved".into()));
+        }
+
+        with_transaction(|| {
+            for (id, amount) in ids.into_iter().zip(amounts.into_iter()) {
+                let result = Self::decode_id(id).and_then(|(tao_id, token_id)| {
+                    pallet_erc1155::Module::<T>::do_transfer_from(&from, &to, tao_id, token_id, amount)
+                        .map_err(|e| ExitError::Other(Into::<&str>::into(e).into()))
+                });
+                if let Err(e) = result {
+                    return TransactionOutcome::Rollback(Err(e));
+                }
+            }
+            TransactionOutcome::Commit(Ok(()))
+        })
+    }
+}
@@ -0,0 +1,196 @@
+use evm::{ExitSucceed, ExitError, Context};
+use frame_support::storage::{with_transaction, TransactionOutcome};
+use orml_traits::MultiCurrency;
+use pallet_evm::AddressMapping;
+use sp_core::{H160, H256};
+use sp_std::{marker::PhantomData, prelude::*, result};
+use primitives::{Balance, CurrencyId};
+use crate::{
+    Config, deposit_log, gas_cost,
+    abi::{EvmDataReader, EvmDataWriter, address_topic},
+};
+
+// name()
+const SELECTOR_NAME: u32 = 0x06fdde03;
+// symbol()
+const SELECTOR_SYMBOL: u32 = 0x95d89b41;
+// decimals()
+const SELECTOR_DECIMALS: u32 = 0x313ce567;
+// totalSupply()
+const SELECTOR_TOTAL_SUPPLY: u32 = 0x18160ddd;
+// balanceOf(address)
+const SELECTOR_BALANCE_OF: u32 = 0x70a08231;
+// transfer(address,uint256)
+const SELECTOR_TRANSFER: u32 = 0xa9059cbb;
+// approve(address,uint256)
+const SELECTOR_APPROVE: u32 = 0x095ea7b3;
+// allowance(address,address)
+const SELECTOR_ALLOWANCE: u32 = 0xdd62ed3e;
+// transferFrom(address,address,uint256)
+const SELECTOR_TRANSFER_FROM: u32 = 0x23b872dd;
+
+// Transfer(address,address,uint256)
+const TRANSFER_TOPIC: [u8; 32] = hex_literal::hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+// Approval(address,address,uint256)
+const APPROVAL_TOPIC: [u8; 32] = hex_literal::hex!("8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925");
+
+/// The ERC-20 interface of an orml currency, living at the address of its `CurrencyId`.
+///
+/// Allowances are kept by `pallet_currency_token`, for the accounts of the addresses.
+/// The writes of a call that fails, such as a `transferFrom` over the balance, are discarded with it.
+/// Those of a call that succeeds are not part of the EVM state, so they stay even when the calling
+/// contract reverts afterwards.
+pub struct Erc20Precompile<T: Config> {
+    _marker: PhantomData<T>,
+}
+
+impl<T: Config> Erc20Precompile<T> {
+    pub fn execute(
+        currency_id: CurrencyId,
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+    ) -> result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        let (selector, input) = EvmDataReader::new_with_selector(input)?;
+
+        let (reads, writes) = match selector {
+            SELECTOR_NAME | SELECTOR_SYMBOL | SELECTOR_DECIMALS
+            | SELECTOR_TOTAL_SUPPLY | SELECTOR_BALANCE_OF | SELECTOR_ALLOWANCE => (1, 0),
+            SELECTOR_TRANSFER => (2, 2),
+            SELECTOR_APPROVE => (0, 1),
+            SELECTOR_TRANSFER_FROM => (3, 3),
+            _ => return Err(ExitError::Other("unknown selector".into())),
+        };
+
+        let gas_used = gas_cost::<T>(reads, writes);
+        if let Some(gas_limit) = target_gas {
+            if gas_used > gas_limit {
+                return Err(ExitError::OutOfGas);
+            }
+        }
+
+        let output = with_transaction(|| match Self::call(currency_id, selector, input, context) {
+            Ok(output) => TransactionOutcome::Commit(Ok(output)),
+            Err(e) => TransactionOutcome::Rollback(Err(e)),
+        })?;
+
+        Ok((ExitSucceed::Returned, output, gas_used))
+    }
+
+    fn call(
+        currency_id: CurrencyId,
+        selector: u32,
+        mut input: EvmDataReader,
+        context: &Context,
+    ) -> result::Result<Vec<u8>, ExitError> {
+        let output = match selector {
+            SELECTOR_NAME => {
+                let name = pallet_currency_token::Module::<T>::asset_metadatas(currency_id)
+                    .map(|metadata| metadata.name)
+                    .unwrap_or_default();
+                EvmDataWriter::new().write_bytes(&name).build()
+            }
+            SELECTOR_SYMBOL => {
+                let symbol = pallet_currency_token::Module::<T>::asset_metadatas(currency_id)
+                    .map(|metadata| metadata.symbol)
+                    .unwrap_or_default();
+                EvmDataWriter::new().write_bytes(&symbol).build()
+            }
+            SELECTOR_DECIMALS => {
+                let decimals = pallet_currency_token::Module::<T>::asset_metadatas(currency_id)
+                    .map(|metadata| metadata.decimals)
+                    .unwrap_or_default();
+                EvmDataWriter::new().write_u256(decimals.into()).build()
+            }
+            SELECTOR_TOTAL_SUPPLY => {
+                let total = <T as pallet_currency_token::Config>::Currency::total_issuance(currency_id);
+                EvmDataWriter::new().write_balance(total).build()
+            }
+            SELECTOR_BALANCE_OF => {
+                let owner = T::AddressMapping::into_account_id(input.read_address()?);
+                let balance = <T as pallet_currency_token::Config>::Currency::free_balance(currency_id, &owner);
+                EvmDataWriter::new().write_balance(balance).build()
+            }
+            SELECTOR_TRANSFER => {
+                let to = input.read_address()?;
+                let amount = input.read_balance()?;
+                Self::transfer(currency_id, context, context.caller, to, amount)?;
+                EvmDataWriter::new().write_bool(true).build()
+            }
+            SELECTOR_APPROVE => {
+                let spender = input.read_address()?;
+                let amount = input.read_balance()?;
+                pallet_currency_token::Module::<T>::set_allowance(
+                    currency_id,
+                    &T::AddressMapping::into_account_id(context.caller),
+                    &T::AddressMapping::into_account_id(spender),
+                    amount,
+                );
+                deposit_log::<T>(
+                    context.address,
+                    vec![
+                        H256::from(APPROVAL_TOPIC),
+                        address_topic(context.caller),
+                        address_topic(spender),
+                    ],
+                    EvmDataWriter::new().write_balance(amount).build(),
+                );
+                EvmDataWriter::new().write_bool(true).build()
+            }
+            SELECTOR_ALLOWANCE => {
+                let owner = input.read_address()?;
+                let spender = input.read_address()?;
+                let allowance = pallet_currency_token::Module::<T>::allowance(
+                    currency_id,
+                    &T::AddressMapping::into_account_id(owner),
+                    &T::AddressMapping::into_account_id(spender),
+                );
+                EvmDataWriter::new().write_balance(allowance).build()
+            }
+            SELECTOR_TRANSFER_FROM => {
+                let from = input.read_address()?;
+                let to = input.read_address()?;
+                let amount = input.read_balance()?;
+                if from != context.caller {
+                    let owner = T::AddressMapping::into_account_id(from);
+                    let spender = T::AddressMapping::into_account_id(context.caller);
+                    let remaining = pallet_currency_token::Module::<T>::allowance(currency_id, &owner, &spender)
+                        .checked_sub(amount)
+                        .ok_or(ExitError::Other("insufficient allowance".into()))?;
+                    pallet_currency_token::Module::<T>::set_allowance(currency_id, &owner, &spender, remaining);
+                }
+                Self::transfer(currency_id, context, from, to, amount)?;
+                EvmDataWriter::new().write_bool(true).build()
+            }
+            _ => unreachable!("selector checked above; qed"),
+        };
+
+        Ok(output)
+    }
+
+    fn transfer(
+        currency_id: CurrencyId,
+        context: &Context,
+        from: H160,
+        to: H160,
+        amount: Balance,
+    ) -> result::Result<(), ExitError> {
+        let from_account = T::AddressMapping::into_account_id(from);
+        let to_account = T::AddressMapping::into_account_id(to);
+
+        <T as pallet_currency_token::Config>::Currency::transfer(currency_id, &from_account, &to_account, amount)
+            .map_err(|e| ExitError::Other(Into::<&str>::into(e).into()))?;
+
+        deposit_log::<T>(
+            context.address,
+            vec![
+                H256::from(TRANSFER_TOPIC),
+                address_topic(from),
+                address_topic(to),
+            ],
+            EvmDataWriter::new().write_balance(amount).build(),
+        );
+
+        Ok(())
+    }
+}
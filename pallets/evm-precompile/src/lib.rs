@@ -1,15 +1,49 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+pub mod abi;
+mod contracts;
+mod dex;
+mod dispatch;
 mod erc1155;
+mod erc20;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
 
-use sp_core::H160;
+use sp_core::{H160, H256};
 use evm::{ExitSucceed, ExitError, Context};
+use frame_support::{
+    Parameter,
+    dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+    traits::{Filter, Get},
+    weights::Weight,
+};
+use pallet_evm::{GasWeightMapping, Log};
 use pallet_evm_precompile_simple::{ECRecover, Identity, Ripemd160, Sha256};
 use fp_evm::{PrecompileSet, Precompile};
-use sp_std::{result, marker::PhantomData, prelude::*, str::FromStr};
+use sp_std::{convert::TryFrom, result, marker::PhantomData, prelude::*, str::FromStr};
+use primitives::CurrencyId;
+pub use contracts::ContractsPrecompile;
+pub use dex::DexPrecompile;
+pub use dispatch::DispatchPrecompile;
 pub use erc1155::Erc1155Precompile;
+pub use erc20::Erc20Precompile;
 
-pub trait Config: pallet_evm::Config + pallet_erc1155::Config {}
+pub trait Config: pallet_evm::Config + pallet_dex::Config + pallet_contracts::Config {
+    /// The runtime call dispatched by `DispatchPrecompile`.
+    type Call: Parameter + Dispatchable<Origin = Self::Origin, PostInfo = PostDispatchInfo> + GetDispatchInfo;
+
+    /// The calls EVM contracts are allowed to dispatch.
+    type CallFilter: Filter<<Self as Config>::Call>;
+
+    /// The most calls between ink! and EVM contracts nested in one another, see `primitives::bridge`.
+    type MaxBridgeDepth: Get<u32>;
+}
 
 // pub type SgcPrecompiles<Runtime> = (
 // 	ECRecover,
@@ -40,9 +74,20 @@ impl<T: Config> PrecompileSet for SgcPrecompiles<T> {
     ) -> Option<result::Result<(ExitSucceed, Vec<u8>, u64), ExitError>> {
         EthereumPrecompiles::execute(address, input, target_gas, context).or_else(|| {
             let addr_erc1155 = H160::from_str("0000000000000000000000000000000000000401").unwrap();
+            let addr_dex = H160::from_str("0000000000000000000000000000000000000402").unwrap();
+            let addr_dispatch = H160::from_str("0000000000000000000000000000000000000403").unwrap();
+            let addr_contracts = H160::from_str("0000000000000000000000000000000000000404").unwrap();
 
             if address == addr_erc1155 {
                 Some(Erc1155Precompile::<T>::execute(input, target_gas, context))
+            } else if address == addr_dex {
+                Some(DexPrecompile::<T>::execute(input, target_gas, context))
+            } else if address == addr_dispatch {
+                Some(DispatchPrecompile::<T>::execute(input, target_gas, context))
+            } else if address == addr_contracts {
+                Some(ContractsPrecompile::<T>::execute(input, target_gas, context))
+            } else if let Some(currency_id) = erc20_currency_id(address) {
+                Some(Erc20Precompile::<T>::execute(currency_id, input, target_gas, context))
             } else {
                 None
             }
@@ -50,6 +95,50 @@ impl<T: Config> PrecompileSet for SgcPrecompiles<T> {
     }
 }
 
+/// The leading bytes of every ERC-20 precompile address.
+pub const ERC20_ADDRESS_PREFIX: [u8; 3] = [0xff; 3];
+
+/// The currency whose ERC-20 precompile lives at `address`.
+///
+/// The reserved range is `ERC20_ADDRESS_PREFIX`, then the tag of the encoded `CurrencyId` (its byte 12),
+/// then bytes `16..32` of the encoding, which holds every id as bytes `13..16` are always zero.
+/// Any other address, including the precompiles at `0x0401` onwards, is no currency.
+pub fn erc20_currency_id(address: H160) -> Option<CurrencyId> {
+    let address = address.as_bytes();
+    if !address.starts_with(&ERC20_ADDRESS_PREFIX) {
+        return None;
+    }
+
+    let mut bytes = [0u8; 32];
+    bytes[12] = address[3];
+    bytes[16..].copy_from_slice(&address[4..]);
+    CurrencyId::try_from(bytes).ok()
+}
+
+/// The ERC-20 precompile address of `currency_id`, see `erc20_currency_id`.
+pub fn erc20_address(currency_id: CurrencyId) -> H160 {
+    let bytes: [u8; 32] = currency_id.into();
+    let mut address = [0u8; 20];
+    address[..3].copy_from_slice(&ERC20_ADDRESS_PREFIX);
+    address[3] = bytes[12];
+    address[4..].copy_from_slice(&bytes[16..]);
+    H160(address)
+}
+
+/// The gas of `reads` and `writes` to storage.
+pub(crate) fn gas_cost<T: Config>(reads: Weight, writes: Weight) -> u64 {
+    let weight = <T as frame_system::Config>::DbWeight::get().reads_writes(reads, writes);
+    T::GasWeightMapping::weight_to_gas(weight)
+}
+
+/// Record an EVM log emitted by a precompile.
+///
+/// Precompiles cannot add logs to the transaction receipt, so they are deposited as `pallet_evm` `Log` events.
+pub(crate) fn deposit_log<T: Config>(address: H160, topics: Vec<H256>, data: Vec<u8>) {
+    let event: <T as pallet_evm::Config>::Event = pallet_evm::Event::<T>::Log(Log { address, topics, data }).into();
+    frame_system::Module::<T>::deposit_event(event);
+}
+
 // type PrecompiledCallable = fn(&[u8], Option<u64>, &Context)
 // 	-> result::Result<(ExitSucceed, Vec<u8>, u64), ExitError>;
 
@@ -0,0 +1,280 @@
+use crate as evm_precompile;
+use sp_core::{H160, H256};
+use frame_support::{parameter_types, traits::{Filter, GenesisBuild}, weights::Weight};
+use sp_runtime::{
+    AccountId32, ModuleId, Perbill, Permill,
+    traits::{BlakeTwo256, IdentityLookup, Zero}, testing::Header,
+};
+use pallet_evm::{AddressMapping, EnsureAddressNever, EnsureAddressRoot};
+use frame_system::{self as system, EnsureRoot};
+use orml_traits::parameter_type_with_key;
+use evm::Context;
+use primitives::{Amount, Balance, CurrencyId, TokenSymbol};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the precompiles.
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Module, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+        Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
+        RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Module, Call, Storage},
+        Contracts: pallet_contracts::{Module, Call, Config<T>, Storage, Event<T>},
+        Token: pallet_erc1155::{Module, Call, Storage, Event<T>},
+        Tokens: orml_tokens::{Module, Storage, Event<T>, Config<T>},
+        CurrencyToken: pallet_currency_token::{Module, Call, Storage, Event<T>, Config},
+        Dex: pallet_dex::{Module, Call, Storage, Event<T>},
+        EVM: pallet_evm::{Module, Config, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+    pub BlockWeights: frame_system::limits::BlockWeights =
+        frame_system::limits::BlockWeights::simple_max(2 * 1_000_000_000_000);
+}
+
+impl system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = BlockWeights;
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId32;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u128>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u128 = 1;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = u128;
+    type MaxLocks = ();
+    type Event = Event;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const SignedClaimHandicap: u64 = 2;
+    pub const TombstoneDeposit: u128 = 16;
+    pub const DepositPerContract: u128 = 8 * DepositPerStorageByte::get();
+    pub const DepositPerStorageByte: u128 = 10_000;
+    pub const DepositPerStorageItem: u128 = 10_000;
+    pub RentFraction: Perbill = Perbill::from_rational_approximation(4u32, 10_000u32);
+    pub const SurchargeReward: u128 = 500_000;
+    pub const MaxDepth: u32 = 32;
+    pub const MaxValueSize: u32 = 16 * 1024;
+    pub const DeletionQueueDepth: u32 = 1024;
+    pub const DeletionWeightLimit: Weight = 500_000_000_000;
+    pub const MaxCodeSize: u32 = 256 * 1024;
+}
+
+impl pallet_contracts::Config for Test {
+    type Time = Timestamp;
+    type Randomness = RandomnessCollectiveFlip;
+    type Currency = Balances;
+    type Event = Event;
+    type RentPayment = ();
+    type SignedClaimHandicap = SignedClaimHandicap;
+    type TombstoneDeposit = TombstoneDeposit;
+    type DepositPerContract = DepositPerContract;
+    type DepositPerStorageByte = DepositPerStorageByte;
+    type DepositPerStorageItem = DepositPerStorageItem;
+    type RentFraction = RentFraction;
+    type SurchargeReward = SurchargeReward;
+    type MaxDepth = MaxDepth;
+    type MaxValueSize = MaxValueSize;
+    type WeightPrice = ();
+    type WeightInfo = ();
+    type ChainExtension = ();
+    type DeletionQueueDepth = DeletionQueueDepth;
+    type DeletionWeightLimit = DeletionWeightLimit;
+    type MaxCodeSize = MaxCodeSize;
+}
+
+parameter_types! {
+    pub const AttributeDepositBase: u128 = 10;
+    pub const DepositPerByte: u128 = 1;
+    pub const KeyLimit: u32 = 32;
+    pub const ValueLimit: u32 = 256;
+}
+
+impl pallet_erc1155::Config for Test {
+    type Event = Event;
+    type TokenId = u64;
+    type TaoId = u64;
+    type Currency = Balances;
+    type AttributeDepositBase = AttributeDepositBase;
+    type DepositPerByte = DepositPerByte;
+    type KeyLimit = KeyLimit;
+    type ValueLimit = ValueLimit;
+    type WeightInfo = ();
+}
+
+parameter_type_with_key! {
+    pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+        Zero::zero()
+    };
+}
+
+impl orml_tokens::Config for Test {
+    type Event = Event;
+    type Balance = Balance;
+    type Amount = Amount;
+    type CurrencyId = CurrencyId;
+    type WeightInfo = ();
+    type ExistentialDeposits = ExistentialDeposits;
+    type OnDust = ();
+}
+
+parameter_types! {
+    pub const CurrencyTokenModuleId: ModuleId = ModuleId(*b"sgc/curr");
+    pub const StringLimit: u32 = 16;
+    pub const DexModuleId: ModuleId = ModuleId(*b"sgc/dexm");
+    pub const DexMaxFee: Permill = Permill::from_percent(3);
+    pub const TreasuryAccount: AccountId32 = AccountId32::new([100u8; 32]);
+}
+
+impl pallet_currency_token::Config for Test {
+    type Event = Event;
+    type ModuleId = CurrencyTokenModuleId;
+    type Currency = Tokens;
+    type RegistryOrigin = EnsureRoot<AccountId32>;
+    type StringLimit = StringLimit;
+    type WeightInfo = ();
+}
+
+impl pallet_dex::Config for Test {
+    type Event = Event;
+    type ModuleId = DexModuleId;
+    type GovernanceOrigin = EnsureRoot<AccountId32>;
+    type MaxFee = DexMaxFee;
+    type TreasuryAccount = TreasuryAccount;
+    type WeightInfo = ();
+}
+
+/// Maps an address to the account of its 20 bytes followed by zeros, so tests can name both.
+pub struct PaddedAddressMapping;
+
+impl AddressMapping<AccountId32> for PaddedAddressMapping {
+    fn into_account_id(address: H160) -> AccountId32 {
+        let mut bytes = [0u8; 32];
+        bytes[..20].copy_from_slice(address.as_bytes());
+        AccountId32::new(bytes)
+    }
+}
+
+parameter_types! {
+    pub const ChainId: u64 = 42;
+}
+
+impl pallet_evm::Config for Test {
+    type FeeCalculator = ();
+    type GasWeightMapping = ();
+    type CallOrigin = EnsureAddressRoot<AccountId32>;
+    type WithdrawOrigin = EnsureAddressNever<AccountId32>;
+    type AddressMapping = PaddedAddressMapping;
+    type Currency = Balances;
+    type Event = Event;
+    type Runner = pallet_evm::runner::stack::Runner<Self>;
+    type Precompiles = evm_precompile::SgcPrecompiles<Test>;
+    type ChainId = ChainId;
+}
+
+/// Only ERC-1155 calls may be dispatched by EVM contracts.
+pub struct TokenCalls;
+
+impl Filter<Call> for TokenCalls {
+    fn filter(call: &Call) -> bool {
+        matches!(call, Call::Token(_))
+    }
+}
+
+parameter_types! {
+    pub const MaxBridgeDepth: u32 = 2;
+}
+
+impl evm_precompile::Config for Test {
+    type Call = Call;
+    type CallFilter = TokenCalls;
+    type MaxBridgeDepth = MaxBridgeDepth;
+}
+
+pub const ALICE: H160 = H160([1u8; 20]);
+pub const BOB: H160 = H160([2u8; 20]);
+pub const DOT: CurrencyId = CurrencyId::Token(TokenSymbol::DOT);
+
+/// The account of `address`.
+pub fn account(address: H160) -> AccountId32 {
+    PaddedAddressMapping::into_account_id(address)
+}
+
+/// A call from `caller` to the precompile at `address`.
+pub fn context(address: H160, caller: H160) -> Context {
+    Context { address, caller, apparent_value: Default::default() }
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(account(ALICE), 1_000_000_000_000), (account(BOB), 1_000_000_000_000)],
+    }
+        .assimilate_storage(&mut t)
+        .unwrap();
+    orml_tokens::GenesisConfig::<Test> {
+        endowed_accounts: vec![(account(ALICE), DOT, 1_000_000_000_000), (account(BOB), DOT, 1_000_000_000_000)],
+    }
+        .assimilate_storage(&mut t)
+        .unwrap();
+    GenesisBuild::<Test>::assimilate_storage(
+        &pallet_currency_token::GenesisConfig {
+            // tao 0, so taos created in tests start at 1
+            currency_tao: Some(vec![]),
+            currencies: vec![DOT],
+            assets: vec![],
+        },
+        &mut t,
+    )
+        .unwrap();
+    let mut ext: sp_io::TestExternalities = t.into();
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,238 @@
+use crate::{
+    mock::*,
+    abi::EvmDataWriter,
+    ContractsPrecompile, DexPrecompile, DispatchPrecompile, Erc1155Precompile, Erc20Precompile,
+    erc20_address, erc20_currency_id,
+};
+use codec::Encode;
+use evm::{ExitError, ExitSucceed};
+use fp_evm::Precompile;
+use frame_support::{assert_ok, traits::Get};
+use orml_traits::MultiCurrency;
+use primitives::{Balance, CurrencyId, TokenSymbol, bridge};
+use sp_core::{H160, U256};
+use sp_runtime::Permill;
+
+const ERC20: H160 = H160([0xee; 20]);
+const ERC1155: H160 = H160([0xef; 20]);
+const DEX: H160 = H160([0xf0; 20]);
+const DISPATCH: H160 = H160([0xf1; 20]);
+const CONTRACTS: H160 = H160([0xf2; 20]);
+
+type PrecompileResult = Result<(ExitSucceed, Vec<u8>, u64), ExitError>;
+
+fn call_data(selector: u32, args: EvmDataWriter) -> Vec<u8> {
+    let mut data = selector.to_be_bytes().to_vec();
+    data.extend(args.build());
+    data
+}
+
+fn reverted(result: PrecompileResult, reason: &str) {
+    assert_eq!(result.map(|_| ()), Err(ExitError::Other(reason.to_string().into())));
+}
+
+fn erc20(caller: H160, data: Vec<u8>) -> PrecompileResult {
+    Erc20Precompile::<Test>::execute(DOT, &data, None, &context(ERC20, caller))
+}
+
+fn erc1155(caller: H160, data: Vec<u8>) -> PrecompileResult {
+    Erc1155Precompile::<Test>::execute(&data, None, &context(ERC1155, caller))
+}
+
+fn dex(caller: H160, data: Vec<u8>) -> PrecompileResult {
+    DexPrecompile::<Test>::execute(&data, None, &context(DEX, caller))
+}
+
+fn dot(address: H160) -> Balance {
+    <Tokens as MultiCurrency<_>>::free_balance(DOT, &account(address))
+}
+
+#[test]
+fn malformed_input_reverts() {
+    new_test_ext().execute_with(|| {
+        reverted(erc1155(ALICE, vec![0xf2, 0x42]), "input too short to contain a selector");
+        reverted(erc1155(ALICE, vec![0, 0, 0, 0]), "unknown selector");
+        reverted(erc20(ALICE, vec![0, 0, 0, 0]), "unknown selector");
+
+        // balanceOf(address,uint256) without its id
+        reverted(erc1155(ALICE, call_data(0x00fdd58e, EvmDataWriter::new().write_address(BOB))), "input too short");
+
+        // an address word with its leading 12 bytes set
+        let dirty = EvmDataWriter::new().write_u256(U256::max_value()).write_u256(U256::zero());
+        reverted(erc1155(ALICE, call_data(0x00fdd58e, dirty)), "invalid address");
+
+        // an id of more than 128 bits
+        let wide = EvmDataWriter::new().write_address(BOB).write_u256(U256::one() << 128);
+        reverted(erc1155(ALICE, call_data(0x00fdd58e, wide)), "invalid id");
+
+        // transfer(address,uint256) of more than a balance
+        let too_much = EvmDataWriter::new().write_address(BOB).write_u256(U256::from(u128::MAX) + 1);
+        reverted(erc20(ALICE, call_data(0xa9059cbb, too_much)), "value does not fit in a balance");
+    })
+}
+
+#[test]
+fn erc20_addresses_are_reserved() {
+    let currency_ids = [
+        DOT,
+        CurrencyId::Token(TokenSymbol::SGC),
+        CurrencyId::DexShare(u32::MAX, u64::MAX),
+        CurrencyId::Erc1155(u64::MAX, 1),
+        CurrencyId::ForeignAsset(7),
+        CurrencyId::Registered(u32::MAX),
+    ];
+    for currency_id in currency_ids.iter() {
+        let address = erc20_address(*currency_id);
+        assert_eq!(&address.as_bytes()[..3], &[0xff; 3]);
+        assert_eq!(erc20_currency_id(address), Some(*currency_id));
+    }
+
+    // raw `CurrencyId` bytes, as the addresses were before, are no longer currencies
+    let dot: [u8; 32] = DOT.into();
+    assert_eq!(erc20_currency_id(H160::from_slice(&dot[12..])), None);
+    assert_eq!(erc20_currency_id(H160::zero()), None);
+    assert_eq!(erc20_currency_id(H160::from_low_u64_be(0x0401)), None);
+    assert_eq!(erc20_currency_id(ALICE), None);
+
+    // the prefix with an unknown tag or non-canonical bytes
+    let mut address = erc20_address(CurrencyId::ForeignAsset(7)).0;
+    address[3] = 0xff;
+    assert_eq!(erc20_currency_id(H160(address)), None);
+    let mut address = erc20_address(CurrencyId::ForeignAsset(7)).0;
+    address[4] = 1;
+    assert_eq!(erc20_currency_id(H160(address)), None);
+}
+
+#[test]
+fn erc20_transfer_from_spends_allowance() {
+    new_test_ext().execute_with(|| {
+        // approve(address,uint256)
+        assert_ok!(erc20(ALICE, call_data(0x095ea7b3, EvmDataWriter::new().write_address(BOB).write_balance(100))));
+
+        // allowance(address,address)
+        let (_, output, _) = erc20(BOB, call_data(0xdd62ed3e, EvmDataWriter::new().write_address(ALICE).write_address(BOB))).unwrap();
+        assert_eq!(output, EvmDataWriter::new().write_balance(100).build());
+
+        // transferFrom(address,address,uint256)
+        let transfer_from = |amount| EvmDataWriter::new().write_address(ALICE).write_address(BOB).write_balance(amount);
+        assert_ok!(erc20(BOB, call_data(0x23b872dd, transfer_from(60))));
+        assert_eq!(dot(BOB), 1_000_000_000_060);
+        assert_eq!(CurrencyToken::allowance(DOT, &account(ALICE), &account(BOB)), 40);
+
+        reverted(erc20(BOB, call_data(0x23b872dd, transfer_from(50))), "insufficient allowance");
+
+        // a failed transfer is rolled back with the allowance it spent
+        CurrencyToken::set_allowance(DOT, &account(ALICE), &account(BOB), u128::MAX);
+        reverted(erc20(BOB, call_data(0x23b872dd, transfer_from(2_000_000_000_000))), "BalanceTooLow");
+        assert_eq!(CurrencyToken::allowance(DOT, &account(ALICE), &account(BOB)), u128::MAX);
+
+        // spending your own balance takes no allowance
+        assert_ok!(erc20(ALICE, call_data(0x23b872dd, EvmDataWriter::new().write_address(ALICE).write_address(BOB).write_balance(1))));
+        assert_eq!(CurrencyToken::allowance(DOT, &account(ALICE), &account(ALICE)), 0);
+    })
+}
+
+#[test]
+fn erc1155_safe_transfer_from_needs_approval() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Token::create_tao(Origin::signed(account(ALICE)), vec![]));
+        let tao_id = Token::next_tao_id() - 1;
+        assert_ok!(Token::mint(Origin::signed(account(ALICE)), account(ALICE), tao_id, 1, 100));
+        let id = Erc1155Precompile::<Test>::encode_id(tao_id, 1);
+
+        // safeTransferFrom(address,address,uint256,uint256,bytes)
+        let transfer = || call_data(0xf242432a, EvmDataWriter::new()
+            .write_address(ALICE).write_address(BOB).write_u256(id).write_balance(10).write_bytes(&[]));
+
+        reverted(erc1155(BOB, transfer()), "caller is not owner nor approved");
+        assert_eq!(Token::balance_of(&account(ALICE), tao_id, 1), 100);
+
+        // setApprovalForAll(address,bool)
+        assert_ok!(erc1155(ALICE, call_data(0xa22cb465, EvmDataWriter::new().write_address(BOB).write_bool(true))));
+        assert_ok!(erc1155(BOB, transfer()));
+        assert_eq!(Token::balance_of(&account(BOB), tao_id, 1), 10);
+
+        assert_ok!(erc1155(ALICE, call_data(0xa22cb465, EvmDataWriter::new().write_address(BOB).write_bool(false))));
+        reverted(erc1155(BOB, transfer()), "caller is not owner nor approved");
+    })
+}
+
+#[test]
+fn dex_swaps_revert_past_deadline_or_slippage() {
+    new_test_ext().execute_with(|| {
+        let alice = account(ALICE);
+        assert_ok!(Token::create_tao(Origin::signed(alice.clone()), vec![]));
+        let token_tao = Token::next_tao_id() - 1;
+        assert_ok!(Token::mint(Origin::signed(alice.clone()), alice.clone(), token_tao, 1, 1_000));
+        assert_ok!(Dex::create_exchange(Origin::signed(alice.clone()), DOT, token_tao, Permill::from_parts(5_000)));
+        assert_ok!(CurrencyToken::wrap(Origin::signed(alice.clone()), DOT, 100_000_000_000));
+        assert_ok!(Dex::add_liquidity(Origin::signed(alice.clone()), 0, alice, vec![1], vec![500], vec![10_000_000_000], None));
+        assert_ok!(CurrencyToken::wrap(Origin::signed(account(BOB)), DOT, 100_000_000_000));
+
+        let price = Dex::get_buy_prices(0, vec![1], vec![10]).unwrap()[0];
+        // buyTokens(uint32,uint64[],uint256[],uint256[],address,uint32)
+        let buy = |max_currency, deadline: u32| call_data(0x25c3d9c8, EvmDataWriter::new()
+            .write_u256(U256::zero())
+            .write_u256_array(&[U256::one()])
+            .write_balance_array(&[10])
+            .write_balance_array(&[max_currency])
+            .write_address(BOB)
+            .write_u256(deadline.into()));
+
+        System::set_block_number(5);
+        reverted(dex(BOB, buy(price, 4)), "DeadlineExpired");
+        reverted(dex(BOB, buy(price - 1, 5)), "SlippageExceeded(0)");
+        assert_eq!(Token::balance_of(&account(BOB), token_tao, 1), 0);
+
+        assert_ok!(dex(BOB, buy(price, 5)));
+        assert_eq!(Token::balance_of(&account(BOB), token_tao, 1), 10);
+        // 0 is no deadline
+        assert_ok!(dex(BOB, buy(u128::MAX, 0)));
+        assert_eq!(Token::balance_of(&account(BOB), token_tao, 1), 20);
+    })
+}
+
+#[test]
+fn dispatch_rejects_filtered_calls_and_rolls_back_failed_ones() {
+    new_test_ext().execute_with(|| {
+        let dispatch = |call: Call| DispatchPrecompile::<Test>::execute(&call.encode(), None, &context(DISPATCH, ALICE));
+
+        let transfer = Call::Balances(pallet_balances::Call::transfer(account(BOB), 10));
+        reverted(dispatch(transfer), "call not allowed");
+
+        assert_ok!(Token::create_tao(Origin::signed(account(ALICE)), vec![]));
+        let tao_id = Token::next_tao_id() - 1;
+        assert_ok!(Token::batch_mint(Origin::signed(account(ALICE)), account(ALICE), tao_id, vec![1, 2], vec![100, 5]));
+
+        // the second transfer fails after the first was written
+        let transfer = Call::Token(pallet_erc1155::Call::batch_transfer_from(
+            account(ALICE), account(BOB), tao_id, vec![1, 2], vec![10, 10],
+        ));
+        reverted(dispatch(transfer), "NumOverflow");
+        assert_eq!(Token::balance_of(&account(ALICE), tao_id, 1), 100);
+        assert_eq!(Token::balance_of(&account(BOB), tao_id, 1), 0);
+
+        let transfer = Call::Token(pallet_erc1155::Call::transfer_from(account(ALICE), account(BOB), tao_id, 1, 10));
+        assert_ok!(dispatch(transfer));
+        assert_eq!(Token::balance_of(&account(BOB), tao_id, 1), 10);
+    })
+}
+
+#[test]
+fn contracts_calls_are_limited_by_bridge_depth() {
+    new_test_ext().execute_with(|| {
+        // call(bytes32,bytes)
+        let data = call_data(0x3ae7af08, EvmDataWriter::new()
+            .write_u256(U256::from_big_endian(account(BOB).as_ref()))
+            .write_bytes(&[]));
+        let call = || ContractsPrecompile::<Test>::execute(&data, Some(1_000_000), &context(CONTRACTS, ALICE));
+
+        // one bridged call is nested, so one more is allowed, failing only as BOB is no contract
+        let result = bridge::enter(MaxBridgeDepth::get(), call).unwrap();
+        assert!(result.is_err());
+        assert_ne!(result.map(|_| ()), Err(ExitError::Other(bridge::DEPTH_EXCEEDED.into())));
+
+        let result = bridge::enter(MaxBridgeDepth::get(), || bridge::enter(MaxBridgeDepth::get(), call)).unwrap().unwrap();
+        reverted(result, bridge::DEPTH_EXCEEDED);
+    })
+}
@@ -0,0 +1,252 @@
+//! `CurrencyId` of the SGC chain and its encodings.
+//!
+//! Shared by the runtime, through `sgc-primitives`, and by ink! contracts, through `sgc-ink-env`,
+//! so it depends on nothing but `codec`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use core::convert::{Into, TryFrom, TryInto};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TokenSymbol {
+	SGC = 0,
+	DOT = 1,
+	ACA = 2,
+    AUSD = 3,
+}
+
+impl TryFrom<u8> for TokenSymbol {
+    type Error = ();
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(TokenSymbol::SGC),
+            1 => Ok(TokenSymbol::DOT),
+            2 => Ok(TokenSymbol::ACA),
+            3 => Ok(TokenSymbol::AUSD),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Identifier of a dex exchange.
+pub type ExchangeId = u32;
+
+/// Identifier of an ERC-1155 tao.
+pub type TaoId = u64;
+
+/// Identifier of an ERC-1155 token within a tao.
+pub type TokenId = u64;
+
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum CurrencyId {
+    Token(TokenSymbol),
+    /// The liquidity share of a token in a dex exchange.
+    DexShare(ExchangeId, TokenId),
+    /// An ERC-1155 token.
+    Erc1155(TaoId, TokenId),
+    /// An asset bridged from another chain.
+    ForeignAsset(u32),
+    /// An asset registered on-chain at runtime.
+    Registered(u32),
+}
+
+/// The tags of `CurrencyId` variants, in byte 12 of the `[u8; 32]` form
+/// (byte 3 of the ERC-20 precompile address) and in the top byte of the `u64` form.
+const TOKEN_TAG: u8 = 0;
+const DEX_SHARE_TAG: u8 = 1;
+const ERC1155_TAG: u8 = 2;
+const FOREIGN_ASSET_TAG: u8 = 3;
+const REGISTERED_TAG: u8 = 4;
+
+impl CurrencyId {
+    pub fn is_token_currency_id(&self) -> bool {
+        matches!(self, CurrencyId::Token(_))
+    }
+
+    pub fn is_dex_share_currency_id(&self) -> bool {
+        matches!(self, CurrencyId::DexShare(_, _))
+    }
+
+    pub fn is_erc1155_currency_id(&self) -> bool {
+        matches!(self, CurrencyId::Erc1155(_, _))
+    }
+
+    pub fn is_foreign_asset_currency_id(&self) -> bool {
+        matches!(self, CurrencyId::ForeignAsset(_))
+    }
+
+    pub fn is_registered_currency_id(&self) -> bool {
+        matches!(self, CurrencyId::Registered(_))
+    }
+}
+
+impl TryFrom<Vec<u8>> for CurrencyId {
+    type Error = ();
+    fn try_from(v: Vec<u8>) -> Result<CurrencyId, ()> {
+        match v.as_slice() {
+            b"SGC" => Ok(CurrencyId::Token(TokenSymbol::SGC)),
+            b"DOT" => Ok(CurrencyId::Token(TokenSymbol::DOT)),
+            b"ACA" => Ok(CurrencyId::Token(TokenSymbol::ACA)),
+            b"AUSD" => Ok(CurrencyId::Token(TokenSymbol::AUSD)),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Note the pre-deployed ERC20 contracts depend on `CurrencyId` implementation,
+/// and need to be updated if any change.
+///
+/// Layout, big-endian, bytes `0..12` are always zero:
+/// - `Token`: byte 30 is the symbol, every other byte is zero
+/// - `DexShare`: byte 12 is `1`, bytes `20..24` the exchange id, bytes `24..32` the token id
+/// - `Erc1155`: byte 12 is `2`, bytes `16..24` the tao id, bytes `24..32` the token id
+/// - `ForeignAsset`: byte 12 is `3`, bytes `28..32` the asset id
+/// - `Registered`: byte 12 is `4`, bytes `28..32` the asset id
+///
+/// All other bytes must be zero, so every id has exactly one encoding.
+impl TryFrom<[u8; 32]> for CurrencyId {
+    type Error = ();
+
+    fn try_from(v: [u8; 32]) -> Result<Self, Self::Error> {
+        if !v.starts_with(&[0u8; 12][..]) {
+            return Err(());
+        }
+
+        let is_zero = |bytes: &[u8]| bytes.iter().all(|b| *b == 0);
+
+        match v[12] {
+            TOKEN_TAG => {
+                if is_zero(&v[13..30]) && v[31] == 0 {
+                    return v[30].try_into().map(CurrencyId::Token);
+                }
+            }
+            DEX_SHARE_TAG => {
+                if is_zero(&v[13..20]) {
+                    let exchange_id = u32::from_be_bytes(v[20..24].try_into().map_err(|_| ())?);
+                    let token_id = u64::from_be_bytes(v[24..32].try_into().map_err(|_| ())?);
+                    return Ok(CurrencyId::DexShare(exchange_id, token_id));
+                }
+            }
+            ERC1155_TAG => {
+                if is_zero(&v[13..16]) {
+                    let tao_id = u64::from_be_bytes(v[16..24].try_into().map_err(|_| ())?);
+                    let token_id = u64::from_be_bytes(v[24..32].try_into().map_err(|_| ())?);
+                    return Ok(CurrencyId::Erc1155(tao_id, token_id));
+                }
+            }
+            FOREIGN_ASSET_TAG => {
+                if is_zero(&v[13..28]) {
+                    let asset_id = u32::from_be_bytes(v[28..32].try_into().map_err(|_| ())?);
+                    return Ok(CurrencyId::ForeignAsset(asset_id));
+                }
+            }
+            REGISTERED_TAG => {
+                if is_zero(&v[13..28]) {
+                    let asset_id = u32::from_be_bytes(v[28..32].try_into().map_err(|_| ())?);
+                    return Ok(CurrencyId::Registered(asset_id));
+                }
+            }
+            _ => {}
+        }
+
+        Err(())
+    }
+}
+
+/// Note the pre-deployed ERC20 contracts depend on `CurrencyId` implementation,
+/// and need to be updated if any change.
+impl Into<[u8; 32]> for CurrencyId {
+    fn into(self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        match self {
+            CurrencyId::Token(token) => {
+                bytes[30] = token as u8;
+            }
+            CurrencyId::DexShare(exchange_id, token_id) => {
+                bytes[12] = DEX_SHARE_TAG;
+                bytes[20..24].copy_from_slice(&exchange_id.to_be_bytes());
+                bytes[24..32].copy_from_slice(&token_id.to_be_bytes());
+            }
+            CurrencyId::Erc1155(tao_id, token_id) => {
+                bytes[12] = ERC1155_TAG;
+                bytes[16..24].copy_from_slice(&tao_id.to_be_bytes());
+                bytes[24..32].copy_from_slice(&token_id.to_be_bytes());
+            }
+            CurrencyId::ForeignAsset(asset_id) => {
+                bytes[12] = FOREIGN_ASSET_TAG;
+                bytes[28..32].copy_from_slice(&asset_id.to_be_bytes());
+            }
+            CurrencyId::Registered(asset_id) => {
+                bytes[12] = REGISTERED_TAG;
+                bytes[28..32].copy_from_slice(&asset_id.to_be_bytes());
+            }
+        }
+        bytes
+    }
+}
+
+/// The `u64` form, used as the ERC-1155 token id of a currency.
+///
+/// The top byte is the variant tag and the rest is split as:
+/// - `Token`: the symbol in the lowest byte
+/// - `DexShare`: the exchange id in bits `32..56`, the token id in bits `0..32`
+/// - `Erc1155`: the tao id in bits `32..56`, the token id in bits `0..32`
+/// - `ForeignAsset`: the asset id in bits `0..32`
+/// - `Registered`: the asset id in bits `0..32`
+///
+/// Ids whose fields do not fit have no `u64` form.
+impl TryFrom<CurrencyId> for u64 {
+    type Error = ();
+
+    fn try_from(id: CurrencyId) -> Result<Self, Self::Error> {
+        let tagged = |tag: u8, high: u64, low: u64| -> Result<u64, ()> {
+            if high >= 1 << 24 || low >= 1 << 32 {
+                return Err(());
+            }
+            Ok((tag as u64) << 56 | high << 32 | low)
+        };
+
+        match id {
+            CurrencyId::Token(token) => Ok(token as u64),
+            CurrencyId::DexShare(exchange_id, token_id) => tagged(DEX_SHARE_TAG, exchange_id as u64, token_id),
+            CurrencyId::Erc1155(tao_id, token_id) => tagged(ERC1155_TAG, tao_id, token_id),
+            CurrencyId::ForeignAsset(asset_id) => tagged(FOREIGN_ASSET_TAG, 0, asset_id as u64),
+            CurrencyId::Registered(asset_id) => tagged(REGISTERED_TAG, 0, asset_id as u64),
+        }
+    }
+}
+
+impl TryFrom<u64> for CurrencyId {
+    type Error = ();
+
+    fn try_from(v: u64) -> Result<Self, Self::Error> {
+        let tag = (v >> 56) as u8;
+        let high = (v >> 32) & 0xff_ffff;
+        let low = v & 0xffff_ffff;
+
+        match tag {
+            TOKEN_TAG if v <= u8::max_value() as u64 => (v as u8).try_into().map(CurrencyId::Token),
+            DEX_SHARE_TAG => {
+                let exchange_id = high.try_into().map_err(|_| ())?;
+                Ok(CurrencyId::DexShare(exchange_id, low))
+            }
+            ERC1155_TAG => Ok(CurrencyId::Erc1155(high, low)),
+            FOREIGN_ASSET_TAG if high == 0 => Ok(CurrencyId::ForeignAsset(low as u32)),
+            REGISTERED_TAG if high == 0 => Ok(CurrencyId::Registered(low as u32)),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use super::*;
+use proptest::prelude::*;
+use core::convert::TryFrom;
+
+fn token_symbol() -> impl Strategy<Value = TokenSymbol> {
+    prop_oneof![
+        Just(TokenSymbol::SGC),
+        Just(TokenSymbol::DOT),
+        Just(TokenSymbol::ACA),
+        Just(TokenSymbol::AUSD),
+    ]
+}
+
+fn currency_id() -> impl Strategy<Value = CurrencyId> {
+    prop_oneof![
+        token_symbol().prop_map(CurrencyId::Token),
+        (any::<ExchangeId>(), any::<TokenId>()).prop_map(|(e, t)| CurrencyId::DexShare(e, t)),
+        (any::<TaoId>(), any::<TokenId>()).prop_map(|(tao, t)| CurrencyId::Erc1155(tao, t)),
+        any::<u32>().prop_map(CurrencyId::ForeignAsset),
+        any::<u32>().prop_map(CurrencyId::Registered),
+    ]
+}
+
+// Ids whose fields fit in the `u64` form
+fn small_currency_id() -> impl Strategy<Value = CurrencyId> {
+    prop_oneof![
+        token_symbol().prop_map(CurrencyId::Token),
+        (0..1u32 << 24, 0..1u64 << 32).prop_map(|(e, t)| CurrencyId::DexShare(e, t)),
+        (0..1u64 << 24, 0..1u64 << 32).prop_map(|(tao, t)| CurrencyId::Erc1155(tao, t)),
+        any::<u32>().prop_map(CurrencyId::ForeignAsset),
+        any::<u32>().prop_map(CurrencyId::Registered),
+    ]
+}
+
+#[test]
+fn token_encoding_is_unchanged() {
+    let bytes: [u8; 32] = CurrencyId::Token(TokenSymbol::DOT).into();
+    let mut expected = [0u8; 32];
+    expected[30] = 1;
+    assert_eq!(bytes, expected);
+
+    assert_eq!(u64::try_from(CurrencyId::Token(TokenSymbol::AUSD)), Ok(3));
+}
+
+#[test]
+fn registered_and_foreign_assets_do_not_collide() {
+    let foreign: [u8; 32] = CurrencyId::ForeignAsset(7).into();
+    let registered: [u8; 32] = CurrencyId::Registered(7).into();
+    assert_ne!(foreign, registered);
+
+    assert_ne!(u64::try_from(CurrencyId::ForeignAsset(7)), u64::try_from(CurrencyId::Registered(7)));
+}
+
+#[test]
+fn u64_rejects_oversized_fields() {
+    assert!(u64::try_from(CurrencyId::DexShare(1 << 24, 0)).is_err());
+    assert!(u64::try_from(CurrencyId::Erc1155(0, 1 << 32)).is_err());
+}
+
+/// Bytes close to an encoded `CurrencyId`, so most decode or fail on a single byte.
+fn near_valid_bytes() -> impl Strategy<Value = [u8; 32]> {
+    prop_oneof![
+        // an encoding with one byte after the zero prefix replaced
+        (currency_id(), 12..32usize, any::<u8>()).prop_map(|(id, i, byte)| {
+            let mut bytes: [u8; 32] = id.into();
+            bytes[i] = byte;
+            bytes
+        }),
+        // the zero prefix, a tag, one past the last, and a random tail
+        (0u8..=5, any::<[u8; 19]>()).prop_map(|(tag, tail)| {
+            let mut bytes = [0u8; 32];
+            bytes[12] = tag;
+            bytes[13..].copy_from_slice(&tail);
+            bytes
+        }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn bytes_round_trip(id in currency_id()) {
+        let bytes: [u8; 32] = id.into();
+        prop_assert!(bytes.starts_with(&[0u8; 12]));
+        prop_assert_eq!(CurrencyId::try_from(bytes), Ok(id));
+    }
+
+    #[test]
+    fn bytes_are_collision_free(a in currency_id(), b in currency_id()) {
+        let a_bytes: [u8; 32] = a.into();
+        let b_bytes: [u8; 32] = b.into();
+        prop_assert_eq!(a == b, a_bytes == b_bytes);
+    }
+
+    #[test]
+    fn bytes_decode_is_canonical(bytes in near_valid_bytes()) {
+        if let Ok(id) = CurrencyId::try_from(bytes) {
+            let encoded: [u8; 32] = id.into();
+            prop_assert_eq!(encoded, bytes);
+        }
+    }
+
+    #[test]
+    fn u64_round_trip(id in small_currency_id()) {
+        let n = u64::try_from(id).unwrap();
+        prop_assert_eq!(CurrencyId::try_from(n), Ok(id));
+    }
+
+    #[test]
+    fn u64_is_collision_free(a in small_currency_id(), b in small_currency_id()) {
+        let a_n = u64::try_from(a).unwrap();
+        let b_n = u64::try_from(b).unwrap();
+        prop_assert_eq!(a == b, a_n == b_n);
+    }
+
+    #[test]
+    fn u64_decode_is_canonical(n in any::<u64>()) {
+        if let Ok(id) = CurrencyId::try_from(n) {
+            prop_assert_eq!(u64::try_from(id), Ok(n));
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Depth of calls bridged between ink! and EVM contracts.
+//!
+//! A call from an ink! contract into the EVM, or from an EVM contract into `pallet_contracts`,
+//! starts a fresh call stack in the other VM, so neither VM's own depth limit bounds a chain of
+//! bridged calls. Both bridges run their call through [`enter`], which counts the bridged calls
+//! of the current stack against a shared limit.
+
+environmental::environmental!(depth: u32);
+
+/// The message of the error a bridge fails with when [`enter`] refuses its call.
+pub const DEPTH_EXCEEDED: &str = "BridgeDepthExceeded";
+
+/// The number of bridged calls the current call is nested in.
+pub fn current_depth() -> u32 {
+    depth::with(|depth| *depth).unwrap_or(0)
+}
+
+/// Run `f` one bridged call deeper, or return `None` if `max_depth` bridged calls are already nested.
+pub fn enter<R>(max_depth: u32, f: impl FnOnce() -> R) -> Option<R> {
+    let current = current_depth();
+    if current >= max_depth {
+        return None;
+    }
+
+    let mut next = current + 1;
+    Some(depth::using(&mut next, f))
+}
@@ -0,0 +1,19 @@
+//! Randomness requested in one block and taken in a later one.
+//!
+//! Randomness known in the block it is used in is known to the block author too. A
+//! [`RandomnessRequests`] source instead resolves each request from what happens after the
+//! request, see `pallet_commit_reveal`.
+
+use sp_runtime::DispatchError;
+
+/// The id of a randomness request.
+pub type RequestId = u64;
+
+pub trait RandomnessRequests<AccountId, Hash> {
+    /// Request randomness for `who`, unknown to anyone until it resolves.
+    fn request(who: &AccountId) -> Result<RequestId, DispatchError>;
+
+    /// Take the randomness of a request of `who`, or `None` while it has not resolved yet.
+    /// The request is removed once taken.
+    fn take(who: &AccountId, request_id: RequestId) -> Result<Option<Hash>, DispatchError>;
+}
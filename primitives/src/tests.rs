@@ -0,0 +1,19 @@
+use super::*;
+
+#[test]
+fn bridge_depth_is_limited() {
+    assert_eq!(bridge::current_depth(), 0);
+
+    let nested = bridge::enter(2, || {
+        assert_eq!(bridge::current_depth(), 1);
+        bridge::enter(2, || {
+            assert_eq!(bridge::current_depth(), 2);
+            bridge::enter(2, || ())
+        })
+    });
+    assert_eq!(nested, Some(Some(None)));
+
+    // the depth is restored once the bridged call returns
+    assert_eq!(bridge::current_depth(), 0);
+    assert_eq!(bridge::enter(0, || ()), None);
+}
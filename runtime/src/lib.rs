@@ -16,7 +16,7 @@ use sp_runtime::{
     transaction_validity::{TransactionValidity, TransactionSource},
 };
 use sp_runtime::traits::{
-    BlakeTwo256, Block as BlockT, NumberFor, AccountIdLookup, Zero,
+    AccountIdConversion, BlakeTwo256, Block as BlockT, NumberFor, AccountIdLookup, Zero,
 };
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -35,7 +35,8 @@ pub use pallet_balances::Call as BalancesCall;
 pub use sp_runtime::{Permill, Perbill};
 pub use frame_support::{
     construct_runtime, parameter_types, StorageValue,
-    traits::{KeyOwnerProofSystem, Randomness, FindAuthor},
+    dispatch::DispatchResult,
+    traits::{KeyOwnerProofSystem, Randomness, FindAuthor, Filter, Currency, OnUnbalanced},
     weights::{
         Weight, IdentityFee,
         constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -49,13 +50,13 @@ use frame_system::{
 };
 use pallet_evm::{
     Account as EVMAccount, FeeCalculator, HashedAddressMapping,
-    EnsureAddressTruncated, Runner,
+    EnsureAddressTruncated, Runner, GasWeightMapping,
 };
 use fp_rpc::TransactionStatus;
 use pallet_transaction_payment::CurrencyAdapter;
 use pallet_contracts::weights::WeightInfo;
 use orml_currencies::BasicCurrencyAdapter;
-use orml_traits::parameter_type_with_key;
+use orml_traits::{parameter_type_with_key, MergeAccount};
 
 /// Constant values used within the runtime.
 mod constants;
@@ -322,16 +323,34 @@ impl FeeCalculator for FixedGasPrice {
     }
 }
 
+/// The gas the EVM executes per second of block weight.
+pub const GAS_PER_SECOND: u64 = 40_000_000;
+/// The weight of one unit of gas.
+pub const WEIGHT_PER_GAS: Weight = WEIGHT_PER_SECOND / GAS_PER_SECOND;
+
+/// Converts between gas and weight at `WEIGHT_PER_GAS`.
+pub struct SgcGasWeightMapping;
+
+impl GasWeightMapping for SgcGasWeightMapping {
+    fn gas_to_weight(gas: u64) -> Weight {
+        gas.saturating_mul(WEIGHT_PER_GAS)
+    }
+
+    fn weight_to_gas(weight: Weight) -> u64 {
+        weight / WEIGHT_PER_GAS
+    }
+}
+
 parameter_types! {
     pub const ChainId: u64 = 102;
 }
 
 impl pallet_evm::Config for Runtime {
     type FeeCalculator = FixedGasPrice;
-    type GasWeightMapping = ();
+    type GasWeightMapping = SgcGasWeightMapping;
     type CallOrigin = EnsureAddressTruncated;
     type WithdrawOrigin = EnsureAddressTruncated;
-    type AddressMapping = HashedAddressMapping<BlakeTwo256>;
+    type AddressMapping = pallet_account_claim::EvmAddressMapping<Runtime>;
     type Currency = Balances;
     type Event = Event;
     type Runner = pallet_evm::runner::stack::Runner<Self>;
@@ -354,7 +373,7 @@ impl<F: FindAuthor<u32>> FindAuthor<H160> for EthereumFindAuthor<F>
 }
 
 parameter_types! {
-    pub BlockGasLimit: U256 = U256::from(u32::max_value());
+    pub BlockGasLimit: U256 = U256::from(NORMAL_DISPATCH_RATIO * MAXIMUM_BLOCK_WEIGHT / WEIGHT_PER_GAS);
 }
 
 impl pallet_ethereum::Config for Runtime {
@@ -383,7 +402,9 @@ impl pallet_scheduler::Config for Runtime {
 
 parameter_type_with_key! {
     pub ExistentialDeposits: |currency_id: CurrencyId| -> Balance {
-        Zero::zero()
+        CurrencyToken::asset_metadatas(currency_id)
+            .map(|metadata| metadata.existential_deposit)
+            .unwrap_or_else(Zero::zero)
     };
 }
 
@@ -409,10 +430,61 @@ impl orml_currencies::Config for Runtime {
     type WeightInfo = ();
 }
 
-impl precompile::Config for Runtime {}
+/// The calls EVM contracts may dispatch through the dispatch precompile.
+///
+/// ERC-1155 calls are listed one by one, each checking the caller against the tao owner or the
+/// approvals of `from`, so a call added to `pallet_erc1155` is not exposed until it is reviewed.
+pub struct EvmDispatchFilter;
+
+impl Filter<Call> for EvmDispatchFilter {
+    fn filter(call: &Call) -> bool {
+        use pallet_erc1155::Call as Erc1155Call;
+
+        match call {
+            Call::Erc1155(call) => matches!(
+                call,
+                Erc1155Call::create_tao(..)
+                    | Erc1155Call::create_token(..)
+                    | Erc1155Call::set_approval_for_all(..)
+                    | Erc1155Call::transfer_from(..)
+                    | Erc1155Call::batch_transfer_from(..)
+                    | Erc1155Call::mint(..)
+                    | Erc1155Call::batch_mint(..)
+                    | Erc1155Call::burn(..)
+                    | Erc1155Call::batch_burn(..)
+                    | Erc1155Call::set_game_server(..)
+                    | Erc1155Call::set_attribute(..)
+                    | Erc1155Call::clear_attribute(..)
+            ),
+            Call::CurrencyToken(_) | Call::Dex(_) | Call::Scheduler(_) => true,
+            _ => false,
+        }
+    }
+}
+
+parameter_types! {
+    /// Calls between ink! and EVM contracts nested deeper than this fail.
+    pub const MaxBridgeDepth: u32 = 4;
+}
+
+impl precompile::Config for Runtime {
+    type Call = Call;
+    type CallFilter = EvmDispatchFilter;
+    type MaxBridgeDepth = MaxBridgeDepth;
+}
 
 impl chain_extension::Config for Runtime {
-    type Randomness = RandomnessCollectiveFlip;
+    type Randomness = CommitReveal;
+    type RandomnessRequests = CommitReveal;
+    type EvmAddress = pallet_account_claim::EvmAddressOf<Runtime>;
+    type MaxBridgeDepth = MaxBridgeDepth;
+}
+
+parameter_types! {
+    pub const AttributeDepositBase: Balance = deposit(1, 0);
+    pub const DepositPerByte: Balance = deposit(0, 1);
+    pub const AttributeKeyLimit: u32 = 32;
+    pub const AttributeValueLimit: u32 = 256;
 }
 
 impl pallet_erc1155::Config for Runtime {
@@ -420,22 +492,92 @@ impl pallet_erc1155::Config for Runtime {
     // type TokenBalance = u128;
     type TokenId = u64;
     type TaoId = u64;
+    type Currency = Balances;
+    type AttributeDepositBase = AttributeDepositBase;
+    type DepositPerByte = DepositPerByte;
+    type KeyLimit = AttributeKeyLimit;
+    type ValueLimit = AttributeValueLimit;
+    type WeightInfo = pallet_erc1155::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
     pub const CurrencyTokenModuleId: ModuleId = ModuleId(*b"sgc/curr");
+    pub const AssetStringLimit: u32 = 32;
 	pub const DexModuleId: ModuleId = ModuleId(*b"sgc/dexm");
+    pub const DexMaxFee: Permill = Permill::from_percent(3);
+    pub const TreasuryModuleId: ModuleId = ModuleId(*b"sgc/trsy");
+    pub TreasuryAccount: AccountId = TreasuryModuleId::get().into_account();
 }
 
 impl pallet_currency_token::Config for Runtime {
     type Event = Event;
     type ModuleId = CurrencyTokenModuleId;
     type Currency = Currencies;
+    type RegistryOrigin = EnsureRoot<AccountId>;
+    type StringLimit = AssetStringLimit;
+    type WeightInfo = pallet_currency_token::weights::SubstrateWeight<Runtime>;
 }
 
 impl pallet_dex::Config for Runtime {
     type Event = Event;
     type ModuleId = DexModuleId;
+    type GovernanceOrigin = EnsureRoot<AccountId>;
+    type MaxFee = DexMaxFee;
+    type TreasuryAccount = TreasuryAccount;
+    type WeightInfo = pallet_dex::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    pub const AirdropModuleId: ModuleId = ModuleId(*b"sgc/aird");
+    pub const AirdropClaimFee: Balance = 10 * MILLICENTS;
+}
+
+impl pallet_airdrop::Config for Runtime {
+    type Event = Event;
+    type ModuleId = AirdropModuleId;
+    type Currency = Balances;
+    type ClaimFee = AirdropClaimFee;
+    type OnClaimFee = ClaimFeesToTreasury;
+}
+
+/// Credits the claim fees of airdrops to the treasury account.
+pub struct ClaimFeesToTreasury;
+
+impl OnUnbalanced<pallet_airdrop::NegativeImbalanceOf<Runtime>> for ClaimFeesToTreasury {
+    fn on_nonzero_unbalanced(amount: pallet_airdrop::NegativeImbalanceOf<Runtime>) {
+        Balances::resolve_creating(&TreasuryAccount::get(), amount);
+    }
+}
+
+/// Merges the `orml_tokens` currencies and ERC-1155 tokens of a fallback account into the account claiming it.
+pub struct MergeFallbackAccount;
+
+impl MergeAccount<AccountId> for MergeFallbackAccount {
+    fn merge_account(source: &AccountId, dest: &AccountId) -> DispatchResult {
+        Tokens::merge_account(source, dest)?;
+        Erc1155::do_merge_account(source, dest)
+    }
+}
+
+impl pallet_account_claim::Config for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type MergeAccount = MergeFallbackAccount;
+    type FallbackAddressMapping = HashedAddressMapping<BlakeTwo256>;
+}
+
+parameter_types! {
+    pub const CommitDeposit: Balance = deposit(1, 64);
+    pub const RevealPeriod: BlockNumber = 10 * MINUTES;
+}
+
+impl pallet_commit_reveal::Config for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type BlockRandomness = RandomnessCollectiveFlip;
+    type CommitDeposit = CommitDeposit;
+    type RevealPeriod = RevealPeriod;
+    type WeightInfo = pallet_commit_reveal::weights::SubstrateWeight<Runtime>;
 }
 
 construct_runtime!(
@@ -461,8 +603,11 @@ construct_runtime!(
         
         // SGC pallets
         Erc1155: pallet_erc1155::{Module, Call, Storage, Event<T>},
-        CurrencyToken: pallet_currency_token::{Module, Call, Storage, Event<T>},
-        Dex: pallet_dex::{Module, Call, Storage, Event<T>},
+        CurrencyToken: pallet_currency_token::{Module, Call, Storage, Event<T>, Config},
+        Dex: pallet_dex::{Module, Call, Storage, Event<T>, Config},
+        Airdrop: pallet_airdrop::{Module, Call, Storage, Event<T>},
+        AccountClaim: pallet_account_claim::{Module, Call, Storage, Event<T>},
+        CommitReveal: pallet_commit_reveal::{Module, Call, Storage, Event<T>},
     }
 );
 
@@ -767,6 +912,32 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_erc1155_runtime_api::Erc1155Api<Block, u64, u64> for Runtime {
+        fn attribute(tao_id: u64, token_id: Option<u64>, key: Vec<u8>) -> Option<Vec<u8>> {
+            Erc1155::attribute(tao_id, token_id, key)
+        }
+
+        fn attributes(tao_id: u64, token_id: Option<u64>) -> Vec<(Vec<u8>, Vec<u8>)> {
+            Erc1155::attributes_of(tao_id, token_id)
+        }
+    }
+
+    impl pallet_currency_token_runtime_api::CurrencyTokenApi<
+        Block,
+        CurrencyId,
+        pallet_currency_token::AssetMetadata<Balance>,
+        u64,
+        u64,
+    > for Runtime {
+        fn asset_metadata(currency_id: CurrencyId) -> Option<pallet_currency_token::AssetMetadata<Balance>> {
+            CurrencyToken::asset_metadatas(currency_id)
+        }
+
+        fn currency_token(currency_id: CurrencyId) -> Option<(u64, u64)> {
+            CurrencyToken::get_currency_token(currency_id).ok()
+        }
+    }
+
     impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
         fn query_info(
             uxt: <Block as BlockT>::Extrinsic,